@@ -7,8 +7,8 @@ use pyo3::prelude::*;
 /// A vertex (node) in a phylogenetic tree.
 ///
 /// A vertex can be one of three types:
-/// - **Root**: Has two children, no parent (check with `is_root()`)
-/// - **Internal**: Has two children and a parent, no label (check with `is_internal()`)
+/// - **Root**: Has two or more children, no parent (check with `is_root()`)
+/// - **Internal**: Has two or more children and a parent, no label (check with `is_internal()`)
 /// - **Leaf**: Has a parent and label, no children (check with `is_leaf()`)
 ///
 /// Use the `is_*` methods to determine the vertex type, then access
@@ -73,11 +73,11 @@ impl PyVertex {
         self.inner.has_branch_length()
     }
 
-    /// Returns the indices of the two children if this vertex has children
+    /// Returns the indices of the children if this vertex has children
     /// (i.e., is a root or internal vertex), otherwise `None`.
     #[getter]
-    fn children(&self) -> Option<(usize, usize)> {
-        self.inner.children()
+    fn children(&self) -> Option<Vec<usize>> {
+        self.inner.children().map(|children| children.to_vec())
     }
 
     /// Returns the index of the parent vertex if this is not the root,
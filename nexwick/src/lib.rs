@@ -4,7 +4,7 @@
 //! This crate offers configurable parser (and writer) functionality for
 //! Nexus format files and Newick strings to parse phylogenetic trees.
 //! Core functionality provided:
-//! - Nexus: Parse the taxa block and tree block of a nexus files (file ending ignored).
+//! - Nexus: Parse the taxa block (optional) and tree block of a nexus files (file ending ignored).
 //! - Newick: Parse each Newick string in a file or single Newick strings.
 //! - Tree builder: You can use the provided tree models or
 //!   provide your own TreeBuilder trait implementation.
@@ -16,19 +16,26 @@
 //!   - Both models use arena pattern and so no direct vertex references are
 //!     stored, only vertex indices.
 //!   - See [crate::model] for more details.
+//! - Network models: [GenNetwork](crate::model::GenNetwork) for phylogenetic
+//!   networks in extended Newick (eNewick) format.
 //! - Configurability:
 //!   - Eager parser (all at once) or lazy (providing an iterator)
 //!   - Burnin: number/percentage of initial trees skipped
+//!   - Thinning: every k-th tree or a target number of evenly spaced trees,
+//!     optionally only trees within a range of MCMC states
 //!   - Skip first: Since some Bayesian MCMC implementations include the start
 //!     tree in Nexus files, which is however not needed for analyzes, parser
 //!     can be configured to directly skip first tree.
 //!   - Loading full file in memory (default) and
 //!     in the future also buffered (for huge files)
+//!   - Compressed files (gzip, zstd, bzip2) decompressed transparently,
+//!     with cargo features `gzip`, `zstd`, `bzip2` (or `compression` for all)
 //!
 //! Limitations:
-//! - Only binary trees
-//! - Only leaf-labels considered
-//! - Trees always considered rooted
+//! - Trees always represented rooted; whether a tree is rooted (`[&R]`) or
+//!   unrooted (`[&U]`) is kept as [TreeMetadata](crate::model::TreeMetadata);
+//!   unrooted trees with a basal trifurcation can be rooted on parsing, see
+//!   [UnrootedTreePolicy](crate::newick::UnrootedTreePolicy)
 //!
 //! # Usage patterns
 //! Can parse files in two main ways:
@@ -0,0 +1,201 @@
+//! Provides [Alignment] for character matrices, e.g. of DNA or protein sequences.
+//!
+//! An [Alignment] is parsed from a Nexus `DATA` or `CHARACTERS` block,
+//! see [NexusParserBuilder::with_alignment()](crate::nexus::NexusParserBuilder::with_alignment).
+//! Sequences are indexed by taxon in the same order as the taxa of the file,
+//! so that the index of a taxon equals its
+//! [LabelIndex](crate::model::LabelIndex) in the [LeafLabelMap](crate::model::LeafLabelMap).
+
+use std::fmt;
+
+// =#========================================================================#=
+// DATA TYPE
+// =#========================================================================$=
+/// Type of the characters of an [Alignment], as given by
+/// `FORMAT DATATYPE=<type>` in a Nexus `DATA`/`CHARACTERS` block.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DataType {
+    /// Discrete (morphological) characters, e.g. `0`, `1`, `2` (Nexus default)
+    #[default]
+    Standard,
+    /// DNA nucleotides `A`, `C`, `G`, `T` (and IUPAC ambiguity codes)
+    Dna,
+    /// RNA nucleotides `A`, `C`, `G`, `U` (and IUPAC ambiguity codes)
+    Rna,
+    /// DNA or RNA nucleotides
+    Nucleotide,
+    /// Amino acids
+    Protein,
+}
+
+impl DataType {
+    /// Parses a Nexus data type name (case-insensitive), e.g. `DNA`;
+    /// returns `None` if the data type is unknown or not supported
+    /// (such as `CONTINUOUS`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "standard" => Some(DataType::Standard),
+            "dna" => Some(DataType::Dna),
+            "rna" => Some(DataType::Rna),
+            "nucleotide" => Some(DataType::Nucleotide),
+            "protein" => Some(DataType::Protein),
+            _ => None,
+        }
+    }
+
+    /// Returns whether symbols are case-insensitive
+    /// (and thus stored in uppercase), i.e. for all molecular data types.
+    pub fn is_molecular(&self) -> bool {
+        !matches!(self, DataType::Standard)
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DataType::Standard => "Standard",
+            DataType::Dna => "DNA",
+            DataType::Rna => "RNA",
+            DataType::Nucleotide => "Nucleotide",
+            DataType::Protein => "Protein",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// =#========================================================================#=
+// ALIGNMENT
+// =#========================================================================$=
+/// Alignment (character matrix) of equally long sequences, one per taxon.
+///
+/// Each character (site) of a sequence is a single byte symbol, e.g. `b'A'`;
+/// missing data and gaps are given by the [missing](Self::missing) and
+/// [gap](Self::gap) symbols (by default `?` and `-`).
+///
+/// # Example
+/// ```
+/// use nexwick::model::{Alignment, DataType};
+///
+/// let mut alignment = Alignment::new(DataType::Dna);
+/// alignment.add_sequence("A_platyrhynchos", b"ACGT-A".to_vec());
+/// alignment.add_sequence("A_acuta", b"ACGTTA".to_vec());
+///
+/// assert_eq!(alignment.num_taxa(), 2);
+/// assert_eq!(alignment.num_chars(), 6);
+/// assert_eq!(alignment.character(0, 4), Some(b'-'));
+/// assert_eq!(alignment.sequence_by_label("A_acuta"), Some(&b"ACGTTA"[..]));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    data_type: DataType,
+    missing: u8,
+    gap: u8,
+    /// Taxon labels, in the same order as the sequences
+    taxa: Vec<String>,
+    sequences: Vec<Vec<u8>>,
+}
+
+impl Alignment {
+    /// Creates a new empty [Alignment] of the given data type,
+    /// with missing symbol `?` and gap symbol `-`.
+    pub fn new(data_type: DataType) -> Self {
+        Self {
+            data_type,
+            missing: b'?',
+            gap: b'-',
+            taxa: Vec::new(),
+            sequences: Vec::new(),
+        }
+    }
+
+    /// Sets the symbol for missing data (default `?`).
+    pub fn with_missing(mut self, missing: u8) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    /// Sets the symbol for gaps (default `-`).
+    pub fn with_gap(mut self, gap: u8) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Adds the sequence of a taxon and returns its taxon index.
+    pub fn add_sequence(&mut self, taxon: &str, sequence: Vec<u8>) -> usize {
+        self.taxa.push(taxon.to_string());
+        self.sequences.push(sequence);
+        self.sequences.len() - 1
+    }
+
+    /// Returns the data type of the characters.
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    /// Returns the symbol for missing data.
+    pub fn missing(&self) -> u8 {
+        self.missing
+    }
+
+    /// Returns the symbol for gaps.
+    pub fn gap(&self) -> u8 {
+        self.gap
+    }
+
+    /// Returns the number of taxa (sequences).
+    pub fn num_taxa(&self) -> usize {
+        self.sequences.len()
+    }
+
+    /// Returns the number of characters (sites), i.e. the sequence length.
+    pub fn num_chars(&self) -> usize {
+        self.sequences.first().map_or(0, |sequence| sequence.len())
+    }
+
+    /// Returns the taxon labels, in the order of the sequences.
+    pub fn taxa(&self) -> &[String] {
+        &self.taxa
+    }
+
+    /// Returns the index of the taxon with the given label, if present.
+    pub fn taxon_index(&self, label: &str) -> Option<usize> {
+        self.taxa.iter().position(|taxon| taxon == label)
+    }
+
+    /// Returns the sequence of the taxon with the given index, if present.
+    pub fn sequence(&self, taxon_index: usize) -> Option<&[u8]> {
+        self.sequences
+            .get(taxon_index)
+            .map(|sequence| sequence.as_slice())
+    }
+
+    /// Returns the sequence of the taxon with the given label, if present.
+    pub fn sequence_by_label(&self, label: &str) -> Option<&[u8]> {
+        self.taxon_index(label)
+            .and_then(|taxon_index| self.sequence(taxon_index))
+    }
+
+    /// Returns the character of a taxon at a site (0-based), if present.
+    pub fn character(&self, taxon_index: usize, site: usize) -> Option<u8> {
+        self.sequence(taxon_index)
+            .and_then(|sequence| sequence.get(site).copied())
+    }
+
+    /// Returns an iterator over pairs of taxon label and sequence.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.taxa
+            .iter()
+            .zip(&self.sequences)
+            .map(|(taxon, sequence)| (taxon.as_str(), sequence.as_slice()))
+    }
+
+    /// Returns whether the symbol denotes missing data.
+    pub fn is_missing(&self, symbol: u8) -> bool {
+        symbol == self.missing
+    }
+
+    /// Returns whether the symbol denotes a gap.
+    pub fn is_gap(&self, symbol: u8) -> bool {
+        symbol == self.gap
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! Provides the [Annotations] struct, which can store parsed annotation values
 //! for vertices based on their indices. Supported values captured by
-//! [AnnotationValue] are `f64`, `i64`, and `String`.
+//! [AnnotationValue] are `f64`, `i64`, `String`, and (nested) lists thereof,
+//! e.g. HPD intervals such as `height_95%_HPD={1.2,3.4}`.
 
 use crate::model::VertexIndex;
 use std::collections::HashMap;
+use std::fmt;
 use std::string::String;
 
 // =#========================================================================#=
@@ -55,6 +57,11 @@ impl Annotations {
             .and_then(|a| a[vertex_index].clone())
     }
 
+    /// Returns an iterator over all annotation keys (in arbitrary order).
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.annotations.keys()
+    }
+
     /// Adds an annotation value for a vertex.
     ///
     /// # Arguments
@@ -77,7 +84,7 @@ impl Annotations {
 // ANNOTATION VALUE
 // =#========================================================================€=
 /// Enum to encapsulate a parsed annotation value.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AnnotationValue {
     /// For floating point values
     Float(f64),
@@ -85,6 +92,62 @@ pub enum AnnotationValue {
     Int(i64),
     /// For strings
     String(String),
+    /// For lists of values, e.g. `{1.2,3.4}` or `{{1,2},{3,4}}`
+    List(Vec<AnnotationValue>),
+}
+
+impl AnnotationValue {
+    /// Returns the values if this is a [AnnotationValue::List], otherwise `None`.
+    pub fn as_list(&self) -> Option<&[AnnotationValue]> {
+        match self {
+            AnnotationValue::List(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `f64` if this is a [AnnotationValue::Float] or
+    /// [AnnotationValue::Int], otherwise `None`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AnnotationValue::Float(v) => Some(*v),
+            AnnotationValue::Int(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AnnotationValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Debug format keeps the decimal point, e.g. "1.0", so type survives a round trip
+            AnnotationValue::Float(v) => write!(f, "{:?}", v),
+            AnnotationValue::Int(v) => write!(f, "{}", v),
+            AnnotationValue::String(v) => {
+                // Quote strings that would otherwise be split or read as a number
+                let needs_quotes = v.is_empty()
+                    || v.parse::<f64>().is_ok()
+                    || v.chars().any(|c| {
+                        c.is_whitespace()
+                            || matches!(c, ',' | ':' | '=' | '[' | ']' | '{' | '}' | '"' | '\'')
+                    });
+                if needs_quotes {
+                    write!(f, "\"{}\"", v.replace('"', "\"\""))
+                } else {
+                    write!(f, "{}", v)
+                }
+            }
+            AnnotationValue::List(values) => {
+                write!(f, "{{")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
 }
 
 impl From<f64> for AnnotationValue {
@@ -117,6 +180,12 @@ impl From<String> for AnnotationValue {
     }
 }
 
+impl<T: Into<AnnotationValue>> From<Vec<T>> for AnnotationValue {
+    fn from(v: Vec<T>) -> Self {
+        AnnotationValue::List(v.into_iter().map(Into::into).collect())
+    }
+}
+
 impl From<&str> for AnnotationValue {
     fn from(v: &str) -> Self {
         AnnotationValue::String(v.to_string())
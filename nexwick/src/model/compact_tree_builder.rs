@@ -3,6 +3,7 @@
 use crate::model::annotation::AnnotationValue;
 use crate::model::label_storage::LabelStorage;
 use crate::model::tree_builder::TreeBuilder;
+use crate::model::tree_metadata::TreeMetadata;
 use crate::model::vertex::BranchLength;
 use crate::model::{CompactTree, LabelIndex, LeafLabelMap, VertexIndex};
 
@@ -60,25 +61,25 @@ impl TreeBuilder for CompactTreeBuilder {
 
     fn add_leaf(&mut self, branch_len: Option<f64>, label: LabelIndex) -> Self::VertexIdx {
         let tree = self.current_tree.as_mut().expect("init not called");
-        tree.add_leaf(branch_len.map(BranchLength::new), label)
+        tree.add_leaf(branch_len.map(BranchLength::new_signed), label)
     }
 
     fn add_internal(
         &mut self,
-        children: (Self::VertexIdx, Self::VertexIdx),
+        children: Vec<Self::VertexIdx>,
         branch_len: Option<f64>,
     ) -> Self::VertexIdx {
         let tree = self.current_tree.as_mut().expect("init not called");
-        tree.add_internal_vertex(children, branch_len.map(BranchLength::new))
+        tree.add_internal_vertex(children, branch_len.map(BranchLength::new_signed))
     }
 
     fn add_root(
         &mut self,
-        children: (Self::VertexIdx, Self::VertexIdx),
+        children: Vec<Self::VertexIdx>,
         branch_len: Option<f64>,
     ) -> Self::VertexIdx {
         let tree = self.current_tree.as_mut().expect("init not called");
-        tree.add_root(children, branch_len.map(BranchLength::new))
+        tree.add_root(children, branch_len.map(BranchLength::new_signed))
     }
 
     fn add_annotation(&mut self, key: String, vertex_idx: Self::VertexIdx, value: AnnotationValue) {
@@ -86,6 +87,26 @@ impl TreeBuilder for CompactTreeBuilder {
         tree.add_annotation(key, vertex_idx, value)
     }
 
+    fn add_branch_annotation(
+        &mut self,
+        key: String,
+        vertex_idx: Self::VertexIdx,
+        value: AnnotationValue,
+    ) {
+        let tree = self.current_tree.as_mut().expect("init not called");
+        tree.add_branch_annotation(key, vertex_idx, value)
+    }
+
+    fn set_metadata(&mut self, metadata: TreeMetadata) {
+        let tree = self.current_tree.as_mut().expect("init not called");
+        tree.set_metadata(metadata)
+    }
+
+    fn add_internal_label(&mut self, vertex_idx: Self::VertexIdx, label: String) {
+        let tree = self.current_tree.as_mut().expect("init not called");
+        tree.set_internal_label(vertex_idx, label)
+    }
+
     fn set_name(&mut self, tree_name: String) {
         if let Some(tree) = &mut self.current_tree {
             tree.set_name(tree_name);
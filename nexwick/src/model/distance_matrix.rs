@@ -0,0 +1,132 @@
+//! Provides [DistanceMatrix] for pairwise distances between taxa.
+//!
+//! A [DistanceMatrix] is parsed from a Nexus `DISTANCES` block, see
+//! [NexusParserBuilder::with_distances()](crate::nexus::NexusParserBuilder::with_distances),
+//! and written with [NexusWriter::write_nexus_distances()](crate::nexus::NexusWriter::write_nexus_distances).
+//! Taxa are indexed in the same order as the taxa of the file, so that the
+//! index of a taxon equals its [LabelIndex] in the [LeafLabelMap].
+
+use crate::model::{LabelIndex, LeafLabelMap};
+
+// =#========================================================================#=
+// DISTANCE MATRIX
+// =#========================================================================$=
+/// Square matrix of pairwise distances between taxa, e.g. as computed by
+/// PAUP* or SplitsTree.
+///
+/// Entries are indexed by the [LabelIndex] of the taxa, i.e. by their
+/// position in [taxa()](Self::taxa). Missing distances are `NaN`; the
+/// matrix is usually, but not necessarily, symmetric.
+///
+/// # Example
+/// ```
+/// use nexwick::model::DistanceMatrix;
+///
+/// let taxa = vec![String::from("Anas"), String::from("Aythya"), String::from("Mergus")];
+/// let mut matrix = DistanceMatrix::new(taxa);
+/// matrix.set(0, 1, 0.2);
+/// matrix.set(0, 2, 0.5);
+/// matrix.set(1, 2, 0.4);
+///
+/// assert_eq!(matrix.num_taxa(), 3);
+/// assert_eq!(matrix.get(1, 0), Some(0.2));
+/// assert_eq!(matrix.get_by_label("Mergus", "Aythya"), Some(0.4));
+/// assert!(matrix.is_symmetric());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatrix {
+    /// Taxon labels, in the order of rows and columns
+    taxa: Vec<String>,
+    /// Distances in row-major order (`num_taxa * num_taxa` entries)
+    distances: Vec<f64>,
+}
+
+impl DistanceMatrix {
+    /// Creates a new [DistanceMatrix] for the given taxa with all
+    /// distances being zero.
+    pub fn new(taxa: Vec<String>) -> Self {
+        let num_taxa = taxa.len();
+        Self {
+            taxa,
+            distances: vec![0.0; num_taxa * num_taxa],
+        }
+    }
+
+    /// Creates a new [DistanceMatrix] for the taxa of a [LeafLabelMap]
+    /// with all distances being zero, so that its entries are indexed by
+    /// the [LabelIndex] of the map.
+    pub fn from_label_map(leaf_label_map: &LeafLabelMap) -> Self {
+        Self::new(leaf_label_map.labels().clone())
+    }
+
+    /// Returns the number of taxa, i.e. the number of rows and columns.
+    pub fn num_taxa(&self) -> usize {
+        self.taxa.len()
+    }
+
+    /// Returns the taxon labels, in the order of rows and columns.
+    pub fn taxa(&self) -> &[String] {
+        &self.taxa
+    }
+
+    /// Returns the index of the taxon with the given label, if present.
+    pub fn taxon_index(&self, label: &str) -> Option<LabelIndex> {
+        self.taxa.iter().position(|taxon| taxon == label)
+    }
+
+    /// Returns the distance from taxon `i` to taxon `j`,
+    /// or `None` if an index is out of bounds.
+    pub fn get(&self, i: LabelIndex, j: LabelIndex) -> Option<f64> {
+        let n = self.num_taxa();
+        (i < n && j < n).then(|| self.distances[i * n + j])
+    }
+
+    /// Returns the distance between the taxa with the given labels,
+    /// or `None` if a label is unknown.
+    pub fn get_by_label(&self, label_i: &str, label_j: &str) -> Option<f64> {
+        self.get(self.taxon_index(label_i)?, self.taxon_index(label_j)?)
+    }
+
+    /// Sets the distance between taxa `i` and `j` in both directions.
+    ///
+    /// # Panics
+    /// Panics if an index is out of bounds.
+    pub fn set(&mut self, i: LabelIndex, j: LabelIndex, distance: f64) {
+        self.set_directed(i, j, distance);
+        self.set_directed(j, i, distance);
+    }
+
+    /// Sets the distance from taxon `i` to taxon `j` only,
+    /// e.g. for asymmetric matrices.
+    ///
+    /// # Panics
+    /// Panics if an index is out of bounds.
+    pub fn set_directed(&mut self, i: LabelIndex, j: LabelIndex, distance: f64) {
+        let n = self.num_taxa();
+        assert!(i < n && j < n, "Taxon index out of bounds");
+        self.distances[i * n + j] = distance;
+    }
+
+    /// Returns the distances from taxon `i` to all taxa, if present.
+    pub fn row(&self, i: LabelIndex) -> Option<&[f64]> {
+        let n = self.num_taxa();
+        (i < n).then(|| &self.distances[i * n..(i + 1) * n])
+    }
+
+    /// Returns whether the distance from `i` to `j` equals the distance
+    /// from `j` to `i` for all taxa (missing distances count as equal).
+    pub fn is_symmetric(&self) -> bool {
+        let n = self.num_taxa();
+        (0..n).all(|i| {
+            (0..i).all(|j| {
+                let (d_ij, d_ji) = (self.distances[i * n + j], self.distances[j * n + i]);
+                d_ij == d_ji || (d_ij.is_nan() && d_ji.is_nan())
+            })
+        })
+    }
+
+    /// Returns whether any distance is missing (`NaN`).
+    pub fn has_missing(&self) -> bool {
+        self.distances.iter().any(|distance| distance.is_nan())
+    }
+}
@@ -15,7 +15,8 @@ use std::fmt::{Debug, Display};
 /// Resolves labels in Newick strings during parsing, using a [LabelStorage] backend.
 ///
 /// Different variants handle different scenarios:
-/// - [VerbatimLabels](Self::VerbatimLabels) — raw Newick files or NEXUS without TRANSLATE
+/// - [VerbatimLabels](Self::VerbatimLabels) — raw Newick files or NEXUS without TAXA block and TRANSLATE
+/// - [TaxaLabels](Self::TaxaLabels) — NEXUS with TAXA block but without TRANSLATE
 /// - [NexusLabels](Self::NexusLabels) — NEXUS with arbitrary TRANSLATE keys
 /// - [NexusIntegerLabels](Self::NexusIntegerLabels) — NEXUS with integer TRANSLATE keys (optimized)
 ///
@@ -27,11 +28,20 @@ pub enum LabelResolver<S: LabelStorage> {
     ///
     /// Use for:
     /// - Raw Newick strings/files (without extra translation map)
-    /// - Nexus file without TRANSLATE command
+    /// - Nexus file without TAXA block and TRANSLATE command
     ///
     /// Each label string is passed directly to the [LabelStorage].
     VerbatimLabels(S),
 
+    /// Resolves labels verbatim against the labels already in storage.
+    ///
+    /// Use for:
+    /// - Nexus file with TAXA block but without TRANSLATE command
+    ///
+    /// Fails on labels that are not in the [LabelStorage], i.e. on leaves
+    /// that are not a taxon of the TAXA block.
+    TaxaLabels(S),
+
     /// Resolves labels using Nexus TRANSLATE command mapping.
     ///
     /// Following specification, tries to resolve in order:
@@ -77,35 +87,41 @@ impl<S: LabelStorage> LabelResolver<S> {
         LabelResolver::VerbatimLabels(storage)
     }
 
+    /// Creates a [TaxaLabels](Self::TaxaLabels) resolver.
+    ///
+    /// # Arguments
+    /// * `storage` - The [LabelStorage] backend (must already contain all labels)
+    pub(crate) fn new_taxa_labels_resolver(storage: S) -> Self {
+        LabelResolver::TaxaLabels(storage)
+    }
+
     /// Creates a [NexusLabels](Self::NexusLabels) resolver.
     ///
     /// Builds a lookup map from TRANSLATE keys to storage references.
+    /// The translation may cover only some labels of the storage (partial
+    /// TRANSLATE command); the others are resolved by index or verbatim.
     ///
     /// # Arguments
     /// * `translation` - TRANSLATE block mapping (key → full taxon label)
     /// * `storage` - The [LabelStorage] backend (must already contain all labels)
     ///
-    /// # Panics
-    /// Panics if any label in `translation` is not found in `storage`.
+    /// # Errors
+    /// Returns a [LabelResolvingError] if any label in `translation` is not found in `storage`.
     pub(crate) fn new_nexus_labels_resolver(
         translation: HashMap<String, String>,
         storage: S,
-    ) -> Self {
+    ) -> Result<Self, LabelResolvingError> {
         // Instead of going from key -> label and then from label -> LabelRef,
         // we create a direct mapping key -> LabelRef
         let mut index_map = HashMap::with_capacity(translation.len());
         for (key, actual_label) in &translation {
-            let label_index = storage.check_and_ref(actual_label).unwrap_or_else(|| {
-                panic!(
-                    "Label {} provided by translation to resolver\
-                  not present in provided label storage.",
-                    actual_label
-                )
-            });
+            let label_index = storage
+                .check_and_ref(actual_label)
+                .ok_or_else(|| unknown_label_error(key, actual_label))?;
             index_map.insert(key.clone(), label_index);
         }
 
-        LabelResolver::NexusLabels { index_map, storage }
+        Ok(LabelResolver::NexusLabels { index_map, storage })
     }
 
     /// Creates a [NexusIntegerLabels](Self::NexusIntegerLabels) resolver.
@@ -116,8 +132,8 @@ impl<S: LabelStorage> LabelResolver<S> {
     /// * `translation` - TRANSLATE block mapping (integer key as string → full taxon label)
     /// * `storage` - The [LabelStorage] backend (must already contain all labels)
     ///
-    /// # Panics
-    /// Panics if:
+    /// # Errors
+    /// Returns a [LabelResolvingError] if:
     /// - Any key is not a valid positive integer
     /// - Any key is out of bounds (must be 1..=num_labels)
     /// - Any label is not found in `storage`
@@ -125,7 +141,7 @@ impl<S: LabelStorage> LabelResolver<S> {
     pub(crate) fn new_nexus_integer_labels_resolver(
         translation: HashMap<String, String>,
         storage: S,
-    ) -> Self {
+    ) -> Result<Self, LabelResolvingError> {
         let num_labels = storage.num_labels();
 
         // Validate all keys are valid integers and build index array;
@@ -135,26 +151,22 @@ impl<S: LabelStorage> LabelResolver<S> {
 
         for (key, actual_label) in &translation {
             // Parse key as integer
-            let nexus_index = key
-                .parse::<usize>()
-                .unwrap_or_else(|_| panic!("TRANSLATE key '{}' is not a valid integer", key));
+            let nexus_index = key.parse::<usize>().map_err(|_| {
+                LabelResolvingError(format!("TRANSLATE key '{}' is not a valid integer", key))
+            })?;
 
             // Validate bounds (1-based NEXUS indexing)
             if nexus_index == 0 || nexus_index > num_labels {
-                panic!(
-                    "TRANSLATE index {} out of bounds (1-based indexing), valid range: 1-{})",
+                return Err(LabelResolvingError(format!(
+                    "TRANSLATE index {} out of bounds (1-based indexing, valid range: 1-{})",
                     nexus_index, num_labels
-                );
+                )));
             }
 
             // Look up the label in the label storage
-            let label_ref = storage.check_and_ref(actual_label).unwrap_or_else(|| {
-                panic!(
-                    "Label '{}' provided by translation to resolver\
-                    not present in provided label storage.",
-                    actual_label
-                )
-            });
+            let label_ref = storage
+                .check_and_ref(actual_label)
+                .ok_or_else(|| unknown_label_error(key, actual_label))?;
 
             // Store in array (converting from 1-based to 0-based indexing)
             index_array[nexus_index - 1] = Some(label_ref);
@@ -165,20 +177,23 @@ impl<S: LabelStorage> LabelResolver<S> {
             .into_iter()
             .enumerate()
             .map(|(i, opt)| {
-                opt.unwrap_or_else(|| panic!("Missing translation for index {}", i + 1))
+                opt.ok_or_else(|| {
+                    LabelResolvingError(format!("Missing translation for index {}", i + 1))
+                })
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        LabelResolver::NexusIntegerLabels {
+        Ok(LabelResolver::NexusIntegerLabels {
             index_array,
             storage,
-        }
+        })
     }
 
     /// Resolves a parsed label string to its storage reference.
     ///
     /// Resolution behavior depends on the variant:
     /// - [VerbatimLabels](Self::VerbatimLabels): stores label and returns reference
+    /// - [TaxaLabels](Self::TaxaLabels): returns reference of stored label
     /// - [NexusLabels](Self::NexusLabels): tries TRANSLATE key, then integer index, then verbatim
     /// - [NexusIntegerLabels](Self::NexusIntegerLabels): parses as integer index only
     ///
@@ -195,6 +210,14 @@ impl<S: LabelStorage> LabelResolver<S> {
         match self {
             LabelResolver::VerbatimLabels(storage) => Ok(storage.store_and_ref(parsed_label)),
 
+            LabelResolver::TaxaLabels(storage) => {
+                storage.check_and_ref(parsed_label).ok_or_else(|| {
+                    LabelResolvingError(format!(
+                        "Unknown taxon '{parsed_label}' (not in TAXA block)"
+                    ))
+                })
+            }
+
             LabelResolver::NexusLabels { index_map, storage } => {
                 // 1. Try if parsed label is key of translation map
                 if let Some(label_ref) = index_map.get(parsed_label) {
@@ -254,6 +277,7 @@ impl<S: LabelStorage> LabelResolver<S> {
     pub(crate) fn into_label_storage(self) -> S {
         match self {
             LabelResolver::VerbatimLabels(storage) => storage,
+            LabelResolver::TaxaLabels(storage) => storage,
             LabelResolver::NexusLabels { storage, .. } => storage,
             LabelResolver::NexusIntegerLabels { storage, .. } => storage,
         }
@@ -263,6 +287,17 @@ impl<S: LabelStorage> LabelResolver<S> {
     pub(crate) fn label_storage(&self) -> &S {
         match self {
             LabelResolver::VerbatimLabels(storage) => storage,
+            LabelResolver::TaxaLabels(storage) => storage,
+            LabelResolver::NexusLabels { storage, .. } => storage,
+            LabelResolver::NexusIntegerLabels { storage, .. } => storage,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying storage.
+    pub(crate) fn label_storage_mut(&mut self) -> &mut S {
+        match self {
+            LabelResolver::VerbatimLabels(storage) => storage,
+            LabelResolver::TaxaLabels(storage) => storage,
             LabelResolver::NexusLabels { storage, .. } => storage,
             LabelResolver::NexusIntegerLabels { storage, .. } => storage,
         }
@@ -275,6 +310,9 @@ impl<S: LabelStorage> Display for LabelResolver<S> {
             LabelResolver::VerbatimLabels(_) => {
                 writeln!(f, "LabelResolver::VerbatimLabels")
             }
+            LabelResolver::TaxaLabels(_) => {
+                writeln!(f, "LabelResolver::TaxaLabels")
+            }
             LabelResolver::NexusLabels { index_map, .. } => {
                 writeln!(f, "LabelResolver::NexusLabels with internal mapping:")?;
                 for (key, value) in index_map {
@@ -300,6 +338,13 @@ impl<S: LabelStorage> Display for LabelResolver<S> {
 #[derive(Debug)]
 pub struct LabelResolvingError(String);
 
+/// Returns the error for a TRANSLATE `key` whose `label` is not in the label storage.
+fn unknown_label_error(key: &str, label: &str) -> LabelResolvingError {
+    LabelResolvingError(format!(
+        "Label '{label}' of TRANSLATE key '{key}' not present in provided label storage"
+    ))
+}
+
 impl Display for LabelResolvingError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(&self.0, f)
@@ -46,6 +46,17 @@ pub trait LabelStorage: Debug {
     /// Used for NEXUS integer label resolution (after converting from 1-based).
     fn index_to_ref(&self, index: usize) -> Self::LabelRef;
 
+    /// Returns the label with the given 0-based index, if any.
+    ///
+    /// Used to recover the taxa of a Nexus file from the labels stored
+    /// while parsing its first tree, if these are not declared.
+    fn get_label(&self, index: usize) -> Option<&str>;
+
     /// Returns the number of labels in storage.
     fn num_labels(&self) -> usize;
+
+    /// Removes all but the first `num_labels` labels.
+    ///
+    /// Used to discard the labels stored while parsing a malformed tree.
+    fn truncate(&mut self, num_labels: usize);
 }
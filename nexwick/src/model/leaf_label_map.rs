@@ -90,8 +90,9 @@ impl LeafLabelMap {
             self.labels.push(s.to_string());
             self.map.insert(s.to_string(), idx);
 
-            // Should not add more labels than specified by capacity `num_leaves`
-            debug_assert!(idx < self.num_leaves);
+            // Expected number of labels `num_leaves` might have been a guess
+            // (e.g. when parsing Newick files or Nexus files without TAXA block)
+            self.num_leaves = self.num_leaves.max(idx + 1);
 
             idx
         }
@@ -135,6 +136,16 @@ impl LeafLabelMap {
         self.labels.len()
     }
 
+    /// Removes all labels with index `num_labels` or higher.
+    ///
+    /// # Arguments
+    /// * `num_labels` - The number of labels to keep
+    pub fn truncate(&mut self, num_labels: usize) {
+        for label in self.labels.drain(num_labels.min(self.labels.len())..) {
+            self.map.remove(&label);
+        }
+    }
+
     /// Returns whether the map has reached its expected capacity.
     pub fn is_full(&self) -> bool {
         self.num_leaves == self.map.len()
@@ -170,9 +181,17 @@ impl LabelStorage for LeafLabelMap {
         index
     }
 
+    fn get_label(&self, index: usize) -> Option<&str> {
+        LeafLabelMap::get_label(self, index)
+    }
+
     fn num_labels(&self) -> usize {
         self.num_labels()
     }
+
+    fn truncate(&mut self, num_labels: usize) {
+        LeafLabelMap::truncate(self, num_labels);
+    }
 }
 
 impl fmt::Display for LeafLabelMap {
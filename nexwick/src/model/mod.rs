@@ -1,10 +1,10 @@
-//! Data model for binary phylogenetic trees.
+//! Data model for phylogenetic trees, binary or with polytomies.
 //!
 //! # Tree representation
 //! Trees are represented by [GenTree], which uses the arena pattern to store
 //! [Vertex] nodes. Each vertex is either a `Root`, `Internal`, or `Leaf`,
-//! referenced by [VertexIndex]. Struct thus restricted to trees with
-//! at least two leaves.
+//! referenced by [VertexIndex]. Root and internal vertices have two or
+//! more children. Struct thus restricted to trees with at least two leaves.
 //!
 //! Two concrete tree types are provided:
 //!
@@ -23,6 +23,12 @@
 //! You can implement [TreeBuilder] to construct your own tree representation
 //! while reusing the library's parsers.
 //!
+//! # Networks
+//! Rooted phylogenetic networks with hybrid vertices (reticulations), as
+//! written in extended Newick (eNewick), are represented by [GenNetwork],
+//! with [CompactNetwork] and [SimpleNetwork] analogous to the trees above.
+//! They are built by a [NetworkBuilder], which plugs into the same parsers.
+//!
 //! # Label handling
 //! During parsing, labels flow through:
 //! 1. [LabelResolver] — translates Newick strings
@@ -30,23 +36,35 @@
 //! 2. [LabelStorage] — stores labels and returns references for tree leaves
 //!
 //! See the [tree_builder] module docs for details on this flow.
+//!
+//! # Alignments and distances
+//! Character matrices of Nexus `DATA`/`CHARACTERS` blocks are represented
+//! by [Alignment], indexed by taxon like the [LeafLabelMap]. Likewise,
+//! pairwise distances of Nexus `DISTANCES` blocks are represented by
+//! [DistanceMatrix].
 
+pub mod alignment;
 pub mod annotation;
 pub mod compact_tree_builder;
+pub mod distance_matrix;
 pub mod label_resolver;
 pub mod label_storage;
 pub mod leaf_label_map;
+pub mod network;
+pub mod network_builder;
 pub mod simple_tree_builder;
 pub mod tree;
 pub mod tree_builder;
+pub mod tree_metadata;
 pub mod vertex;
 
 // Tree (generic)
+pub use annotation::{AnnotationValue, Annotations};
 pub use tree::GenTree;
 pub use tree::VertexIndex;
 pub use tree_builder::TreeBuilder;
+pub use tree_metadata::TreeMetadata;
 pub use vertex::Vertex;
-pub use annotation::{Annotations, AnnotationValue};
 // Compact tree
 pub use compact_tree_builder::CompactTreeBuilder;
 pub use leaf_label_map::LabelIndex;
@@ -56,6 +74,12 @@ pub use tree::CompactTree;
 pub use simple_tree_builder::SimpleLabelStorage;
 pub use simple_tree_builder::SimpleTreeBuilder;
 pub use tree::SimpleTree;
+// Network
+pub use network::{CompactNetwork, GenNetwork, NetworkVertex, ParentEdge, SimpleNetwork};
+pub use network_builder::{CompactNetworkBuilder, NetworkBuilder, SimpleNetworkBuilder};
+// Alignment & distances
+pub use alignment::{Alignment, DataType};
+pub use distance_matrix::DistanceMatrix;
 // Label handling
 pub use label_resolver::LabelResolver;
 pub use label_storage::LabelStorage;
@@ -0,0 +1,511 @@
+//! Provides generic phylogenetic network representations.
+//!
+//! Provides core data structures for representing rooted phylogenetic
+//! networks, as written in extended Newick (eNewick) format:
+//! * [`GenNetwork<LabelRef>`] - Main network structure using the arena
+//!   pattern, generic over way leaves handle labels.
+//! * [NetworkVertex] as vertex type, which may have multiple parents
+//! * [ParentEdge] as incoming edge of a vertex, with branch length and
+//!   inheritance probability
+//! * [CompactNetwork] as realization with [LabelIndex]
+//! * [SimpleNetwork] as realization with [String]
+
+use crate::model::annotation::{AnnotationValue, Annotations};
+use crate::model::leaf_label_map::{LabelIndex, LeafLabelMap};
+use crate::model::tree::{ValidLabel, VertexIndex};
+use crate::model::vertex::BranchLength;
+use crate::newick;
+use crate::newick::NewickStyle;
+
+/// Float comparison tolerance (for inheritance probabilities)
+const EPSILON: f64 = 1e-7;
+
+/// *During construction only*, index for unset root.
+const NO_ROOT_SET_INDEX: VertexIndex = usize::MAX;
+
+// =#========================================================================#=
+// NETWORK
+// =#========================================================================$=
+/// A rooted phylogenetic network represented using the arena pattern
+/// on [NetworkVertex].
+///
+/// Vertices are stored in a contiguous vector and referenced by
+/// [VertexIndex], just as in [GenTree](crate::model::GenTree).
+///
+/// Generic over `L` (LabelRef), representing how leaves handle labels
+/// (e.g. as index or String).
+///
+/// # Structure
+/// - All vertices (root, tree vertices, hybrids, and leaves) are stored in the arena.
+/// - Index of root is maintained; the root is the only vertex without parents.
+/// - Hybrid vertices (reticulations) have two or more parents;
+///   all other non-root vertices have exactly one.
+/// - Edges are stored as [ParentEdge]s at their child vertex, holding the
+///   optional branch length and inheritance probability (γ).
+/// - Hybrid vertices keep their eNewick identifier (e.g. `H1` for `#H1`).
+/// - Annotations and labels of non-leaf vertices are stored in the network,
+///   accessed via key and vertex index.
+///
+/// # Construction
+/// Add vertices with [add_leaf()](Self::add_leaf) and
+/// [add_vertex()](Self::add_vertex), connect them with
+/// [add_edge()](Self::add_edge), and mark the root with
+/// [set_root()](Self::set_root).
+/// Test validity with [`GenNetwork::is_valid()`].
+#[derive(Debug, Clone)]
+pub struct GenNetwork<L> {
+    /// Vertices of this network (arena pattern)
+    vertices: Vec<NetworkVertex<L>>,
+
+    /// Index of the root of this network
+    root_index: VertexIndex,
+
+    /// Name of network; optional, e.g. when parsed from Nexus file
+    name: Option<String>,
+
+    /// Annotation data
+    annotations: Option<Box<Annotations>>,
+
+    /// Labels of non-leaf vertices, parallel to arena;
+    /// only allocated once the first such label is set
+    internal_labels: Option<Vec<Option<String>>>,
+}
+
+// Convenient type aliases
+/// Network with shared labels via [LeafLabelMap], which is efficient for set of networks.
+pub type CompactNetwork = GenNetwork<LabelIndex>;
+
+/// Network with embedded String labels.
+pub type SimpleNetwork = GenNetwork<String>;
+
+// ============================================================================
+// New, Getters / Accessors, etc. (pub)
+// ============================================================================
+impl<L> GenNetwork<L> {
+    /// Creates a new network with capacity for `num_vertices` vertices.
+    ///
+    /// # Arguments
+    /// `num_vertices` - expected number of vertices (hint for allocation)
+    pub fn new(num_vertices: usize) -> Self {
+        GenNetwork {
+            vertices: Vec::with_capacity(num_vertices),
+            root_index: NO_ROOT_SET_INDEX,
+            name: None,
+            annotations: None,
+            internal_labels: None,
+        }
+    }
+
+    /// Attaches a name to this network.
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Adds a leaf with the given label, assigning a unique index, which gets returned.
+    pub fn add_leaf(&mut self, label: L) -> VertexIndex {
+        let index = self.vertices.len();
+        self.vertices.push(NetworkVertex::new(index, Some(label)));
+        index
+    }
+
+    /// Adds a non-leaf vertex (root, tree vertex or hybrid) without any edges,
+    /// assigning a unique index, which gets returned.
+    ///
+    /// Use [add_edge()](Self::add_edge) to connect it to its children and parents.
+    pub fn add_vertex(&mut self) -> VertexIndex {
+        let index = self.vertices.len();
+        self.vertices.push(NetworkVertex::new(index, None));
+        index
+    }
+
+    /// Adds an edge from `parent` to `child`.
+    ///
+    /// # Arguments
+    /// * `parent` - Index of the parent vertex
+    /// * `child` - Index of the child vertex
+    /// * `branch_length` - Optional length of the edge (non-negative)
+    /// * `inheritance` - Optional inheritance probability (γ) of the edge,
+    ///   usually only given for edges into hybrid vertices
+    ///
+    /// # Panics
+    /// Panics if `parent` or `child` is out of bounds.
+    pub fn add_edge(
+        &mut self,
+        parent: VertexIndex,
+        child: VertexIndex,
+        branch_length: Option<BranchLength>,
+        inheritance: Option<f64>,
+    ) {
+        self.vertices[parent].children.push(child);
+        self.vertices[child].parents.push(ParentEdge {
+            parent,
+            branch_length,
+            inheritance,
+        });
+    }
+
+    /// Sets the root of this network.
+    pub fn set_root(&mut self, root_index: VertexIndex) {
+        self.root_index = root_index;
+    }
+
+    /// Sets the label of a vertex, e.g. for a hybrid leaf added via
+    /// [add_vertex()](Self::add_vertex).
+    pub fn set_label(&mut self, vertex_index: VertexIndex, label: L) {
+        self.vertices[vertex_index].label = Some(label);
+    }
+
+    /// Sets the eNewick identifier of a hybrid vertex, e.g. `H1` for `#H1`.
+    pub fn set_hybrid_id(&mut self, vertex_index: VertexIndex, hybrid_id: String) {
+        self.vertices[vertex_index].hybrid_id = Some(hybrid_id);
+    }
+
+    /// Adds an annotation value for a specific vertex.
+    ///
+    /// # Arguments
+    /// * `key` - Annotation name (e.g. "pop_size", "rate")
+    /// * `vertex_index` - Index of the vertex to annotate
+    /// * `value` - The [AnnotationValue] to store
+    ///
+    /// # Panics
+    /// Panics if `vertex_index` is out of bounds.
+    pub fn add_annotation(
+        &mut self,
+        key: String,
+        vertex_index: VertexIndex,
+        value: AnnotationValue,
+    ) {
+        let annotations = self.annotations.get_or_insert_with(|| {
+            let num_vertices = self.vertices.capacity().max(self.vertices.len());
+            Box::new(Annotations::new(num_vertices))
+        });
+        annotations.add(key, vertex_index, value);
+    }
+
+    /// Returns a reference to the annotations, if any.
+    pub fn annotations(&self) -> Option<&Annotations> {
+        self.annotations.as_deref()
+    }
+
+    /// Returns a single annotation value for a vertex.
+    pub fn annotation(&self, key: &str, vertex_index: VertexIndex) -> Option<AnnotationValue> {
+        self.annotations.as_deref()?.get(key, vertex_index)
+    }
+
+    /// Sets the label of a non-leaf vertex, e.g. a clade name.
+    pub fn set_internal_label(&mut self, vertex_index: VertexIndex, label: String) {
+        let labels = self.internal_labels.get_or_insert_with(Vec::new);
+        if vertex_index >= labels.len() {
+            let num_vertices = self.vertices.capacity().max(vertex_index + 1);
+            labels.resize(num_vertices, None);
+        }
+        labels[vertex_index] = Some(label);
+    }
+
+    /// Returns the label of a non-leaf vertex, if it has one.
+    pub fn internal_label(&self, vertex_index: VertexIndex) -> Option<&str> {
+        self.internal_labels.as_ref()?.get(vertex_index)?.as_deref()
+    }
+
+    /// Returns reference to name of this network, or `None` if not set.
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    /// Sets the name of this network.
+    pub fn set_name(&mut self, name: String) {
+        self.name = Some(name);
+    }
+
+    /// Returns reference to the root.
+    ///
+    /// # Panics
+    /// Panics if root not set yet.
+    pub fn root(&self) -> &NetworkVertex<L> {
+        &self.vertices[self.root_index]
+    }
+
+    /// Returns index of the root.
+    pub fn root_index(&self) -> VertexIndex {
+        self.root_index
+    }
+
+    /// Returns reference to the vertex with the given index.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn vertex(&self, index: VertexIndex) -> &NetworkVertex<L> {
+        &self.vertices[index]
+    }
+
+    /// Returns an iterator over all vertices (in arena order).
+    pub fn vertices(&self) -> impl Iterator<Item = &NetworkVertex<L>> {
+        self.vertices.iter()
+    }
+
+    /// Returns an iterator over all hybrid vertices (in arena order).
+    pub fn hybrids(&self) -> impl Iterator<Item = &NetworkVertex<L>> {
+        self.vertices.iter().filter(|v| v.is_hybrid())
+    }
+
+    /// Returns the total number of vertices.
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns the number of leaves.
+    pub fn num_leaves(&self) -> usize {
+        self.vertices.iter().filter(|v| v.is_leaf()).count()
+    }
+
+    /// Returns the number of hybrid vertices (reticulations).
+    pub fn num_hybrids(&self) -> usize {
+        self.hybrids().count()
+    }
+
+    /// Returns the number of edges.
+    pub fn num_edges(&self) -> usize {
+        self.vertices.iter().map(|v| v.parents.len()).sum()
+    }
+
+    /// Returns whether this network is a tree, i.e. has no hybrid vertices.
+    pub fn is_tree(&self) -> bool {
+        self.vertices.iter().all(|v| !v.is_hybrid())
+    }
+}
+
+impl<L: ValidLabel> GenNetwork<L> {
+    /// Validates the network structure and all index references.
+    ///
+    /// Checks:
+    /// - Root index is valid and root is the only vertex without parents
+    /// - All vertex indices match their position in the arena
+    /// - All child and parent references are in bounds and consistent
+    /// - Leaves are labelled; non-hybrid non-leaf vertices have at least two children
+    /// - Inheritance probabilities of a hybrid, if all given, sum up to one
+    /// - All vertices are reachable from the root and there are no cycles
+    ///
+    /// # Returns
+    /// `true` if network is valid, `false` otherwise
+    pub fn is_valid(&self) -> bool {
+        // Check root index is set and within bounds
+        if self.root_index >= self.vertices.len() {
+            return false;
+        }
+
+        let num_leaves = self.num_leaves();
+        let mut num_edges = 0;
+
+        for (index, vertex) in self.vertices.iter().enumerate() {
+            // Check vertex index matches its arena position
+            if vertex.index != index {
+                return false;
+            }
+
+            // Check only the root has no parents
+            if vertex.parents.is_empty() != (index == self.root_index) {
+                return false;
+            }
+
+            // Check children are in bounds and list this vertex as a parent
+            for &child in &vertex.children {
+                if child >= self.vertices.len()
+                    || !self.vertices[child]
+                        .parents
+                        .iter()
+                        .any(|edge| edge.parent == index)
+                {
+                    return false;
+                }
+            }
+            num_edges += vertex.children.len();
+
+            // Check parents are in bounds and list this vertex as a child
+            for edge in &vertex.parents {
+                if edge.parent >= self.vertices.len()
+                    || !self.vertices[edge.parent].children.contains(&index)
+                {
+                    return false;
+                }
+            }
+
+            // Check labels and number of children
+            if vertex.is_leaf() {
+                let label = vertex.label();
+                if label.is_none_or(|l| !l.is_valid_for_tree(num_leaves)) {
+                    return false;
+                }
+            } else if vertex.label.is_some() || (!vertex.is_hybrid() && vertex.children.len() < 2) {
+                return false;
+            }
+
+            // Check inheritance probabilities of hybrid sum up to one
+            if vertex.is_hybrid() {
+                let gammas: Option<Vec<f64>> =
+                    vertex.parents.iter().map(|e| e.inheritance).collect();
+                if let Some(gammas) = gammas
+                    && (gammas.iter().sum::<f64>() - 1.0).abs() > EPSILON
+                {
+                    return false;
+                }
+            }
+        }
+
+        // Check each edge is listed once at parent and once at child
+        if num_edges != self.num_edges() {
+            return false;
+        }
+
+        // Check all vertices are reachable from the root without cycles
+        // (Kahn's algorithm: a vertex is visited once all its parents are)
+        let mut num_unvisited_parents: Vec<usize> =
+            self.vertices.iter().map(|v| v.parents.len()).collect();
+        let mut stack = vec![self.root_index];
+        let mut num_reached = 0;
+        while let Some(index) = stack.pop() {
+            num_reached += 1;
+            for &child in &self.vertices[index].children {
+                num_unvisited_parents[child] -= 1;
+                if num_unvisited_parents[child] == 0 {
+                    stack.push(child);
+                }
+            }
+        }
+
+        num_reached == self.vertices.len()
+    }
+}
+
+impl<L> std::ops::Index<VertexIndex> for GenNetwork<L> {
+    type Output = NetworkVertex<L>;
+
+    fn index(&self, index: VertexIndex) -> &Self::Output {
+        &self.vertices[index]
+    }
+}
+
+// ============================================================================
+// Printing (pub, only for CompactNetwork)
+// ============================================================================
+impl CompactNetwork {
+    /// Convenience method to convert this network to an eNewick string
+    pub fn to_enewick(&self, style: &NewickStyle, leaf_label_map: Option<&LeafLabelMap>) -> String {
+        newick::to_enewick(style, self, leaf_label_map)
+    }
+}
+
+// =#========================================================================#=
+// NETWORK VERTEX
+// =#========================================================================$=
+/// Vertex of a [GenNetwork], which may have several parents.
+///
+/// Whether a vertex is the root, a leaf, or a hybrid follows from its number
+/// of parents and children.
+#[derive(Debug, Clone)]
+pub struct NetworkVertex<L> {
+    /// Index of this vertex in the arena
+    index: VertexIndex,
+    /// Incoming edges; empty for the root, two or more for hybrids
+    parents: Vec<ParentEdge>,
+    /// Indices of children; empty for leaves
+    children: Vec<VertexIndex>,
+    /// Label reference of leaves
+    label: Option<L>,
+    /// eNewick identifier of hybrids, e.g. `H1`
+    hybrid_id: Option<String>,
+}
+
+impl<L> NetworkVertex<L> {
+    /// Creates a new vertex without any edges.
+    fn new(index: VertexIndex, label: Option<L>) -> Self {
+        NetworkVertex {
+            index,
+            parents: Vec::new(),
+            children: Vec::new(),
+            label,
+            hybrid_id: None,
+        }
+    }
+
+    /// Returns index of this vertex.
+    pub fn index(&self) -> VertexIndex {
+        self.index
+    }
+
+    /// Returns the incoming edges of this vertex.
+    pub fn parents(&self) -> &[ParentEdge] {
+        &self.parents
+    }
+
+    /// Returns the indices of the children of this vertex.
+    pub fn children(&self) -> &[VertexIndex] {
+        &self.children
+    }
+
+    /// Returns the label (reference) of this vertex, if it is a leaf.
+    pub fn label(&self) -> Option<&L> {
+        self.label.as_ref()
+    }
+
+    /// Returns the eNewick identifier of this vertex, if it is a hybrid.
+    pub fn hybrid_id(&self) -> Option<&str> {
+        self.hybrid_id.as_deref()
+    }
+
+    /// Returns the number of parents.
+    pub fn num_parents(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the number of children.
+    pub fn num_children(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns whether this vertex is the root (has no parents).
+    pub fn is_root(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Returns whether this vertex is a leaf (has no children).
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns whether this vertex is a hybrid (has two or more parents).
+    pub fn is_hybrid(&self) -> bool {
+        self.parents.len() > 1
+    }
+}
+
+// =#========================================================================#=
+// PARENT EDGE
+// =#========================================================================$=
+/// Incoming edge of a [NetworkVertex].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParentEdge {
+    /// Index of the parent vertex
+    parent: VertexIndex,
+    /// Optional branch length
+    branch_length: Option<BranchLength>,
+    /// Optional inheritance probability (γ)
+    inheritance: Option<f64>,
+}
+
+impl ParentEdge {
+    /// Returns the index of the parent vertex.
+    pub fn parent(&self) -> VertexIndex {
+        self.parent
+    }
+
+    /// Returns the branch length of this edge, if set.
+    pub fn branch_length(&self) -> Option<BranchLength> {
+        self.branch_length
+    }
+
+    /// Returns the inheritance probability (γ) of this edge, if set.
+    pub fn inheritance(&self) -> Option<f64> {
+        self.inheritance
+    }
+}
@@ -0,0 +1,259 @@
+//! Provides [TreeBuilder] implementation structs for [GenNetwork].
+
+use crate::model::annotation::AnnotationValue;
+use crate::model::label_storage::LabelStorage;
+use crate::model::network::GenNetwork;
+use crate::model::tree_builder::TreeBuilder;
+use crate::model::vertex::BranchLength;
+use crate::model::{LeafLabelMap, SimpleLabelStorage, VertexIndex};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Builder that constructs [GenNetwork] instances from extended Newick
+/// (eNewick) strings, e.g. `((A,(B)#H1:0.5::0.3),(#H1:0.2::0.7,C));`.
+///
+/// [NetworkBuilder] implements [TreeBuilder] with support for hybrid
+/// vertices, so that the Newick and Nexus parsers can be reused to parse
+/// phylogenetic networks. Every occurrence of a hybrid vertex in the
+/// eNewick string is first recorded separately, as its branch length and
+/// inheritance probability belong to the edge from its respective parent;
+/// occurrences with the same identifier are merged in
+/// [finish_tree()](TreeBuilder::finish_tree).
+///
+/// Branch annotations (e.g. `A:[&rate=0.9]0.12`) are ignored; use
+/// [BranchAnnotationPolicy::Merge](crate::newick::BranchAnnotationPolicy::Merge)
+/// to keep them as vertex annotations.
+///
+/// Generic over the [LabelStorage] `S`; see the type aliases
+/// [CompactNetworkBuilder] and [SimpleNetworkBuilder].
+///
+/// # Example
+/// ```
+/// use nexwick::model::CompactNetworkBuilder;
+/// use nexwick::newick::NewickParser;
+/// use nexwick::parser::byte_parser::ByteParser;
+///
+/// let mut byte_parser = ByteParser::for_str("((A,(B)#H1:0.5::0.3),(#H1:0.2::0.7,C));");
+/// let mut parser = NewickParser::new(CompactNetworkBuilder::new());
+/// let network = parser.parse_str(&mut byte_parser)?;
+/// assert_eq!(network.num_hybrids(), 1);
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct NetworkBuilder<S: LabelStorage> {
+    current: Option<NetworkUnderConstruction<S::LabelRef>>,
+    _storage: PhantomData<S>,
+}
+
+/// Builder that constructs [CompactNetwork](crate::model::CompactNetwork)
+/// instances with labels stored in a shared [LeafLabelMap].
+pub type CompactNetworkBuilder = NetworkBuilder<LeafLabelMap>;
+
+/// Builder that constructs [SimpleNetwork](crate::model::SimpleNetwork)
+/// instances with labels stored directly in leaves.
+pub type SimpleNetworkBuilder = NetworkBuilder<SimpleLabelStorage>;
+
+/// State of the network currently constructed; vertices are occurrences in
+/// the eNewick string, so hybrid vertices may be present multiple times.
+struct NetworkUnderConstruction<L> {
+    occurrences: Vec<Occurrence<L>>,
+    root: Option<VertexIndex>,
+    name: Option<String>,
+    annotations: Vec<(String, VertexIndex, AnnotationValue)>,
+}
+
+/// Occurrence of a vertex in the eNewick string, with its incoming edge.
+struct Occurrence<L> {
+    children: Vec<VertexIndex>,
+    label: Option<L>,
+    internal_label: Option<String>,
+    hybrid_id: Option<String>,
+    branch_len: Option<f64>,
+    inheritance: Option<f64>,
+}
+
+impl<S: LabelStorage> NetworkBuilder<S> {
+    /// Creates a new builder in the empty state.
+    pub fn new() -> Self {
+        Self {
+            current: None,
+            _storage: PhantomData,
+        }
+    }
+
+    /// Records an occurrence and returns its index.
+    fn push(&mut self, occurrence: Occurrence<S::LabelRef>) -> VertexIndex {
+        let current = self.current.as_mut().expect("init not called");
+        current.occurrences.push(occurrence);
+        current.occurrences.len() - 1
+    }
+}
+
+impl<S: LabelStorage> Default for NetworkBuilder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: LabelStorage> TreeBuilder for NetworkBuilder<S> {
+    type LabelRef = S::LabelRef;
+    type VertexIdx = VertexIndex;
+    type Tree = GenNetwork<S::LabelRef>;
+    type Storage = S;
+
+    fn create_storage(capacity: usize) -> S {
+        S::with_capacity(capacity)
+    }
+
+    fn init_next(&mut self, num_leaves: usize) {
+        self.current = Some(NetworkUnderConstruction {
+            occurrences: Vec::with_capacity(2 * num_leaves),
+            root: None,
+            name: None,
+            annotations: Vec::new(),
+        });
+    }
+
+    fn add_leaf(&mut self, branch_len: Option<f64>, label: Self::LabelRef) -> Self::VertexIdx {
+        self.push(Occurrence {
+            children: Vec::new(),
+            label: Some(label),
+            internal_label: None,
+            hybrid_id: None,
+            branch_len,
+            inheritance: None,
+        })
+    }
+
+    fn add_internal(
+        &mut self,
+        children: Vec<Self::VertexIdx>,
+        branch_len: Option<f64>,
+    ) -> Self::VertexIdx {
+        self.push(Occurrence {
+            children,
+            label: None,
+            internal_label: None,
+            hybrid_id: None,
+            branch_len,
+            inheritance: None,
+        })
+    }
+
+    fn add_root(
+        &mut self,
+        children: Vec<Self::VertexIdx>,
+        branch_len: Option<f64>,
+    ) -> Self::VertexIdx {
+        let index = self.add_internal(children, branch_len);
+        self.current.as_mut().expect("init not called").root = Some(index);
+        index
+    }
+
+    fn supports_hybrids(&self) -> bool {
+        true
+    }
+
+    fn add_hybrid(
+        &mut self,
+        hybrid_id: String,
+        children: Vec<Self::VertexIdx>,
+        label: Option<Self::LabelRef>,
+        branch_len: Option<f64>,
+        inheritance: Option<f64>,
+    ) -> Self::VertexIdx {
+        self.push(Occurrence {
+            children,
+            label,
+            internal_label: None,
+            hybrid_id: Some(hybrid_id),
+            branch_len,
+            inheritance,
+        })
+    }
+
+    fn add_annotation(&mut self, key: String, vertex_idx: Self::VertexIdx, value: AnnotationValue) {
+        let current = self.current.as_mut().expect("init not called");
+        current.annotations.push((key, vertex_idx, value));
+    }
+
+    fn add_internal_label(&mut self, vertex_idx: Self::VertexIdx, label: String) {
+        let current = self.current.as_mut().expect("init not called");
+        current.occurrences[vertex_idx].internal_label = Some(label);
+    }
+
+    fn set_name(&mut self, tree_name: String) {
+        if let Some(current) = &mut self.current {
+            current.name = Some(tree_name);
+        }
+    }
+
+    fn finish_tree(&mut self) -> Option<Self::Tree> {
+        let current = self.current.take()?;
+        let mut network = GenNetwork::new(current.occurrences.len());
+
+        // Edges, given by the occurrence of each child (as they hold the edge data)
+        let edges: Vec<(VertexIndex, VertexIndex, Option<f64>, Option<f64>)> = current
+            .occurrences
+            .iter()
+            .enumerate()
+            .flat_map(|(parent, occurrence)| {
+                occurrence
+                    .children
+                    .iter()
+                    .map(move |&child| (parent, child))
+            })
+            .map(|(parent, child)| {
+                let edge = &current.occurrences[child];
+                (parent, child, edge.branch_len, edge.inheritance)
+            })
+            .collect();
+
+        // Vertices, merging all occurrences of the same hybrid
+        let mut vertex_of: Vec<VertexIndex> = Vec::with_capacity(current.occurrences.len());
+        let mut hybrids: HashMap<String, VertexIndex> = HashMap::new();
+        for occurrence in current.occurrences {
+            let index = if let Some(hybrid_id) = occurrence.hybrid_id {
+                let index = *hybrids.entry(hybrid_id).or_insert_with_key(|hybrid_id| {
+                    let index = network.add_vertex();
+                    network.set_hybrid_id(index, hybrid_id.clone());
+                    index
+                });
+                // Hybrid leaf
+                if let Some(label) = occurrence.label {
+                    network.set_label(index, label);
+                }
+                index
+            } else {
+                match occurrence.label {
+                    Some(label) => network.add_leaf(label),
+                    None => network.add_vertex(),
+                }
+            };
+            if let Some(internal_label) = occurrence.internal_label {
+                network.set_internal_label(index, internal_label);
+            }
+            vertex_of.push(index);
+        }
+
+        for (parent, child, branch_len, inheritance) in edges {
+            network.add_edge(
+                vertex_of[parent],
+                vertex_of[child],
+                branch_len.map(BranchLength::new_signed),
+                inheritance,
+            );
+        }
+        if let Some(root) = current.root {
+            network.set_root(vertex_of[root]);
+        }
+        for (key, occurrence, value) in current.annotations {
+            network.add_annotation(key, vertex_of[occurrence], value);
+        }
+        if let Some(name) = current.name {
+            network.set_name(name);
+        }
+
+        Some(network)
+    }
+}
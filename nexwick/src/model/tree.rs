@@ -9,9 +9,10 @@
 
 use crate::model::annotation::{AnnotationValue, Annotations};
 use crate::model::leaf_label_map::{LabelIndex, LeafLabelMap};
+use crate::model::tree_metadata::TreeMetadata;
 use crate::model::vertex::{BranchLength, Vertex};
 use crate::newick;
-use crate::newick::NewickStyle;
+use crate::newick::{AnnotationStyle, NewickStyle};
 
 /// Float comparison tolerance
 const EPSILON: f64 = 1e-7;
@@ -27,7 +28,7 @@ const NO_ROOT_SET_INDEX: VertexIndex = usize::MAX;
 // =#========================================================================#=
 // TREE
 // =#========================================================================$=
-/// A rooted binary phylogenetic tree represented using the arena pattern
+/// A rooted phylogenetic tree represented using the arena pattern
 /// on [Vertex].
 ///
 /// Vertices are stored in a contiguous vector and referenced by
@@ -40,12 +41,19 @@ const NO_ROOT_SET_INDEX: VertexIndex = usize::MAX;
 /// # Structure
 /// - All vertices (root, internal, and leaves) are stored in the arena.
 /// - Index of root is maintained.
+/// - Root and internal vertices have at least two children; trees are
+///   binary unless some vertex has more (a polytomy).
 /// - No assumption on order of indices is maintained.
 ///   (e.g. leaves must not be first `n` indices)
 /// - Leaves handle labels via their label reference type `L`,
 ///   e.g. implementation [CompactTree] pointing into a shared [LeafLabelMap].
-/// - Branch lengths are optional, but if provided must be non-negative.
+/// - Branch lengths are optional, but if provided must be non-negative
+///   (unless explicitly created as signed, see [BranchLength::new_signed]).
 /// - Annotations are stored in the tree, accessed via key and vertex index.
+/// - Tree-level [TreeMetadata] (e.g. rooted `[&R]`, weight `[&W 0.25]`)
+///   is stored in the tree.
+/// - Labels of internal vertices and the root (e.g. clade names or support
+///   values) are optional and stored in the tree, accessed via vertex index.
 ///
 /// # Construction
 /// To construct a tree, specify its size based on the number of leaves,
@@ -68,6 +76,16 @@ pub struct GenTree<L> {
 
     /// Annotation data
     annotations: Option<Box<Annotations>>,
+
+    /// Annotation data of branches, indexed by the child vertex of a branch
+    branch_annotations: Option<Box<Annotations>>,
+
+    /// Tree-level metadata, e.g. whether rooted and weight
+    metadata: TreeMetadata,
+
+    /// Labels of non-leaf vertices (e.g. support values), parallel to arena;
+    /// only allocated once the first such label is set
+    internal_labels: Option<Vec<Option<String>>>,
 }
 
 // Convenient type aliases
@@ -83,8 +101,10 @@ pub type SimpleTree = GenTree<String>;
 impl<L> GenTree<L> {
     /// Creates a new tree with capacity for a binary tree with `num_leaves` leaves.
     ///
+    /// Trees with polytomies have fewer vertices and thus fit as well.
+    ///
     /// # Arguments
-    /// `num_leaves` - number of leaves of the new binary tree, implying number of vertices; must be positive
+    /// `num_leaves` - number of leaves of the new tree, implying (maximum) number of vertices; must be positive
     pub fn new(num_leaves: usize) -> Self {
         assert!(num_leaves > 0);
         let capacity = 2 * num_leaves - 1;
@@ -94,6 +114,9 @@ impl<L> GenTree<L> {
             root_index: NO_ROOT_SET_INDEX,
             vertices: Vec::with_capacity(capacity),
             annotations: None,
+            branch_annotations: None,
+            metadata: TreeMetadata::default(),
+            internal_labels: None,
         }
     }
 
@@ -106,23 +129,24 @@ impl<L> GenTree<L> {
     /// Adds a root to the tree, assigning a unique index, which gets returned.
     ///
     /// # Arguments
-    /// * `children` - Tuple of child indices
+    /// * `children` - List of child indices
     /// * `branch_length` - Optional length of incoming edge (for special cases, non-negative)
     ///
     /// # Returns
     /// The index of the newly created root vertex.
     pub fn add_root(
         &mut self,
-        children: (VertexIndex, VertexIndex),
+        children: Vec<VertexIndex>,
         branch_length: Option<BranchLength>,
     ) -> VertexIndex {
         let index = self.vertices.len();
+        for &child in &children {
+            self[child].set_parent(index);
+        }
         self.vertices
             .push(Vertex::new_root(index, children, branch_length));
 
         self.root_index = index;
-        self[children.0].set_parent(index);
-        self[children.1].set_parent(index);
 
         index
     }
@@ -130,37 +154,34 @@ impl<L> GenTree<L> {
     /// Adds a root to the tree, assigning a unique index, which gets returned.
     ///
     /// # Arguments
-    /// * `children` - Tuple of child indices
+    /// * `children` - List of child indices
     ///
     /// # Returns
     /// The index of the newly created root vertex.
-    pub fn add_root_without_branch(&mut self, children: (VertexIndex, VertexIndex)) -> VertexIndex {
+    pub fn add_root_without_branch(&mut self, children: Vec<VertexIndex>) -> VertexIndex {
         self.add_root(children, None)
     }
 
     /// Adds an internal vertex to the tree, assigning a unique index, which gets returned.
     ///
     /// # Arguments
-    /// * `children` - Tuple of child indices
+    /// * `children` - List of child indices
     /// * `branch_length` - Length of incoming branch, i.e. distance to parent (non-negative)
     ///
     /// # Returns
     /// The index of the newly created internal vertex.
-    ///
-    /// # Panics
-    /// Panics if `branch_length` is negative.
     pub fn add_internal_vertex(
         &mut self,
-        children: (VertexIndex, VertexIndex),
+        children: Vec<VertexIndex>,
         branch_length: Option<BranchLength>,
     ) -> VertexIndex {
         let index = self.vertices.len();
+        for &child in &children {
+            self[child].set_parent(index);
+        }
         self.vertices
             .push(Vertex::new_internal(index, children, branch_length));
 
-        self[children.0].set_parent(index);
-        self[children.1].set_parent(index);
-
         index
     }
 
@@ -172,9 +193,6 @@ impl<L> GenTree<L> {
     ///
     /// # Returns
     /// The index of the newly created leaf vertex.
-    ///
-    /// # Panics
-    /// Panics if `branch_length` is negative.
     pub fn add_leaf(&mut self, branch_length: Option<BranchLength>, label: L) -> usize {
         let index = self.vertices.len();
         self.vertices
@@ -224,6 +242,112 @@ impl<L> GenTree<L> {
         self.annotations.is_some()
     }
 
+    /// Adds an annotation value for the branch from a vertex to its parent,
+    /// e.g. a per-branch rate as in `A:[&rate=0.9]0.12`.
+    ///
+    /// # Arguments
+    /// * `key` - Annotation name (e.g. "rate", "prob")
+    /// * `vertex_index` - Index of the child vertex of the branch
+    /// * `value` - The [AnnotationValue] to store
+    ///
+    /// # Panics
+    /// Panics if `vertex_index` is out of bounds.
+    pub fn add_branch_annotation(
+        &mut self,
+        key: String,
+        vertex_index: VertexIndex,
+        value: AnnotationValue,
+    ) {
+        let branch_annotations = self.branch_annotations.get_or_insert_with(|| {
+            let num_vertices = self.vertices.capacity().max(self.vertices.len());
+            Box::new(Annotations::new(num_vertices))
+        });
+        branch_annotations.add(key, vertex_index, value);
+    }
+
+    /// Returns a reference to the branch annotations, if any;
+    /// indexed by the child vertex of each branch.
+    pub fn branch_annotations(&self) -> Option<&Annotations> {
+        self.branch_annotations.as_deref()
+    }
+
+    /// Returns a single annotation value for the branch from a vertex to its parent.
+    pub fn branch_annotation(
+        &self,
+        key: &str,
+        vertex_index: VertexIndex,
+    ) -> Option<AnnotationValue> {
+        self.branch_annotations.as_deref()?.get(key, vertex_index)
+    }
+
+    /// Returns the tree-level [TreeMetadata], e.g. whether this tree is
+    /// rooted (`[&R]`) and its weight (`[&W 0.25]`).
+    pub fn metadata(&self) -> &TreeMetadata {
+        &self.metadata
+    }
+
+    /// Returns a mutable reference to the tree-level [TreeMetadata].
+    pub fn metadata_mut(&mut self) -> &mut TreeMetadata {
+        &mut self.metadata
+    }
+
+    /// Returns whether this tree is rooted, i.e. not explicitly marked as
+    /// unrooted in its [TreeMetadata] (e.g. by `[&U]` or a basal trifurcation).
+    pub fn is_rooted(&self) -> bool {
+        self.metadata.rooted() != Some(false)
+    }
+
+    /// Sets the tree-level [TreeMetadata].
+    pub fn set_metadata(&mut self, metadata: TreeMetadata) {
+        self.metadata = metadata;
+    }
+
+    /// Returns whether this tree has branch annotations.
+    pub fn has_branch_annotations(&self) -> bool {
+        self.branch_annotations.is_some()
+    }
+
+    /// Sets the label of a root or internal vertex,
+    /// e.g. a clade name or a support value such as `95` or `80/95`.
+    ///
+    /// # Arguments
+    /// * `vertex_index` - Index of the (non-leaf) vertex to label
+    /// * `label` - The label to store
+    pub fn set_internal_label(&mut self, vertex_index: VertexIndex, label: String) {
+        let labels = self.internal_labels.get_or_insert_with(Vec::new);
+        if vertex_index >= labels.len() {
+            let num_vertices = self.vertices.capacity().max(vertex_index + 1);
+            labels.resize(num_vertices, None);
+        }
+        labels[vertex_index] = Some(label);
+    }
+
+    /// Returns the label of a root or internal vertex, if it has one.
+    pub fn internal_label(&self, vertex_index: VertexIndex) -> Option<&str> {
+        self.internal_labels.as_ref()?.get(vertex_index)?.as_deref()
+    }
+
+    /// Returns whether any root or internal vertex of this tree has a label.
+    pub fn has_internal_labels(&self) -> bool {
+        self.internal_labels.is_some()
+    }
+
+    /// Returns the support values of a vertex by interpreting its internal
+    /// label as a number (e.g. `95`) or as slash-separated numbers
+    /// (e.g. `80/95` for SH-aLRT/UFBoot support).
+    ///
+    /// # Returns
+    /// `None` if the vertex has no label or the label is not numeric.
+    pub fn support_values(&self, vertex_index: VertexIndex) -> Option<Vec<f64>> {
+        parse_support_values(self.internal_label(vertex_index)?)
+    }
+
+    /// Returns the (first) support value of a vertex, see
+    /// [support_values()](Self::support_values).
+    pub fn support(&self, vertex_index: VertexIndex) -> Option<f64> {
+        self.support_values(vertex_index)?.first().copied()
+    }
+
     /// Returns reference to name of this tree, or `None` if not set.
     pub fn name(&self) -> Option<&String> {
         self.name.as_ref()
@@ -310,6 +434,14 @@ impl<L> GenTree<L> {
         self.vertices.len()
     }
 
+    /// Returns whether this tree is binary,
+    /// i.e. the root and all internal vertices have exactly two children.
+    pub fn is_binary(&self) -> bool {
+        self.vertices
+            .iter()
+            .all(|v| v.is_leaf() || v.num_children() == 2)
+    }
+
     /// Returns the height of this tree (assuming it is ultrametric; undefined otherwise),
     /// that is, the distance of the root to any/each leaf.
     pub fn height(&self) -> f64 {
@@ -330,7 +462,7 @@ impl<L> GenTree<L> {
                 break;
             }
 
-            let child_index = current_vertex.children().unwrap().0;
+            let child_index = current_vertex.children().unwrap()[0];
             current_vertex = &self.vertices[child_index];
             height += *current_vertex.branch_length().unwrap();
         }
@@ -355,16 +487,18 @@ impl<L> GenTree<L> {
             if vertex.is_leaf() {
                 distances[vertex.index()] = *vertex.branch_length().unwrap();
             } else {
-                let (left, right) = vertex.children().unwrap();
-                let left_dist: f64 = distances[left];
-                let right_dist: f64 = distances[right];
+                let children = vertex.children().unwrap();
+                let first_dist: f64 = distances[children[0]];
 
-                if (left_dist - right_dist).abs() > EPSILON {
+                if children[1..]
+                    .iter()
+                    .any(|&child| (first_dist - distances[child]).abs() > EPSILON)
+                {
                     return false;
                 }
 
                 if !vertex.is_root() {
-                    distances[vertex.index()] = left_dist + *vertex.branch_length().unwrap();
+                    distances[vertex.index()] = first_dist + *vertex.branch_length().unwrap();
                 }
             }
         }
@@ -406,6 +540,8 @@ impl<L: ValidLabel> GenTree<L> {
     /// - All child indices are valid and point back to correct parent
     /// - All parent indices are valid and include this vertex as a child
     /// - Root vertex has no parent set, all others have valid parent set
+    /// - Root and internal vertices have at least two children
+    /// - All vertices are reachable from the root (no cycles)
     ///
     /// # Returns
     /// `true` if tree is valid, `false` otherwise
@@ -425,8 +561,8 @@ impl<L: ValidLabel> GenTree<L> {
             return false;
         }
 
-        let mut leaf_count = 0;
         let mut found_root = false;
+        let mut num_edges = 0;
 
         // Validate each vertex
         for (index, vertex) in self.vertices.iter().enumerate() {
@@ -444,24 +580,24 @@ impl<L: ValidLabel> GenTree<L> {
                 }
             }
 
-            // Check that there are not too many leaves
-            if vertex.is_leaf() {
-                leaf_count += 1;
-            }
-
             // Check children references
-            if let Some((left, right)) = vertex.children() {
-                // Check child indices are in bounds
-                if left >= self.vertices.len() || right >= self.vertices.len() {
+            if let Some(children) = vertex.children() {
+                // Check there are at least two children
+                if children.len() < 2 {
                     return false;
                 }
+                num_edges += children.len();
 
-                // Check children point back to this vertex as parent
-                let left_parent = self.vertices[left].parent();
-                let right_parent = self.vertices[right].parent();
+                for &child in children {
+                    // Check child index is in bounds
+                    if child >= self.vertices.len() {
+                        return false;
+                    }
 
-                if left_parent != Some(index) || right_parent != Some(index) {
-                    return false;
+                    // Check child points back to this vertex as parent
+                    if self.vertices[child].parent() != Some(index) {
+                        return false;
+                    }
                 }
             }
 
@@ -482,8 +618,8 @@ impl<L: ValidLabel> GenTree<L> {
                         }
 
                         // Check parent includes this vertex in its children
-                        if let Some((left, right)) = self.vertices[parent_index].children() {
-                            if left != index && right != index {
+                        if let Some(children) = self.vertices[parent_index].children() {
+                            if !children.contains(&index) {
                                 return false;
                             }
                         } else {
@@ -503,14 +639,28 @@ impl<L: ValidLabel> GenTree<L> {
             }
         }
 
-        // Check leaf count matches binary tree invariant:
-        // for n leaves, there are 2n-1 vertices
-        let expected_leaf_count = self.vertices.len().div_ceil(2);
-        if leaf_count != expected_leaf_count {
+        // Check each non-root vertex is listed exactly once as a child:
+        // a tree on m vertices has m-1 edges
+        if num_edges + 1 != self.vertices.len() {
             return false;
         }
 
-        true
+        // Check all vertices are reachable from the root
+        let mut visited = vec![false; self.vertices.len()];
+        let mut stack = vec![self.root_index];
+        let mut num_reached = 0;
+        while let Some(index) = stack.pop() {
+            if visited[index] {
+                return false;
+            }
+            visited[index] = true;
+            num_reached += 1;
+            if let Some(children) = self.vertices[index].children() {
+                stack.extend_from_slice(children);
+            }
+        }
+
+        num_reached == self.vertices.len()
     }
 }
 
@@ -528,6 +678,24 @@ impl<L> std::ops::IndexMut<VertexIndex> for GenTree<L> {
     }
 }
 
+// ============================================================================
+// Relabelling (pub, only for CompactTree)
+// ============================================================================
+impl CompactTree {
+    /// Replaces the [LabelIndex] `i` of each leaf by `mapping[i]`, e.g. to
+    /// refer to another [LeafLabelMap] with the same labels in different order.
+    ///
+    /// # Panics
+    /// Panics if a label index of a leaf is out of bounds of `mapping`.
+    pub fn remap_labels(&mut self, mapping: &[LabelIndex]) {
+        for vertex in &mut self.vertices {
+            if let Vertex::Leaf { label, .. } = vertex {
+                *label = mapping[*label];
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Printing (pub, only for CompactTree)
 // ============================================================================
@@ -537,6 +705,17 @@ impl CompactTree {
         newick::to_newick(style, self, leaf_label_map)
     }
 
+    /// Convenience method to convert this tree to a Newick string
+    /// including annotations in the given [AnnotationStyle]
+    pub fn to_newick_with_annotations(
+        &self,
+        style: &NewickStyle,
+        annotation_style: &AnnotationStyle,
+        leaf_label_map: Option<&LeafLabelMap>,
+    ) -> String {
+        newick::to_newick_with_annotations(style, annotation_style, self, leaf_label_map)
+    }
+
     /// Prints a visual representation of the tree to the console.
     ///
     /// # Arguments
@@ -617,15 +796,17 @@ impl CompactTree {
             println!("{}{}[{}] Internal {}", prefix, connector, idx, branch_str);
 
             // Print children if they exist
-            if let Some((left, right)) = vertex.children() {
+            if let Some(children) = vertex.children() {
                 let new_prefix = if prefix.is_empty() {
                     "  ".to_string()
                 } else {
                     format!("{}{}  ", prefix, if is_last { " " } else { "│" })
                 };
 
-                self.print_vertex(left, &new_prefix, false, label_map);
-                self.print_vertex(right, &new_prefix, true, label_map);
+                let last = children.len() - 1;
+                for (i, &child) in children.iter().enumerate() {
+                    self.print_vertex(child, &new_prefix, i == last, label_map);
+                }
             }
         }
     }
@@ -650,7 +831,7 @@ impl<L> GenTree<L> {
     /// let mut labels = LeafLabelMap::new(2);
     /// let a = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("A"));
     /// let b = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("B"));
-    /// tree.add_root_without_branch((a, b));
+    /// tree.add_root_without_branch(vec![a, b]);
     ///
     /// let indices: Vec<_> = tree.post_order_iter().map(|v| v.index()).collect();
     /// // Leaves come before root
@@ -674,7 +855,7 @@ impl<L> GenTree<L> {
     /// let mut labels = LeafLabelMap::new(2);
     /// let a = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("A"));
     /// let b = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("B"));
-    /// tree.add_root_without_branch((a, b));
+    /// tree.add_root_without_branch(vec![a, b]);
     ///
     /// let indices: Vec<_> = tree.pre_order_iter().map(|v| v.index()).collect();
     /// // Root comes before leaves
@@ -717,10 +898,10 @@ impl<'a, L> Iterator for PostOrderIter<'a, L> {
                 // Mark this vertex as "children will be visited"
                 self.stack.push((index, true));
 
-                // Push children (right first, so left is processed first)
-                if let Some((left, right)) = vertex.children() {
-                    self.stack.push((right, false));
-                    self.stack.push((left, false));
+                // Push children in reverse, so leftmost is processed first
+                if let Some(children) = vertex.children() {
+                    self.stack
+                        .extend(children.iter().rev().map(|&child| (child, false)));
                 }
             }
         }
@@ -754,16 +935,24 @@ impl<'a, L> Iterator for PreOrderIter<'a, L> {
         let index = self.stack.pop()?;
         let vertex = &self.tree[index];
 
-        // Push children onto stack (right first, so left is processed first)
-        if let Some((left, right)) = vertex.children() {
-            self.stack.push(right);
-            self.stack.push(left);
+        // Push children onto stack in reverse, so leftmost is processed first
+        if let Some(children) = vertex.children() {
+            self.stack.extend(children.iter().rev());
         }
 
         Some(vertex)
     }
 }
 
+/// Parses a label such as `95` or `80/95` into its support values;
+/// returns `None` if any slash-separated part is not a number.
+fn parse_support_values(label: &str) -> Option<Vec<f64>> {
+    label
+        .split('/')
+        .map(|part| part.trim().parse::<f64>().ok())
+        .collect()
+}
+
 // =#========================================================================#=
 // VALID LABEL TRAIT
 // =#========================================================================T=
@@ -21,9 +21,16 @@
 //! - `LabelStorage` translates String label into a label (reference) `label_ref`
 //! - `TreeBuilder::add_leaf(branch_len, label_ref) `
 //!
+//! # Branch lengths
+//! Branch lengths are checked by the parser according to its
+//! [BranchLengthPolicy](crate::newick::BranchLengthPolicy) before being
+//! passed on, so they might be negative (or even NaN or infinite) if the
+//! parser is configured to keep them; builders store them as given.
+//!
 //! # Built-in implementations
 //! * [CompactTreeBuilder] - Builds [CompactTree] with labels stored in a shared [LeafLabelMap]
 //! * [SimpleTreeBuilder] - Builds [SimpleTree] with labels (copies) stored directly in leaves
+//! * [NetworkBuilder] - Builds [GenNetwork](crate::model::GenNetwork)s from extended Newick
 //!
 //! # Custom implementations
 //! You can implement [TreeBuilder] to construct your own tree representation,
@@ -42,9 +49,11 @@
 // Imports for doc links
 use crate::model::annotation::AnnotationValue;
 use crate::model::label_storage::LabelStorage;
+use crate::model::tree_metadata::TreeMetadata;
 #[allow(unused_imports)]
 use crate::model::{
-    CompactTree, CompactTreeBuilder, LabelResolver, LeafLabelMap, SimpleTree, SimpleTreeBuilder,
+    CompactTree, CompactTreeBuilder, LabelResolver, LeafLabelMap, NetworkBuilder, SimpleTree,
+    SimpleTreeBuilder,
 };
 
 // =#========================================================================#=
@@ -134,23 +143,25 @@ pub trait TreeBuilder {
     /// The vertex index of the new leaf.
     fn add_leaf(&mut self, branch_len: Option<f64>, label: Self::LabelRef) -> Self::VertexIdx;
 
-    /// Adds an internal (non-root) vertex with two children.
+    /// Adds an internal (non-root) vertex with two or more children.
     ///
     /// Called when the parser encounters an internal vertex.
-    /// The children are vertex indices returned by previous `add_*` calls.
+    /// The children are vertex indices returned by previous `add_*` calls,
+    /// in the order they appear in the Newick string; more than two children
+    /// represent a polytomy.
     /// Returns a vertex index that the parser will later pass to
     /// [`add_internal`](Self::add_internal) or [`add_root`](Self::add_root)
     /// to establish parent-child relationships.
     ///
     /// # Arguments
-    /// * `children` — Indices of the left and right child vertices
+    /// * `children` — Indices of the child vertices (at least two)
     /// * `branch_len` — Branch length to parent, if specified in Newick string
     ///
     /// # Returns
     /// The vertex index of the new internal vertex.
     fn add_internal(
         &mut self,
-        children: (Self::VertexIdx, Self::VertexIdx),
+        children: Vec<Self::VertexIdx>,
         branch_len: Option<f64>,
     ) -> Self::VertexIdx;
 
@@ -161,14 +172,14 @@ pub trait TreeBuilder {
     /// [`set_name`](Self::set_name) and [`finish_tree`](Self::finish_tree) remain.
     ///
     /// # Arguments
-    /// * `children` — Indices of the root's two child vertices
+    /// * `children` — Indices of the root's child vertices (at least two)
     /// * `branch_len` — Root branch length (rare, but allowed in Newick)
     ///
     /// # Returns
     /// The vertex index of the root.
     fn add_root(
         &mut self,
-        children: (Self::VertexIdx, Self::VertexIdx),
+        children: Vec<Self::VertexIdx>,
         branch_len: Option<f64>,
     ) -> Self::VertexIdx;
 
@@ -180,6 +191,80 @@ pub trait TreeBuilder {
     /// * `value` - The [AnnotationValue] to store
     fn add_annotation(&mut self, key: String, vertex_idx: Self::VertexIdx, value: AnnotationValue);
 
+    /// Adds an annotation for the branch from a vertex to its parent.
+    /// Default implementation ignores it.
+    ///
+    /// # Arguments
+    /// * `key` - Annotation name (e.g. "rate", "prob")
+    /// * `vertex_idx` - Index of the child vertex of the branch
+    /// * `value` - The [AnnotationValue] to store
+    fn add_branch_annotation(
+        &mut self,
+        _key: String,
+        _vertex_idx: Self::VertexIdx,
+        _value: AnnotationValue,
+    ) {
+    }
+
+    /// Sets tree-level metadata, e.g. whether the tree is rooted (`[&R]`)
+    /// and its weight (`[&W 0.25]`). Default implementation ignores it.
+    ///
+    /// # Arguments
+    /// * `metadata` - The [TreeMetadata] found before the Newick string
+    fn set_metadata(&mut self, _metadata: TreeMetadata) {}
+
+    /// Sets the label of a root or internal vertex, e.g. a clade name or a
+    /// support value. Default implementation ignores it.
+    ///
+    /// # Arguments
+    /// * `vertex_idx` - Index of the (non-leaf) vertex to label
+    /// * `label` - The label as found in the Newick string (unquoted)
+    fn add_internal_label(&mut self, _vertex_idx: Self::VertexIdx, _label: String) {}
+
+    /// Returns whether this builder constructs phylogenetic networks, i.e.
+    /// supports hybrid vertices via [`add_hybrid`](Self::add_hybrid).
+    ///
+    /// Default implementation returns `false`, in which case parsers treat
+    /// `#` in labels verbatim instead of as extended Newick hybrid marker.
+    fn supports_hybrids(&self) -> bool {
+        false
+    }
+
+    /// Adds an occurrence of a hybrid vertex of a phylogenetic network,
+    /// written `[label]#H1` in extended Newick (eNewick).
+    ///
+    /// A hybrid vertex occurs once per parent in the Newick string, at most
+    /// once with its children, e.g. `((A,(B)#H1),(#H1,C));`. Each occurrence
+    /// gets its own index, as branch length and inheritance probability
+    /// belong to the edge from its respective parent; implementations merge
+    /// occurrences with the same `hybrid_id`.
+    ///
+    /// Only called by parsers if [`supports_hybrids`](Self::supports_hybrids)
+    /// returns `true`.
+    ///
+    /// # Arguments
+    /// * `hybrid_id` - Identifier of the hybrid without `#`, e.g. `H1`
+    /// * `children` - Indices of the child vertices of this occurrence (might be empty)
+    /// * `label` - Label reference if the hybrid is a leaf (label before `#`)
+    /// * `branch_len` - Branch length to parent of this occurrence
+    /// * `inheritance` - Inheritance probability (γ) of the edge to parent of this occurrence
+    ///
+    /// # Returns
+    /// The vertex index of this occurrence.
+    ///
+    /// # Panics
+    /// Default implementation panics, as trees have no hybrid vertices.
+    fn add_hybrid(
+        &mut self,
+        _hybrid_id: String,
+        _children: Vec<Self::VertexIdx>,
+        _label: Option<Self::LabelRef>,
+        _branch_len: Option<f64>,
+        _inheritance: Option<f64>,
+    ) -> Self::VertexIdx {
+        panic!("Tree builder does not support hybrid vertices")
+    }
+
     /// Sets the name of the currently constructed tree.
     ///
     ///
@@ -0,0 +1,185 @@
+//! Provides [TreeMetadata] for tree-level information.
+//!
+//! Tree-level information is given in comments preceding a Newick string,
+//! e.g. in a Nexus `TREE` command `tree t1 = [&R] [&W 0.25] ((A,B),C);`:
+//! * `[&R]` / `[&U]` - whether the tree is rooted or unrooted
+//! * `[&W 0.25]` - weight of the tree (as written by PAUP* or MrBayes `sumt`)
+//! * `[&key=value,...]` - other annotations of the tree, e.g.
+//!   `[&lnP=-1234.5,posterior=-1300.2]` as written by BEAST
+//!
+//! In Nexus files, these comments may also precede the `=` of the `TREE`
+//! command, e.g. `tree STATE_1000 [&lnP=-1234.5] = ...`, and the MCMC state
+//! (sample or generation number) is taken from the tree name, see
+//! [TreeMetadata::state_from_name()].
+
+use crate::model::annotation::AnnotationValue;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Prefixes of tree names followed by the MCMC state, e.g. `STATE_1000`
+/// (BEAST) or `gen.1000` (MrBayes, older versions `rep.1000`)
+const STATE_PREFIXES: [&str; 3] = ["STATE_", "gen.", "rep."];
+
+/// Annotation keys of the log likelihood, e.g. `lnP` (BEAST) or `lnL`
+const LOG_LIKELIHOOD_KEYS: [&str; 3] = ["lnP", "lnL", "likelihood"];
+
+// =#========================================================================#=
+// TREE METADATA
+// =#========================================================================$=
+/// Tree-level metadata of a [GenTree](crate::model::GenTree); each entry
+/// is optional and `None` if not given.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeMetadata {
+    /// Whether tree is rooted (`[&R]`) or unrooted (`[&U]`)
+    rooted: Option<bool>,
+    /// Whether the basal polytomy of the unrooted tree has been rooted at
+    /// its first child when parsing (see `UnrootedTreePolicy::RootAtFirstChild`)
+    rooted_at_first_child: bool,
+    /// Weight of tree (`[&W 0.25]`)
+    weight: Option<f64>,
+    /// MCMC state, i.e. sample or generation number (e.g. of `STATE_1000`)
+    state: Option<u64>,
+    /// Other annotations (`[&lnP=-1234.5,posterior=-1300.2]`)
+    annotations: HashMap<String, AnnotationValue>,
+}
+
+impl TreeMetadata {
+    /// Creates new empty [TreeMetadata].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the tree is rooted (`[&R]`) or unrooted (`[&U]`),
+    /// or `None` if not specified.
+    pub fn rooted(&self) -> Option<bool> {
+        self.rooted
+    }
+
+    /// Sets whether the tree is rooted (`[&R]`) or unrooted (`[&U]`).
+    pub fn set_rooted(&mut self, rooted: bool) {
+        self.rooted = Some(rooted);
+    }
+
+    /// Returns whether the parser rooted the basal polytomy of this unrooted
+    /// tree at its first child, so that writers restore the polytomy, see
+    /// [UnrootedTreePolicy::RootAtFirstChild](crate::newick::UnrootedTreePolicy::RootAtFirstChild).
+    pub fn rooted_at_first_child(&self) -> bool {
+        self.rooted_at_first_child
+    }
+
+    /// Marks the tree as unrooted with its basal polytomy rooted at its first child.
+    pub fn set_rooted_at_first_child(&mut self) {
+        self.rooted = Some(false);
+        self.rooted_at_first_child = true;
+    }
+
+    /// Returns the weight of the tree (`[&W 0.25]`), or `None` if not specified.
+    pub fn weight(&self) -> Option<f64> {
+        self.weight
+    }
+
+    /// Sets the weight of the tree (`[&W 0.25]`).
+    pub fn set_weight(&mut self, weight: f64) {
+        self.weight = Some(weight);
+    }
+
+    /// Returns the MCMC state (sample or generation number) of the tree,
+    /// e.g. `1000` of tree `STATE_1000`, or `None` if not specified.
+    pub fn state(&self) -> Option<u64> {
+        self.state
+    }
+
+    /// Sets the MCMC state (sample or generation number) of the tree.
+    pub fn set_state(&mut self, state: u64) {
+        self.state = Some(state);
+    }
+
+    /// Returns all other annotations of the tree, e.g. `lnP` and `posterior`
+    /// of `[&lnP=-1234.5,posterior=-1300.2]`.
+    pub fn annotations(&self) -> &HashMap<String, AnnotationValue> {
+        &self.annotations
+    }
+
+    /// Returns the annotation with the given key (case-sensitive), if any.
+    pub fn annotation(&self, key: &str) -> Option<&AnnotationValue> {
+        self.annotations.get(key)
+    }
+
+    /// Sets the annotation with the given key, overwriting any previous value.
+    pub fn set_annotation(&mut self, key: String, value: AnnotationValue) {
+        self.annotations.insert(key, value);
+    }
+
+    /// Returns the log likelihood of the tree, given by annotation `lnP`
+    /// (BEAST), `lnL`, or `likelihood` (case-insensitive), if numeric.
+    pub fn log_likelihood(&self) -> Option<f64> {
+        LOG_LIKELIHOOD_KEYS.iter().find_map(|key| {
+            self.annotations
+                .iter()
+                .find(|(annotation_key, _)| annotation_key.eq_ignore_ascii_case(key))
+                .and_then(|(_, value)| value.as_f64())
+        })
+    }
+
+    /// Returns the log posterior of the tree, given by annotation
+    /// `posterior` (case-insensitive), if numeric.
+    pub fn posterior(&self) -> Option<f64> {
+        self.annotations
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("posterior"))
+            .and_then(|(_, value)| value.as_f64())
+    }
+
+    /// Returns whether no metadata is specified at all.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Parses the MCMC state from a tree name following common naming
+    /// conventions (case-insensitive), i.e. `STATE_<n>` (BEAST) and
+    /// `gen.<n>` or `rep.<n>` (MrBayes); `None` for any other name.
+    ///
+    /// # Example
+    /// ```
+    /// use nexwick::model::TreeMetadata;
+    ///
+    /// assert_eq!(TreeMetadata::state_from_name("STATE_120000"), Some(120000));
+    /// assert_eq!(TreeMetadata::state_from_name("gen.1000"), Some(1000));
+    /// assert_eq!(TreeMetadata::state_from_name("tree_1"), None);
+    /// ```
+    pub fn state_from_name(name: &str) -> Option<u64> {
+        STATE_PREFIXES.iter().find_map(|prefix| {
+            let (head, number) = name.split_at_checked(prefix.len())?;
+            if !head.eq_ignore_ascii_case(prefix) || !number.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            number.parse().ok()
+        })
+    }
+}
+
+/// Formats the metadata as Nexus tree comments, e.g.
+/// `[&R] [&W 0.25] [&lnP=-1234.5,posterior=-1300.2]` (with annotation keys
+/// in sorted order); empty if no metadata is specified. The state is not
+/// included, as it is given by the tree name.
+impl fmt::Display for TreeMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut comments = Vec::new();
+        if let Some(rooted) = self.rooted {
+            comments.push(if rooted { "[&R]" } else { "[&U]" }.to_string());
+        }
+        if let Some(weight) = self.weight {
+            comments.push(format!("[&W {}]", weight));
+        }
+        if !self.annotations.is_empty() {
+            let mut keys: Vec<&String> = self.annotations.keys().collect();
+            keys.sort();
+            let pairs: Vec<String> = keys
+                .into_iter()
+                .map(|key| format!("{}={}", key, self.annotations[key]))
+                .collect();
+            comments.push(format!("[&{}]", pairs.join(",")));
+        }
+        write!(f, "{}", comments.join(" "))
+    }
+}
@@ -17,37 +17,39 @@ const NO_PARENT_SET: VertexIndex = usize::MAX;
 /// Represents a vertex (node) in a phylogenetic tree.
 ///
 /// A vertex can be either:
-/// - **Root**: Has two children, no parent, but might have branch length
-///   (exists for special cases)
-/// - **Internal**: Has two children, no label, might have branch length
+/// - **Root**: Has two or more children, no parent, but might have branch
+///   length (exists for special cases)
+/// - **Internal**: Has two or more children, no label, might have branch length
 /// - **Leaf**: Has no children, has label (ref) and might have branch length
 ///
 /// # Invariants
 /// - `index` is index in arena; non-negative
-/// - `branch_length` is non-negative (enforced); might not be set
+/// - `branch_length` is non-negative (enforced unless created with
+///   [BranchLength::new_signed]); might not be set
 /// - Leaves and internal vertices have `parent` is `TreeIndex` of parent in
 ///   arena; `NO_PARENT_SET = usize::MAX` only during construction
-/// - Internal vertices have `children` as tuple of `TreeIndex`
+/// - Root and internal vertices have `children` as list of `TreeIndex`;
+///   more than two children represent a polytomy (multifurcation)
 /// - Leaf vertices have a `label_index`, since many trees share labels
 #[derive(PartialEq, Debug, Clone)]
 pub enum Vertex<L> {
-    /// Root vertex of the tree (has no parent, has two children)
+    /// Root vertex of the tree (has no parent, has two or more children)
     Root {
         /// Index of this vertex in the tree arena
         index: VertexIndex,
-        /// Indices of the two child vertices
-        children: (VertexIndex, VertexIndex),
+        /// Indices of the child vertices
+        children: Vec<VertexIndex>,
         /// Optional length of incoming edge (optional and only for special cases, non-negative if present)
         branch_length: Option<BranchLength>,
     },
-    /// Internal vertex (has parent and two children, no label)
+    /// Internal vertex (has parent and two or more children, no label)
     Internal {
         /// Index of this vertex in the tree arena
         index: VertexIndex,
         /// Index of the parent vertex
         parent: VertexIndex,
-        /// Indices of the two child vertices
-        children: (VertexIndex, VertexIndex),
+        /// Indices of the child vertices
+        children: Vec<VertexIndex>,
         /// Distance to parent node (optional, non-negative if present)
         branch_length: Option<BranchLength>,
     },
@@ -69,11 +71,11 @@ impl<L> Vertex<L> {
     ///
     /// # Arguments
     /// * `index` - The unique index of this vertex in the tree (arena)
-    /// * `children` - Tuple of child indices
+    /// * `children` - List of child indices
     /// * `branch_length` - Optional length of incoming edge (for special cases)
     pub fn new_root(
         index: VertexIndex,
-        children: (VertexIndex, VertexIndex),
+        children: Vec<VertexIndex>,
         branch_length: Option<BranchLength>,
     ) -> Self {
         Vertex::Root {
@@ -87,11 +89,8 @@ impl<L> Vertex<L> {
     ///
     /// # Arguments
     /// * `index` - The unique index of this vertex in the tree (arena)
-    /// * `children` - Tuple of child indices
-    pub fn new_root_without_branch(
-        index: VertexIndex,
-        children: (VertexIndex, VertexIndex),
-    ) -> Self {
+    /// * `children` - List of child indices
+    pub fn new_root_without_branch(index: VertexIndex, children: Vec<VertexIndex>) -> Self {
         Vertex::Root {
             index,
             children,
@@ -103,11 +102,11 @@ impl<L> Vertex<L> {
     ///
     /// # Arguments
     /// * `index` - The unique index of this vertex in the tree (arena)
-    /// * `children` - Tuple of child indices
+    /// * `children` - List of child indices
     /// * `branch_length` - Distance to parent node (non-negative)
     pub fn new_internal(
         index: VertexIndex,
-        children: (VertexIndex, VertexIndex),
+        children: Vec<VertexIndex>,
         branch_length: Option<BranchLength>,
     ) -> Self {
         Vertex::Internal {
@@ -179,14 +178,19 @@ impl<L> Vertex<L> {
     }
 
     /// Returns indices of the children if this vertex has any, else `None`.
-    pub fn children(&self) -> Option<(usize, usize)> {
+    pub fn children(&self) -> Option<&[VertexIndex]> {
         match self {
-            Vertex::Root { children, .. } => Some(*children),
-            Vertex::Internal { children, .. } => Some(*children),
+            Vertex::Root { children, .. } => Some(children),
+            Vertex::Internal { children, .. } => Some(children),
             Vertex::Leaf { .. } => None,
         }
     }
 
+    /// Returns the number of children of this vertex (`0` for a leaf).
+    pub fn num_children(&self) -> usize {
+        self.children().map_or(0, |children| children.len())
+    }
+
     /// Returns `true` if this vertex is a root.
     pub fn is_root(&self) -> bool {
         matches!(self, Vertex::Root { .. })
@@ -241,8 +245,8 @@ impl<L: fmt::Display> fmt::Display for Vertex<L> {
             } => {
                 write!(
                     f,
-                    "Root(idx: {}, children: [{}, {}], len: {:?})",
-                    index, children.0, children.1, branch_length
+                    "Root(idx: {}, children: {:?}, len: {:?})",
+                    index, children, branch_length
                 )
             }
             Vertex::Internal {
@@ -253,8 +257,8 @@ impl<L: fmt::Display> fmt::Display for Vertex<L> {
             } => {
                 write!(
                     f,
-                    "Internal(idx: {}, parent: {}, children: [{}, {}], len: {:?})",
-                    index, parent, children.0, children.1, branch_length
+                    "Internal(idx: {}, parent: {}, children: {:?}, len: {:?})",
+                    index, parent, children, branch_length
                 )
             }
             Vertex::Leaf {
@@ -279,7 +283,9 @@ impl<L: fmt::Display> fmt::Display for Vertex<L> {
 /// Branch length in a phylogenetic tree, enforced non-negative.
 ///
 /// Represents the evolutionary distance between a vertex and its parent.
-/// The value is guaranteed to be non-negative and finite.
+/// The value is guaranteed to be non-negative and finite, unless created
+/// with [new_signed()](Self::new_signed), e.g. for negative lengths of
+/// neighbour-joining trees.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BranchLength(f64);
 
@@ -304,6 +310,15 @@ impl BranchLength {
         );
         BranchLength(length)
     }
+
+    /// Creates a new branch length without enforcing it to be non-negative
+    /// and finite, e.g. for negative lengths of neighbour-joining trees.
+    ///
+    /// # Arguments
+    /// * `length` - The branch length value (any sign, might be NaN or infinite)
+    pub fn new_signed(length: f64) -> Self {
+        BranchLength(length)
+    }
 }
 
 impl Deref for BranchLength {
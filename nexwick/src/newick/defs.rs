@@ -6,5 +6,8 @@
 /// Newick label delimiters: parentheses, comma, colon, semicolon, whitespace
 pub(crate) const NEWICK_LABEL_DELIMITERS: &[u8] = b"([,:; \n\t\r)]";
 
+/// Marker of NHX annotation blocks `[&&NHX:key=value:...]`, following `[&`
+pub(crate) const NHX_MARKER: &[u8] = b"&NHX";
+
 /// Default guess for number of leaves, when unknown
 pub(crate) const DEFAULT_NUM_LEAVES_GUESS: usize = 10;
@@ -9,6 +9,7 @@
 //! # Quick API
 //! For simple use cases with default settings:
 //! * [`parse_file`] - parses a file, returns [CompactTree]s + [LeafLabelMap]
+//! * [`parse_reader`] - parses trees read from a reader (e.g. stdin), same returns
 //! * [`parse_str`] - parses a single string, returns a [SimpleTree]
 //!
 //! # Full API
@@ -17,12 +18,14 @@
 //! * [`NewickParser::parse_str`] - parse a single tree
 //! * [`NewickParser::parse_all`] - parse all trees until EOF
 //! * [`NewickParser::into_iter`] - obtain an iterator over trees
+//! * [`NewickParser::parse_all_from_reader`] and
+//!   [`NewickParser::into_iter_from_reader`] - the same reading from a reader
 //!
 //! # Format
 //! The Newick format has the following simple grammar:
 //! * `tree ::= vertex ';'`
 //! * `vertex ::= leaf | internal_vertex`
-//! * `internal_vertex ::= '(' vertex ',' vertex ')' [branch_length]`
+//! * `internal_vertex ::= '(' vertex ',' vertex {',' vertex} ')' [label] [branch_length]`
 //! * `leaf ::= label [branch_length]`
 //! * `branch_length ::= ':' number`
 //!
@@ -31,9 +34,18 @@
 //!   just not within an unquoted label or a branch_length
 //! * Even newlines can occur anywhere except in labels (quoted and unquoted)
 //! * Comments are square brackets and can occur anywhere where newlines are allowed
+//! * Vertices with more than two children (polytomies) are supported
+//! * Internal vertices and the root may have a label, e.g. a clade name
+//!   `((A,B)Clade_X,C);` or a support value `((A,B)95:0.1,C);` or `80/95`,
+//!   see [GenTree::support_values](crate::model::GenTree::support_values)
+//! * Unrooted trees are commonly written with a basal trifurcation,
+//!   e.g. `(A,B,(C,D));`, and marked unrooted (`[&U]`) unless explicitly
+//!   rooted (`[&R]`); see [UnrootedTreePolicy] to resolve them into a binary root
 //!
 //! In the extended Newick format, there can be comment-like annotation:
 //! * `[@pop_size=0.543,color=blue]`
+//! * Values may be lists and quoted strings, e.g.
+//!   `[&height_95%_HPD={1.2,3.4},name="a,b"]`
 //!
 //! For a leaf:
 //! * label \[annotation\] \[branch_length\]
@@ -43,18 +55,48 @@
 //! * (children) \[annotation\] \[branch_length\]
 //!   - Example: (A,B\[@pop_size=0.345\]:6.7
 //!
+//! In the NHX format, annotations are separated by colons and commonly
+//! follow the branch length:
+//! * `[&&NHX:S=human:D=N:B=95]`
+//!   - Example: A:2.1\[&&NHX:S=human\]
+//!
+//! Annotations of the branch (MrBayes style) can occur after the colon,
+//! either before or after the branch length:
+//! * label \[annotation\] : \[branch_annotation\] branch_length \[branch_annotation\]
+//!   - Example: A:\[&rate=0.9\]0.12 or A:0.12\[&rate=0.9\]
+//!   - Kept separately or merged into vertex annotations,
+//!     see [BranchAnnotationPolicy]
+//!
 //! By default, they are not parsed, considered comments and thus skipped.
+//! Use [`to_newick_with_annotations`] to write them in either style.
+//!
+//! # Networks
+//! Phylogenetic networks in extended Newick (eNewick) are parsed when
+//! the parser uses a [NetworkBuilder](crate::model::NetworkBuilder)
+//! (see [`parse_network_str`]) and written with [`to_enewick`]:
+//! * `hybrid ::= [label] '#' id [':' [branch_length] [':' [support] [':' inheritance]]]`
+//!   - Example: `((A,(B)#H1:0.5::0.3),(#H1:0.2::0.7,C));`
+//! * A hybrid vertex occurs once per parent (at least twice), exactly once
+//!   with its children (or, for a hybrid leaf, its label)
+//! * The support value of a hybrid edge is ignored
 
 mod defs;
 pub mod parser;
 pub mod writer;
 
-pub use parser::{NewickIterator, NewickParser};
-pub use writer::{NewickStyle, to_newick, write_newick_file};
+pub use parser::{
+    BranchAnnotationPolicy, BranchLengthPolicy, NewickIterator, NewickParser, UnrootedTreePolicy,
+};
+pub use writer::{
+    AnnotationStyle, NewickStyle, to_enewick, to_newick, to_newick_with_annotations,
+    write_newick_file,
+};
 
-use crate::model::{CompactTree, LeafLabelMap, SimpleTree};
+use crate::model::{CompactTree, LeafLabelMap, SimpleNetwork, SimpleNetworkBuilder, SimpleTree};
 use crate::parser::ParsingError;
 use crate::parser::byte_parser::ByteParser;
+use crate::parser::decompressing_byte_source::Compression;
+use std::io::Read;
 use std::path::Path;
 
 // ============================================================================
@@ -69,7 +111,8 @@ use std::path::Path;
 ///
 /// # Arguments
 /// * `path` - Path to the file (accepting `&str`, `String`, `Path`, or `PathBuf`)
-///   with semicolon-separated list of Newick strings
+///   with semicolon-separated list of Newick strings, possibly compressed
+///   (gzip, zstd, or bzip2 with the respective cargo feature enabled)
 ///
 /// # Returns
 /// * `(Vec<CompactTree>, LeafLabelMap)` - All parsed trees and their shared label mapping
@@ -92,12 +135,40 @@ use std::path::Path;
 pub fn parse_file<P: AsRef<Path>>(
     path: P,
 ) -> Result<(Vec<CompactTree>, LeafLabelMap), ParsingError> {
-    // Set up byte parser
-    let byte_parser = ByteParser::from_file_buffered(path)?;
+    // Parse all trees (streaming compressed files through a decompressor)
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let trees = if Compression::detect(&path)?.is_some() {
+        newick_parser.parse_all(ByteParser::from_file_decompressing(path)?)?
+    } else {
+        newick_parser.parse_all(ByteParser::from_file_buffered(path)?)?
+    };
+    let label_map = newick_parser.into_label_storage();
+    Ok((trees, label_map))
+}
 
-    // Parse all trees
+/// Parses the Newick strings read from a reader (e.g. stdin, a pipe, or a
+/// socket) until its end, like [parse_file()] does for a file.
+///
+/// # Arguments
+/// * `reader` - The reader of a semicolon-separated list of Newick strings
+///
+/// # Returns
+/// * `(Vec<CompactTree>, LeafLabelMap)` - All parsed trees and their shared label mapping
+/// * [ParsingError] - If reading fails or Newick format is invalid
+///
+/// # Example
+/// ```no_run
+/// use nexwick::newick::parse_reader;
+///
+/// // E.g. `cat anseriformes.nwk | my_tool`
+/// let (trees, label_map) = parse_reader(std::io::stdin())?;
+/// println!("Parsed {} trees with {} taxa", trees.len(), label_map.num_labels());
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn parse_reader<R: Read>(reader: R) -> Result<(Vec<CompactTree>, LeafLabelMap), ParsingError> {
     let mut newick_parser = NewickParser::new_compact_defaults();
-    let trees = newick_parser.parse_all(byte_parser)?;
+    let trees = newick_parser.parse_all_from_reader(reader)?;
     let label_map = newick_parser.into_label_storage();
     Ok((trees, label_map))
 }
@@ -127,3 +198,30 @@ pub fn parse_str<S: AsRef<str>>(newick: S) -> Result<SimpleTree, ParsingError> {
     let mut byte_parser = ByteParser::for_str(newick.as_ref());
     newick_parser.parse_str(&mut byte_parser)
 }
+
+/// Parses a single extended Newick (eNewick) string to obtain a [SimpleNetwork].
+///
+/// This is a convenience function for quick parsing of a single network
+/// using default settings and thus not requiring configuration of a parser.
+///
+/// # Arguments
+/// * `enewick` - The eNewick format string to parse
+///
+/// # Returns
+/// * [SimpleNetwork] - Network parsed from the string
+/// * [ParsingError] - If the string is not valid eNewick format
+///
+/// # Example
+/// ```
+/// use nexwick::newick::parse_network_str;
+///
+/// let network = parse_network_str("((A,(B)#H1:0.5::0.3),(#H1:0.2::0.7,C));")?;
+/// assert_eq!(network.num_hybrids(), 1);
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn parse_network_str<S: AsRef<str>>(enewick: S) -> Result<SimpleNetwork, ParsingError> {
+    let mut newick_parser = NewickParser::new(SimpleNetworkBuilder::new());
+    let mut byte_parser = ByteParser::for_str(enewick.as_ref());
+    newick_parser.parse_str(&mut byte_parser)
+}
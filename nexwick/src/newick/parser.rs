@@ -5,20 +5,108 @@
 //! [NewickIterator].
 
 use crate::model::annotation::AnnotationValue;
+use crate::model::label_storage::LabelStorage;
 use crate::model::simple_tree_builder::{SimpleLabelStorage, SimpleTreeBuilder};
 use crate::model::tree_builder::TreeBuilder;
-use crate::model::{CompactTreeBuilder, LabelResolver, LeafLabelMap};
-use crate::newick::defs::{DEFAULT_NUM_LEAVES_GUESS, NEWICK_LABEL_DELIMITERS};
+use crate::model::{CompactTreeBuilder, LabelResolver, LeafLabelMap, TreeMetadata};
+use crate::newick::defs::{DEFAULT_NUM_LEAVES_GUESS, NEWICK_LABEL_DELIMITERS, NHX_MARKER};
 use crate::parser::byte_parser::ByteParser;
 use crate::parser::byte_source::ByteSource;
 use crate::parser::parsing_error::ParsingError;
+use crate::parser::reader_byte_source::ReaderByteSource;
 use std::collections::HashMap;
+use std::io::Read;
+
+// =#========================================================================#=
+// BRANCH ANNOTATION POLICY
+// =#========================================================================€=
+/// Specifies how to handle branch annotations, i.e. annotations placed
+/// between colon and branch length or after the branch length
+/// (MrBayes style), e.g. `A:[&rate=0.9]0.12` or `A:0.12[&rate=0.9]`.
+///
+/// Only relevant if annotations are parsed at all, see
+/// [`NewickParser::with_annotations()`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BranchAnnotationPolicy {
+    /// Keep branch annotations separate from vertex annotations, passed on
+    /// via [TreeBuilder::add_branch_annotation], see
+    /// [GenTree::branch_annotations](crate::model::GenTree::branch_annotations).
+    #[default]
+    Separate,
+
+    /// Merge branch annotations into the annotations of the child vertex
+    /// of the branch; on conflicting keys, the vertex annotation is kept.
+    Merge,
+}
+
+// =#========================================================================#=
+// BRANCH LENGTH POLICY
+// =#========================================================================€=
+/// Specifies how to handle negative, NaN, and infinite branch lengths,
+/// e.g. as found in trees inferred with neighbour joining.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BranchLengthPolicy {
+    /// Return a [ParsingError] for negative, NaN, or infinite branch lengths.
+    #[default]
+    Error,
+
+    /// Clamp negative branch lengths (including `-0.0`) to zero;
+    /// still return a [ParsingError] for NaN or infinite branch lengths.
+    ClampToZero,
+
+    /// Keep all branch lengths as-is, stored as signed
+    /// [BranchLength](crate::model::vertex::BranchLength) (see
+    /// [BranchLength::new_signed](crate::model::vertex::BranchLength::new_signed)).
+    Keep,
+}
+
+impl BranchLengthPolicy {
+    /// Applies this policy to the given branch length.
+    ///
+    /// # Returns
+    /// * `Ok(value)` - Branch length to use, possibly clamped
+    /// * `Err(message)` - Description why the branch length is rejected
+    fn apply(&self, value: f64) -> Result<f64, String> {
+        match self {
+            BranchLengthPolicy::Keep => Ok(value),
+            _ if !value.is_finite() => Err(format!("Branch length not finite: {}", value)),
+            BranchLengthPolicy::ClampToZero => Ok(value.max(0.0)),
+            BranchLengthPolicy::Error if value.is_sign_negative() && value != 0.0 => {
+                Err(format!("Negative branch length: {}", value))
+            }
+            BranchLengthPolicy::Error => Ok(value),
+        }
+    }
+}
+
+// =#========================================================================#=
+// UNROOTED TREE POLICY
+// =#========================================================================€=
+/// Specifies how to handle unrooted trees, which are commonly written with a
+/// basal trifurcation, e.g. `(A,B,(C,D));`.
+///
+/// A root with three or more children is considered such a basal polytomy
+/// of an unrooted tree, unless the tree is explicitly marked as rooted with
+/// `[&R]`. Either way, unrooted trees are marked as such in their
+/// [TreeMetadata], so that writers can restore the basal trifurcation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UnrootedTreePolicy {
+    /// Keep the basal polytomy as root with three or more children.
+    #[default]
+    Keep,
+
+    /// Root the tree deterministically on the branch of the first child of
+    /// the basal polytomy, giving a binary root: `(A,B,(C,D));` becomes
+    /// `(A,(B,(C,D)));`, where the new vertex gets branch length zero
+    /// (if the tree has branch lengths), so that distances are maintained.
+    RootAtFirstChild,
+}
 
 // =#========================================================================#=
 // NEWICK PARSER
 // =#========================================================================$=
-/// Parser (configuration) for single/multiple Newick format (binary)
-/// phylogenetic trees.
+/// Parser (configuration) for single/multiple Newick format
+/// phylogenetic trees (binary or with polytomies).
 ///
 /// Generic over [TreeBuilder] (construction). Uses a [LabelResolver]
 /// (with in turn uses the builders [LabelStorage](crate::model::LabelStorage)
@@ -41,6 +129,18 @@ use std::collections::HashMap;
 /// * [`with_annotations()`](Self::with_annotations)
 ///     - Configures the parser to parse vertex annotations
 ///       (e.g. `[&rate=0.5,pop_size=1.2]`) instead of treating them as comments.
+/// * [`with_unrooted_tree_policy(policy)`](Self::with_unrooted_tree_policy)
+///     - Configures whether unrooted trees with a basal trifurcation are kept
+///       as such or rooted, see [UnrootedTreePolicy] (default: keep).
+/// * [`with_branch_length_policy(policy)`](Self::with_branch_length_policy)
+///     - Configures how negative, NaN, and infinite branch lengths are handled,
+///       see [BranchLengthPolicy] (default: error).
+/// * [`with_branch_annotation_policy(policy)`](Self::with_branch_annotation_policy)
+///     - Configures how annotations of branches (e.g. `A:[&rate=0.9]0.12`)
+///       are handled, see [BranchAnnotationPolicy].
+/// * [`with_error_recovery()`](Self::with_error_recovery)
+///     - Configures the parser to skip malformed trees when parsing multiple
+///       trees (lenient mode), see [`errors()`](Self::errors).
 ///
 /// # Parsing
 /// * [`parse_str`](Self::parse_str) — Parse single tree
@@ -65,6 +165,25 @@ pub struct NewickParser<T: TreeBuilder> {
     tree_builder: T,
     resolver: LabelResolver<T::Storage>,
     parse_annotations: bool,
+    branch_annotation_policy: BranchAnnotationPolicy,
+    branch_length_policy: BranchLengthPolicy,
+    unrooted_tree_policy: UnrootedTreePolicy,
+    /// Whether any branch length has been seen in the current tree
+    has_branch_lengths: bool,
+    /// Hybrid identifiers of the current network with their number of
+    /// occurrences and of defining ones (with subtree or leaf label)
+    hybrids: HashMap<String, (usize, usize)>,
+    /// Whether to skip malformed trees instead of failing (lenient mode)
+    error_recovery: bool,
+    /// Errors of malformed trees skipped in lenient mode
+    errors: Vec<ParsingError>,
+}
+
+/// Annotations parsed for a vertex and for the branch to its parent
+#[derive(Default)]
+struct ParsedAnnotations {
+    vertex: Option<HashMap<String, AnnotationValue>>,
+    branch: Option<HashMap<String, AnnotationValue>>,
 }
 
 // ============================================================================
@@ -82,6 +201,13 @@ impl<T: TreeBuilder> NewickParser<T> {
             tree_builder,
             resolver,
             parse_annotations: false,
+            branch_annotation_policy: BranchAnnotationPolicy::default(),
+            branch_length_policy: BranchLengthPolicy::default(),
+            unrooted_tree_policy: UnrootedTreePolicy::default(),
+            has_branch_lengths: false,
+            hybrids: HashMap::new(),
+            error_recovery: false,
+            errors: Vec::new(),
         }
     }
 
@@ -123,7 +249,20 @@ impl<T: TreeBuilder> NewickParser<T> {
         self
     }
 
-    /// Configures the parser to parse vertex annotations.
+    /// Replaces the resolver with a new one and returns the old one,
+    /// e.g. to retrieve its label storage.
+    ///
+    /// Used by [NexusParser](crate::nexus::NexusParser) to parse several
+    /// TREES blocks, each with its own label storage.
+    pub(crate) fn replace_resolver(
+        &mut self,
+        resolver: LabelResolver<T::Storage>,
+    ) -> LabelResolver<T::Storage> {
+        std::mem::replace(&mut self.resolver, resolver)
+    }
+
+    /// Configures the parser to parse vertex annotations, both
+    /// BEAST style (`[&rate=0.5]`) and NHX style (`[&&NHX:S=human]`).
     pub fn with_annotations(mut self) -> Self {
         self.parse_annotations = true;
         self
@@ -135,6 +274,57 @@ impl<T: TreeBuilder> NewickParser<T> {
         self
     }
 
+    /// Configures how the parser handles unrooted trees with a basal
+    /// trifurcation (default: [UnrootedTreePolicy::Keep]).
+    pub fn with_unrooted_tree_policy(mut self, policy: UnrootedTreePolicy) -> Self {
+        self.unrooted_tree_policy = policy;
+        self
+    }
+
+    /// Configures how the parser handles unrooted trees with a basal trifurcation.
+    pub(crate) fn set_unrooted_tree_policy(&mut self, policy: UnrootedTreePolicy) -> &mut Self {
+        self.unrooted_tree_policy = policy;
+        self
+    }
+
+    /// Configures how the parser handles negative, NaN, and infinite
+    /// branch lengths (default: [BranchLengthPolicy::Error]).
+    pub fn with_branch_length_policy(mut self, policy: BranchLengthPolicy) -> Self {
+        self.branch_length_policy = policy;
+        self
+    }
+
+    /// Configures how the parser handles negative, NaN, and infinite branch lengths.
+    pub(crate) fn set_branch_length_policy(&mut self, policy: BranchLengthPolicy) -> &mut Self {
+        self.branch_length_policy = policy;
+        self
+    }
+
+    /// Configures how the parser handles branch annotations
+    /// (default: [BranchAnnotationPolicy::Separate]).
+    pub fn with_branch_annotation_policy(mut self, policy: BranchAnnotationPolicy) -> Self {
+        self.branch_annotation_policy = policy;
+        self
+    }
+
+    /// Configures how the parser handles branch annotations.
+    pub(crate) fn set_branch_annotation_policy(
+        &mut self,
+        policy: BranchAnnotationPolicy,
+    ) -> &mut Self {
+        self.branch_annotation_policy = policy;
+        self
+    }
+
+    /// Configures the parser to skip malformed trees (lenient mode) in
+    /// [`parse_all`](Self::parse_all) and [NewickIterator], resynchronizing
+    /// after the next semicolon. Errors of skipped trees are recorded with
+    /// the tree index, see [`errors()`](Self::errors).
+    pub fn with_error_recovery(mut self) -> Self {
+        self.error_recovery = true;
+        self
+    }
+
     /// Consumes the parser and returns the tree builder and resolver.
     pub fn into_parts(self) -> (T, LabelResolver<T::Storage>) {
         (self.tree_builder, self.resolver)
@@ -154,6 +344,13 @@ impl<T: TreeBuilder> NewickParser<T> {
     pub fn label_storage(&self) -> &T::Storage {
         self.resolver.label_storage()
     }
+
+    /// Get the errors of malformed trees skipped so far in lenient mode
+    /// (see [`with_error_recovery()`](Self::with_error_recovery)),
+    /// each with the index of the tree.
+    pub fn errors(&self) -> &[ParsingError] {
+        &self.errors
+    }
 }
 
 // Convenience Default 1
@@ -169,6 +366,13 @@ impl NewickParser<CompactTreeBuilder> {
             tree_builder: CompactTreeBuilder::new(),
             resolver: LabelResolver::VerbatimLabels(LeafLabelMap::new(DEFAULT_NUM_LEAVES_GUESS)),
             parse_annotations: false,
+            branch_annotation_policy: BranchAnnotationPolicy::default(),
+            branch_length_policy: BranchLengthPolicy::default(),
+            unrooted_tree_policy: UnrootedTreePolicy::default(),
+            has_branch_lengths: false,
+            hybrids: HashMap::new(),
+            error_recovery: false,
+            errors: Vec::new(),
         }
     }
 }
@@ -193,6 +397,13 @@ impl NewickParser<SimpleTreeBuilder> {
             tree_builder: SimpleTreeBuilder::new(),
             resolver: LabelResolver::VerbatimLabels(storage),
             parse_annotations: false,
+            branch_annotation_policy: BranchAnnotationPolicy::default(),
+            branch_length_policy: BranchLengthPolicy::default(),
+            unrooted_tree_policy: UnrootedTreePolicy::default(),
+            has_branch_lengths: false,
+            hybrids: HashMap::new(),
+            error_recovery: false,
+            errors: Vec::new(),
         }
     }
 }
@@ -215,6 +426,7 @@ impl<T: TreeBuilder> NewickParser<T> {
         NewickIterator {
             byte_parser,
             parser: self,
+            tree_index: 0,
             done: false,
         }
     }
@@ -226,23 +438,64 @@ impl<T: TreeBuilder> NewickParser<T> {
     ///   Newick strings, except for whitespace and `[...]` comments.
     ///
     /// # Returns
-    /// * `Ok(Vec<T::Tree>)` - All parsed trees
+    /// * `Ok(Vec<T::Tree>)` - All parsed trees (in lenient mode,
+    ///   without malformed trees, whose errors are given by [`errors()`](Self::errors))
     /// * `Err(ParsingError)` - If any tree fails to parse
     pub fn parse_all<B: ByteSource>(
         &mut self,
         mut byte_parser: ByteParser<B>,
     ) -> Result<Vec<T::Tree>, ParsingError> {
         let mut trees = Vec::new();
-        loop {
-            byte_parser.skip_comment_and_whitespace()?;
+        for tree_index in 0.. {
+            skip_to_next_tree(&mut byte_parser)?;
             if byte_parser.is_eof() {
                 break;
             }
-            trees.push(self.parse_str(&mut byte_parser)?);
+            if let Some(tree) = self.parse_str_or_recover(&mut byte_parser, tree_index)? {
+                trees.push(tree);
+            }
         }
         Ok(trees)
     }
 
+    /// Parses all Newick trees read from `reader` until its end, e.g. from
+    /// stdin or a pipe, see [parse_all()](Self::parse_all).
+    ///
+    /// # Arguments
+    /// * `reader` - A reader of only Newick strings, except for whitespace
+    ///   and `[...]` comments.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::newick::NewickParser;
+    ///
+    /// // E.g. `cat trees.nwk | my_tool`
+    /// let mut parser = NewickParser::new_compact_defaults();
+    /// let trees = parser.parse_all_from_reader(std::io::stdin())?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse_all_from_reader<R: Read>(
+        &mut self,
+        reader: R,
+    ) -> Result<Vec<T::Tree>, ParsingError> {
+        self.parse_all(ByteParser::from_reader(reader))
+    }
+
+    /// Consumes the parser and returns an iterator over trees read from
+    /// `reader` (e.g. stdin or a pipe) as they come, see
+    /// [into_iter()](Self::into_iter).
+    ///
+    /// # Arguments
+    /// * `reader` - A reader of only Newick strings, except for whitespace
+    ///   and `[...]` comments.
+    pub fn into_iter_from_reader<R: Read>(
+        self,
+        reader: R,
+    ) -> NewickIterator<ReaderByteSource<R>, T> {
+        self.into_iter(ByteParser::from_reader(reader))
+    }
+
     /// Parses a single Newick tree from the given [ByteParser].
     ///
     /// # Arguments
@@ -256,15 +509,18 @@ impl<T: TreeBuilder> NewickParser<T> {
         &mut self,
         parser: &mut ByteParser<B>,
     ) -> Result<T::Tree, ParsingError> {
-        self.parse_str_and_name(parser, None)
+        self.parse_str_and_name(parser, None, TreeMetadata::default())
     }
 
     /// Parses a single Newick tree from the given [ByteParser]
-    /// and gives it the provided name.
+    /// and gives it the provided name and metadata.
     ///
     /// # Arguments
     /// * `parser` - The byte parser positioned at the start of a Newick tree string
     /// * `tree_name` - The name to give to the parsed tree
+    /// * `metadata` - Metadata of the tree known beforehand (e.g. from comments
+    ///   in a Nexus `TREE` command before its `=`), extended by the comments
+    ///   preceding the Newick string
     ///
     /// # Returns
     /// * `Ok(T::Tree)` - The parsed phylogenetic tree
@@ -274,6 +530,7 @@ impl<T: TreeBuilder> NewickParser<T> {
         &mut self,
         parser: &mut ByteParser<B>,
         tree_name: Option<String>,
+        metadata: TreeMetadata,
     ) -> Result<T::Tree, ParsingError> {
         self.tree_builder.init_next(self.num_leaves);
 
@@ -287,7 +544,20 @@ impl<T: TreeBuilder> NewickParser<T> {
             self.num_leaves = 0;
         }
 
-        self.parse_root(parser)?;
+        self.has_branch_lengths = false;
+        self.hybrids.clear();
+        let mut metadata = metadata;
+        let num_labels = self.resolver.label_storage().num_labels();
+        let result = Self::parse_tree_metadata(parser, &mut metadata)
+            .and_then(|()| self.parse_root(parser, &mut metadata));
+        if let Err(err) = result {
+            // Discard labels stored for the malformed tree
+            self.resolver.label_storage_mut().truncate(num_labels);
+            return Err(err);
+        }
+        if !metadata.is_empty() {
+            self.tree_builder.set_metadata(metadata);
+        }
 
         // Having parsed a full tree,
         // the number of leaves in a tree is now known
@@ -301,28 +571,152 @@ impl<T: TreeBuilder> NewickParser<T> {
 // Parsing
 // ============================================================================
 impl<T: TreeBuilder> NewickParser<T> {
+    /// Parses a single Newick tree; in lenient mode, a malformed tree is
+    /// skipped until after the next semicolon and its error recorded.
+    ///
+    /// # Arguments
+    /// * `parser` - The byte parser positioned at the start of a Newick tree string
+    /// * `tree_index` - The index of the tree, attached to its error
+    ///
+    /// # Returns
+    /// * `Ok(Some(T::Tree))` - The parsed phylogenetic tree
+    /// * `Ok(None)` - If the tree was malformed and skipped (lenient mode)
+    /// * `Err(ParsingError)` - If the Newick format is invalid (strict mode)
+    fn parse_str_or_recover<B: ByteSource>(
+        &mut self,
+        parser: &mut ByteParser<B>,
+        tree_index: usize,
+    ) -> Result<Option<T::Tree>, ParsingError> {
+        match self.parse_str(parser) {
+            Ok(tree) => Ok(Some(tree)),
+            Err(err) if self.error_recovery => {
+                // Resynchronize after the semicolon ending the malformed tree
+                // (not one within a quoted label or comment), continuing from
+                // the error, as streams cannot be rewound
+                parser.consume_until_unquoted(b';');
+                self.errors.push(err.with_tree(tree_index, None));
+                Ok(None)
+            }
+            Err(err) => Err(err.with_tree(tree_index, None)),
+        }
+    }
+
+    /// Parses the comments preceding a Newick string and collects
+    /// tree-level [TreeMetadata] from them into `metadata`:
+    /// - `[&R]` / `[&U]` - tree is rooted / unrooted
+    /// - `[&W weight]` - weight of tree as number or fraction,
+    ///   e.g. `[&W 0.25]` or `[&W 1/4]`
+    /// - `[&key=value,...]` - annotations of tree, e.g. `[&lnP=-1234.5]`
+    /// - Skips whitespace and any other comments (including `[&...]`
+    ///   comments without `=`, which are not key-value pairs)
+    ///
+    /// # Errors
+    /// Returns an error if a weight or key-value pairs (i.e. `[&...]` with
+    /// `=`) cannot be parsed, or if a comment is not closed.
+    pub(crate) fn parse_tree_metadata<B: ByteSource>(
+        parser: &mut ByteParser<B>,
+        metadata: &mut TreeMetadata,
+    ) -> Result<(), ParsingError> {
+        loop {
+            parser.skip_whitespace();
+            if !parser.consume_if_sequence(b"[&") {
+                if parser.skip_comment()? {
+                    continue;
+                }
+                return Ok(());
+            }
+
+            let content = Self::parse_comment_content(parser)?;
+            let content = content.trim();
+            if content.eq_ignore_ascii_case("R") {
+                metadata.set_rooted(true);
+            } else if content.eq_ignore_ascii_case("U") {
+                metadata.set_rooted(false);
+            } else if let Some(weight_str) = content.strip_prefix(['W', 'w'])
+                && weight_str.starts_with(char::is_whitespace)
+            {
+                let weight = parse_weight(weight_str.trim()).ok_or_else(|| {
+                    ParsingError::invalid_newick_string(
+                        parser,
+                        format!("Invalid tree weight: {}", weight_str.trim()),
+                    )
+                })?;
+                metadata.set_weight(weight);
+            } else if content.contains('=') {
+                // (Parsed separately, with errors reported for the whole comment)
+                let mut content_parser = ByteParser::for_str(&format!("[&{content}]"));
+                let annotations = match Self::parse_annotations(&mut content_parser) {
+                    Ok(Some(annotations)) if content_parser.is_eof() => annotations,
+                    _ => {
+                        return Err(ParsingError::invalid_newick_string(
+                            parser,
+                            format!("Invalid tree annotations: [&{content}]"),
+                        ));
+                    }
+                };
+                for (key, value) in annotations {
+                    metadata.set_annotation(key, value);
+                }
+            }
+        }
+    }
+
+    /// Parses the content of a comment after its opening `[&` up to and
+    /// including its closing `]`, skipping `]` within quoted (`'...'` or
+    /// `"..."`) values and nested comments.
+    ///
+    /// # Errors
+    /// Returns an error if the comment is not closed.
+    fn parse_comment_content<B: ByteSource>(
+        parser: &mut ByteParser<B>,
+    ) -> Result<String, ParsingError> {
+        let mut content = Vec::new();
+        let mut quote = None;
+        let mut depth = 0;
+        while let Some(byte) = parser.next_byte() {
+            match (quote, byte) {
+                (Some(open), _) if byte == open => quote = None,
+                (Some(_), _) => {}
+                (None, b'\'' | b'"') => quote = Some(byte),
+                (None, b'[') => depth += 1,
+                (None, b']') if depth == 0 => return Ok(String::from_utf8_lossy(&content).into()),
+                (None, b']') => depth -= 1,
+                (None, _) => {}
+            }
+            content.push(byte);
+        }
+        Err(ParsingError::unclosed_comment(parser))
+    }
+
     /// Parses root of tree and adds it to tree:
-    /// - `(left, right)[:branch_length]`
+    /// - `(child_1, ..., child_k)[label][:branch_length]`
     /// - Skips leading comments and whitespace
-    /// - Calls `parse_children` to parse the children pair
+    /// - Calls `parse_children` to parse the children list
+    /// - Marks tree as unrooted in `metadata` if the root is a basal
+    ///   polytomy, handled according to the [UnrootedTreePolicy]
     ///
     /// Equivalent to `parse_internal_vertex` but takes care of root specialities.
     fn parse_root<B: ByteSource>(
         &mut self,
         parser: &mut ByteParser<B>,
+        metadata: &mut TreeMetadata,
     ) -> Result<(), ParsingError> {
         parser.skip_comment_and_whitespace()?;
 
-        let (left_index, right_index) = self.parse_children(parser)?;
+        let children = self.parse_children(parser)?;
+        let label = self.parse_internal_label(parser)?;
+        Self::check_num_children(parser, &children)?;
 
-        let annotations = if self.parse_annotations {
-            self.parse_annotations(parser)?
-        } else {
-            None
-        };
+        let mut annotations = ParsedAnnotations::default();
+        if self.parse_annotations {
+            annotations.vertex = Self::parse_annotations(parser)?;
+        }
 
         // Root may have an optional branch length (might be None)
-        let branch_length = self.parse_branch_length(parser)?;
+        let branch_length = self.parse_branch_length(parser, &mut annotations)?;
+
+        // (Checked before the semicolon, so that errors leave the parser within the tree)
+        self.check_hybrids(parser)?;
 
         // Consume the terminating semicolon
         parser.skip_comment_and_whitespace()?;
@@ -334,10 +728,23 @@ impl<T: TreeBuilder> NewickParser<T> {
             ));
         }
 
-        let root_index = self
-            .tree_builder
-            .add_root((left_index, right_index), branch_length);
+        // Basal polytomy of unrooted tree, unless explicitly rooted
+        let mut children = children;
+        if children.len() > 2 && metadata.rooted() != Some(true) {
+            metadata.set_rooted(false);
+            if self.unrooted_tree_policy == UnrootedTreePolicy::RootAtFirstChild {
+                metadata.set_rooted_at_first_child();
+                let branch_length = self.has_branch_lengths.then_some(0.0);
+                let rest = children.split_off(1);
+                children.push(self.tree_builder.add_internal(rest, branch_length));
+            }
+        }
+
+        let root_index = self.tree_builder.add_root(children, branch_length);
 
+        if let Some(label) = label {
+            self.tree_builder.add_internal_label(root_index, label);
+        }
         self.add_annotations(annotations, root_index);
 
         Ok(())
@@ -363,8 +770,10 @@ impl<T: TreeBuilder> NewickParser<T> {
     }
 
     /// Parses internal vertex, adds it to tree, and returns its index:
-    /// - `(left, right)[:branch_length]`
-    /// - Calls `parser_children` to parse the children pair
+    /// - `(child_1, ..., child_k)[label][:branch_length]`
+    /// - Calls `parser_children` to parse the children list
+    /// - Dispatches to `parse_hybrid` if label marks a hybrid vertex
+    ///   and tree builder supports them
     ///
     /// # Returns
     /// - vertex index of parsed internal vertex
@@ -373,34 +782,46 @@ impl<T: TreeBuilder> NewickParser<T> {
         &mut self,
         parser: &mut ByteParser<B>,
     ) -> Result<T::VertexIdx, ParsingError> {
-        let (left_index, right_index) = self.parse_children(parser)?;
-        let annotations = if self.parse_annotations {
-            self.parse_annotations(parser)?
-        } else {
-            None
-        };
-        let branch_length = self.parse_branch_length(parser)?;
-        let index = self
-            .tree_builder
-            .add_internal((left_index, right_index), branch_length);
+        let children = self.parse_children(parser)?;
+        let label = self.parse_internal_label(parser)?;
+
+        // Hybrid vertex of a network (eNewick), e.g. `(B)#H1`
+        if self.tree_builder.supports_hybrids()
+            && let Some((prefix, hybrid_id)) = label.as_deref().and_then(split_hybrid_label)
+        {
+            return self.parse_hybrid(parser, prefix, hybrid_id, children);
+        }
+
+        Self::check_num_children(parser, &children)?;
+        let mut annotations = ParsedAnnotations::default();
+        if self.parse_annotations {
+            annotations.vertex = Self::parse_annotations(parser)?;
+        }
+        let branch_length = self.parse_branch_length(parser, &mut annotations)?;
+        let index = self.tree_builder.add_internal(children, branch_length);
 
+        if let Some(label) = label {
+            self.tree_builder.add_internal_label(index, label);
+        }
         self.add_annotations(annotations, index);
 
         Ok(index)
     }
 
-    /// Parses children pair `(left, right)` and returns their indices:
+    /// Parses children list `(child_1, ..., child_k)`
+    /// and returns their indices:
     /// - Expects parser at opening `(`
     ///   (caller should skip leading comments/whitespace)
+    /// - Caller should check that `k >= 2` (unless a hybrid vertex)
     ///
     /// # Returns
-    /// - vertex indices of left and right child vertices
+    /// - vertex indices of the child vertices, in order of appearance
     /// - [ParsingError] if something went wrong
     fn parse_children<B: ByteSource>(
         &mut self,
         parser: &mut ByteParser<B>,
-    ) -> Result<(T::VertexIdx, T::VertexIdx), ParsingError> {
-        // Parse: "(left"
+    ) -> Result<Vec<T::VertexIdx>, ParsingError> {
+        // Parse: "(first"
         // Calling methods should have skipped comments and whitespace
         if !parser.consume_if(b'(') {
             let next_char = parser.peek().map(char::from);
@@ -409,34 +830,76 @@ impl<T: TreeBuilder> NewickParser<T> {
                 format!("Expected '(' before children but found {:?}", next_char),
             ));
         }
-        let left_index = self.parse_vertex(parser)?;
+        let mut children = Vec::with_capacity(2);
+        children.push(self.parse_vertex(parser)?);
 
-        // Parse: ",right"
-        parser.skip_comment_and_whitespace()?;
-        if !parser.consume_if(b',') {
+        // Parse: ",next" as long as there are more children
+        loop {
+            parser.skip_comment_and_whitespace()?;
+            if !parser.consume_if(b',') {
+                break;
+            }
+            children.push(self.parse_vertex(parser)?);
+        }
+
+        // Parse: ")"
+        if !parser.consume_if(b')') {
             let next_char = parser.peek().map(char::from);
             return Err(ParsingError::invalid_newick_string(
                 parser,
-                format!("Expected ',' between children but found {:?}", next_char),
+                format!(
+                    "Expected ',' or ')' after child vertex but found {:?}",
+                    next_char
+                ),
             ));
         }
-        let right_index = self.parse_vertex(parser)?;
 
-        // Parse: ")"
-        parser.skip_comment_and_whitespace()?;
-        if !parser.consume_if(b')') {
-            let next_char = parser.peek().map(char::from);
+        Ok(children)
+    }
+
+    /// Checks that a (non-hybrid) root or internal vertex has at least two children.
+    fn check_num_children<B: ByteSource>(
+        parser: &mut ByteParser<B>,
+        children: &[T::VertexIdx],
+    ) -> Result<(), ParsingError> {
+        if children.len() < 2 {
             return Err(ParsingError::invalid_newick_string(
                 parser,
-                format!("Expected ')' after children but found {:?}", next_char),
+                String::from("Expected at least two children"),
             ));
         }
+        Ok(())
+    }
+
+    /// Parses optional label of an internal vertex or root directly after
+    /// its closing `)`, e.g. a clade name `Clade_X` or support value `95`:
+    /// - Skips whitespace, but not comments (might be an annotation block)
+    /// - Supports quoted and unquoted labels
+    ///
+    /// # Returns
+    /// - `Ok(Some(label))` if a non-empty label was found
+    /// - `Ok(None)` if no label present
+    /// - [ParsingError] if something went wrong, e.g. unexpected EOF
+    fn parse_internal_label<B: ByteSource>(
+        &mut self,
+        parser: &mut ByteParser<B>,
+    ) -> Result<Option<String>, ParsingError> {
+        parser.skip_whitespace();
+        let label = match parser.peek() {
+            Some(b'\'') => parser.parse_quoted_label()?,
+            Some(b) if !NEWICK_LABEL_DELIMITERS.contains(&b) => {
+                parser.parse_unquoted_label(NEWICK_LABEL_DELIMITERS)?
+            }
+            _ => return Ok(None),
+        };
 
-        Ok((left_index, right_index))
+        Ok(Some(label).filter(|label| !label.is_empty()))
     }
 
     /// Parses leaf vertex and adds it to tree:
     /// - `label[:branch_length]`
+    /// - Dispatches to `parse_hybrid` if label marks a hybrid vertex
+    ///   and tree builder supports them
     /// - Expects parser at start of label
     ///   (caller should skip leading comments/whitespace)
     ///
@@ -448,17 +911,26 @@ impl<T: TreeBuilder> NewickParser<T> {
         &mut self,
         parser: &mut ByteParser<B>,
     ) -> Result<T::VertexIdx, ParsingError> {
+        let is_quoted = parser.peek_is(b'\'');
         let label = parser.parse_label(NEWICK_LABEL_DELIMITERS)?;
+
+        // Occurrence of hybrid vertex of a network (eNewick), e.g. `#H1`
+        if !is_quoted
+            && self.tree_builder.supports_hybrids()
+            && let Some((prefix, hybrid_id)) = split_hybrid_label(&label)
+        {
+            return self.parse_hybrid(parser, prefix, hybrid_id, Vec::new());
+        }
+
         let label_ref = self
             .resolver
             .resolve_label(&label)
             .map_err(|e| ParsingError::unresolved_label(parser, e.to_string()))?;
-        let annotations = if self.parse_annotations {
-            self.parse_annotations(parser)?
-        } else {
-            None
-        };
-        let branch_length = self.parse_branch_length(parser)?;
+        let mut annotations = ParsedAnnotations::default();
+        if self.parse_annotations {
+            annotations.vertex = Self::parse_annotations(parser)?;
+        }
+        let branch_length = self.parse_branch_length(parser, &mut annotations)?;
         if !self.know_num_leaves {
             self.num_leaves += 1;
         }
@@ -472,46 +944,193 @@ impl<T: TreeBuilder> NewickParser<T> {
 
     /// Parses optional branch length `[:number]`:
     /// - Skips comments/whitespace before and after `:`
-    /// - Supports scientific notation (e.g., `1.5e-10`)
+    /// - Supports scientific notation (e.g., `1.5e-10`), as well as
+    ///   `NaN` and `inf`
+    /// - Applies the [BranchLengthPolicy] to negative, NaN, or infinite values
     ///
     /// # Returns
     /// -  `Ok(Some(branch_length))` if found a branch length and was able to parse it
     /// - `Ok(None)` if no branch length found
     /// - [ParsingError] if it couldn't parse branch length value
+    ///   or the value is rejected by the [BranchLengthPolicy]
     fn parse_branch_length<B: ByteSource>(
         &mut self,
         parser: &mut ByteParser<B>,
+        annotations: &mut ParsedAnnotations,
     ) -> Result<Option<f64>, ParsingError> {
         // Parse: Whitespace/Comments : Whitespace/Comments
         parser.skip_comment_and_whitespace()?;
         if !parser.consume_if(b':') {
             return Ok(None);
         }
-        parser.skip_comment_and_whitespace()?;
-
-        // Find end of branch length substring
-        let mut branch_length_str = String::new();
-        while let Some(b) = parser.peek() {
-            // Valid characters for a float: digits, '.', '-', '+', 'e', 'E'
-            if b.is_ascii_digit() || b == b'.' || b == b'-' || b == b'+' || b == b'e' || b == b'E' {
-                branch_length_str.push(b as char);
-                parser.next_byte(); // consume it
-            } else {
-                break; // Hit a delimiter like ',', ')', ';', or whitespace
-            }
-        }
+        self.parse_branch_comments(parser, annotations)?;
 
         // Parse branch length substring
+        let branch_length_str = Self::parse_number_str(parser);
         let value: f64 = branch_length_str.parse().map_err(|_| {
             ParsingError::invalid_newick_string(
                 parser,
                 format!("Invalid branch length: {}", branch_length_str),
             )
         })?;
+        let value = self.check_branch_length(parser, value)?;
+        self.has_branch_lengths = true;
+        self.parse_branch_comments(parser, annotations)?;
+
         Ok(Some(value))
     }
 
-    /// Parses an annotation block `[&key=value,...]` if present.
+    /// Applies the [BranchLengthPolicy] to a parsed branch length.
+    ///
+    /// # Errors
+    /// Returns [ParsingErrorType::InvalidBranchLength](crate::parser::parsing_error::ParsingErrorType::InvalidBranchLength)
+    /// if the policy rejects the value.
+    fn check_branch_length<B: ByteSource>(
+        &self,
+        parser: &mut ByteParser<B>,
+        value: f64,
+    ) -> Result<f64, ParsingError> {
+        self.branch_length_policy
+            .apply(value)
+            .map_err(|msg| ParsingError::invalid_branch_length(parser, msg))
+    }
+
+    /// Consumes and returns the substring of a number at current position
+    /// (empty if there is none), e.g. `1.5e-10`, `NaN`, or `-inf`.
+    fn parse_number_str<B: ByteSource>(parser: &mut ByteParser<B>) -> String {
+        let mut number_str = String::new();
+        while let Some(b) = parser.peek() {
+            // Valid characters for a float: digits, '.', '-', '+', and letters
+            // (for exponent 'e', 'NaN', 'inf'); validated when parsed
+            if b.is_ascii_alphanumeric() || b == b'.' || b == b'-' || b == b'+' {
+                number_str.push(b as char);
+                parser.next_byte(); // consume it
+            } else {
+                break; // Hit a delimiter like ',', ')', ';', ':', or whitespace
+            }
+        }
+        number_str
+    }
+
+    /// Checks that each hybrid vertex of the network just parsed occurs at
+    /// least twice (once per parent) and is defined by exactly one occurrence,
+    /// i.e. the one with its subtree (or leaf label).
+    ///
+    /// # Errors
+    /// Returns [ParsingError::invalid_newick_string] for the first hybrid
+    /// violating this.
+    fn check_hybrids<B: ByteSource>(&self, parser: &mut ByteParser<B>) -> Result<(), ParsingError> {
+        let mut hybrids: Vec<_> = self.hybrids.iter().collect();
+        hybrids.sort_unstable_by_key(|(hybrid_id, _)| *hybrid_id);
+        for (hybrid_id, &(num_occurrences, num_definitions)) in hybrids {
+            let problem = match (num_occurrences, num_definitions) {
+                (1, _) => "occurs only once",
+                (_, 0) => "has neither subtree nor label",
+                (_, 2..) => "is defined more than once",
+                _ => continue,
+            };
+            return Err(ParsingError::invalid_newick_string(
+                parser,
+                format!("Hybrid '#{hybrid_id}' {problem}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parses the rest of an occurrence of a hybrid vertex `[label]#H1` in
+    /// extended Newick (eNewick) and adds it to the network:
+    /// - `[annotation][:[branch_length][:[support][:inheritance]]]`,
+    ///   where the support value is ignored
+    /// - Expects parser after the label
+    /// - Label before `#` is a clade name if the occurrence has children,
+    ///   and a leaf label otherwise
+    ///
+    /// # Returns
+    /// - vertex index of parsed hybrid occurrence
+    /// - [ParsingError] if something went wrong,
+    ///   e.g. if label couldn't be resolved
+    fn parse_hybrid<B: ByteSource>(
+        &mut self,
+        parser: &mut ByteParser<B>,
+        prefix: String,
+        hybrid_id: String,
+        children: Vec<T::VertexIdx>,
+    ) -> Result<T::VertexIdx, ParsingError> {
+        if hybrid_id.is_empty() {
+            return Err(ParsingError::invalid_newick_string(
+                parser,
+                format!("Missing hybrid identifier after '#' in '{}#'", prefix),
+            ));
+        }
+
+        let is_leaf = children.is_empty();
+        let (num_occurrences, num_definitions) = self.hybrids.entry(hybrid_id.clone()).or_default();
+        *num_occurrences += 1;
+        if !is_leaf || !prefix.is_empty() {
+            *num_definitions += 1;
+        }
+
+        let label_ref = if is_leaf && !prefix.is_empty() {
+            let label_ref = self
+                .resolver
+                .resolve_label(&prefix)
+                .map_err(|e| ParsingError::unresolved_label(parser, e.to_string()))?;
+            Some(label_ref)
+        } else {
+            None
+        };
+        let mut annotations = ParsedAnnotations::default();
+        if self.parse_annotations {
+            annotations.vertex = Self::parse_annotations(parser)?;
+        }
+
+        // Parse: ":[branch_length][:[support][:inheritance]]", each part optional
+        let mut edge_values = [None; 3];
+        parser.skip_comment_and_whitespace()?;
+        for value in edge_values.iter_mut() {
+            if !parser.consume_if(b':') {
+                break;
+            }
+            self.parse_branch_comments(parser, &mut annotations)?;
+            let value_str = Self::parse_number_str(parser);
+            if !value_str.is_empty() {
+                *value = Some(value_str.parse::<f64>().map_err(|_| {
+                    ParsingError::invalid_newick_string(
+                        parser,
+                        format!(
+                            "Invalid number after hybrid '#{}': {}",
+                            hybrid_id, value_str
+                        ),
+                    )
+                })?);
+            }
+            self.parse_branch_comments(parser, &mut annotations)?;
+        }
+        // (Support values of hybrid edges are not stored in the network)
+        let [branch_length, _support, inheritance] = edge_values;
+        let branch_length = branch_length
+            .map(|value| self.check_branch_length(parser, value))
+            .transpose()?;
+        self.has_branch_lengths |= branch_length.is_some();
+
+        let index = self.tree_builder.add_hybrid(
+            hybrid_id,
+            children,
+            label_ref,
+            branch_length,
+            inheritance,
+        );
+
+        if !is_leaf && !prefix.is_empty() {
+            self.tree_builder.add_internal_label(index, prefix);
+        }
+        self.add_annotations(annotations, index);
+
+        Ok(index)
+    }
+
+    /// Parses an annotation block `[&key=value,...]` (BEAST style)
+    /// or `[&&NHX:key=value:...]` (NHX style) if present.
     ///
     /// Returns [None] if the current position is not `[&`.
     /// Note that `[` without `&` is a regular comment, not an annotation.
@@ -521,51 +1140,44 @@ impl<T: TreeBuilder> NewickParser<T> {
     /// * `Ok(None)` - No annotation block at current position
     /// * `Err(ParsingError)` - If annotation block is malformed
     fn parse_annotations<B: ByteSource>(
-        &mut self,
         parser: &mut ByteParser<B>,
     ) -> Result<Option<HashMap<String, AnnotationValue>>, ParsingError> {
-        // Parse '[&'
+        // Parse '[&' and, for NHX, '&NHX'
         if !parser.consume_if_sequence(b"[&") {
             return Ok(None);
         }
+        let is_nhx = parser.consume_if_sequence(NHX_MARKER);
 
-        let mut annotations = HashMap::new();
+        // Pairs separated by ',' (BEAST) or each preceded by ':' (NHX)
+        let (separator, value_delimiters): (u8, &[u8]) =
+            if is_nhx { (b':', b":]") } else { (b',', b",]") };
+        let has_pairs = !is_nhx || parser.consume_if(b':');
 
-        loop {
-            // Parse key (until '=')
-            let key = parser.parse_unquoted_label(b"=")?;
-            if key.is_empty() {
-                return Err(ParsingError::invalid_newick_string(
-                    parser,
-                    "Empty annotation key".to_string(),
-                ));
-            }
+        let mut annotations = HashMap::new();
 
-            parser.next_byte(); // consume '='
+        if has_pairs {
+            loop {
+                // Parse key (until '='), ignoring surrounding whitespace
+                parser.skip_whitespace();
+                let key = parser.parse_unquoted_label(b"=")?.trim_end().to_string();
+                if key.is_empty() {
+                    return Err(ParsingError::invalid_newick_string(
+                        parser,
+                        "Empty annotation key".to_string(),
+                    ));
+                }
 
-            // Parse value (until ',' or ']')
-            let value_str = parser.parse_unquoted_label(b",]")?;
-            if value_str.is_empty() {
-                return Err(ParsingError::invalid_newick_string(
-                    parser,
-                    format!("Empty annotation value for key '{}'", key),
-                ));
-            }
-            let value = if let Ok(v) = value_str.parse::<i64>() {
-                AnnotationValue::Int(v)
-            } else if let Ok(v) = value_str.parse::<f64>() {
-                AnnotationValue::Float(v)
-            } else {
-                AnnotationValue::String(value_str)
-            };
+                parser.next_byte(); // consume '='
+                parser.skip_whitespace();
 
-            annotations.insert(key, value);
+                // Parse value (until separator or ']')
+                let value = Self::parse_annotation_value(parser, &key, value_delimiters)?;
+                annotations.insert(key, value);
 
-            // ',' means more pairs, ']' means end
-            if parser.consume_if(b',') {
-                continue;
-            } else {
-                break;
+                // Separator means more pairs, ']' means end
+                if !parser.consume_if(separator) {
+                    break;
+                }
             }
         }
 
@@ -580,29 +1192,194 @@ impl<T: TreeBuilder> NewickParser<T> {
         Ok(Some(annotations))
     }
 
-    /// If annotations were parsed, they are added to passed onward to the
-    /// [TreeBuilder] to handle.
+    /// Parses a single annotation value, which is either
+    /// * a list `{value,value,...}`, possibly nested, e.g. `{1.2,3.4}`,
+    /// * a quoted string `'...'` or `"..."`, which may contain delimiters, or
+    /// * an unquoted integer, float, or string until any of the `delimiters`.
+    ///
+    /// # Errors
+    /// Returns an error if the value is empty, or a list or quoted string
+    /// is not closed.
+    fn parse_annotation_value<B: ByteSource>(
+        parser: &mut ByteParser<B>,
+        key: &str,
+        delimiters: &[u8],
+    ) -> Result<AnnotationValue, ParsingError> {
+        match parser.peek() {
+            Some(b'{') => {
+                parser.next_byte(); // consume '{'
+                let mut values = Vec::new();
+                parser.skip_whitespace();
+                if !parser.peek_is(b'}') {
+                    loop {
+                        parser.skip_whitespace();
+                        values.push(Self::parse_annotation_value(parser, key, b",}")?);
+                        parser.skip_whitespace();
+                        if !parser.consume_if(b',') {
+                            break;
+                        }
+                    }
+                }
+                if !parser.consume_if(b'}') {
+                    return Err(ParsingError::invalid_newick_string(
+                        parser,
+                        format!("Expected '}}' at end of annotation list for key '{}'", key),
+                    ));
+                }
+                Ok(AnnotationValue::List(values))
+            }
+            Some(quote @ (b'\'' | b'"')) => {
+                parser.next_byte(); // consume opening quote
+                let mut value = String::new();
+                loop {
+                    match parser.next_byte() {
+                        // Doubled quote is an escaped quote, single one closes
+                        Some(b) if b == quote && parser.peek() == Some(quote) => {
+                            value.push(b as char);
+                            parser.next_byte();
+                        }
+                        Some(b) if b == quote => break,
+                        Some(b) => value.push(b as char),
+                        None => return Err(ParsingError::unexpected_eof(parser)),
+                    }
+                }
+                Ok(AnnotationValue::String(value))
+            }
+            _ => {
+                let value_str = parser.parse_unquoted_label(delimiters)?;
+                let value_str = value_str.trim_end();
+                if value_str.is_empty() {
+                    return Err(ParsingError::invalid_newick_string(
+                        parser,
+                        format!("Empty annotation value for key '{}'", key),
+                    ));
+                }
+                Ok(if let Ok(v) = value_str.parse::<i64>() {
+                    AnnotationValue::Int(v)
+                } else if let Ok(v) = value_str.parse::<f64>() {
+                    AnnotationValue::Float(v)
+                } else {
+                    AnnotationValue::String(value_str.to_string())
+                })
+            }
+        }
+    }
+
+    /// Skips whitespace and comments around a branch length, but parses
+    /// annotation blocks among them if annotations are enabled:
+    /// - BEAST style blocks as branch annotations (MrBayes style),
+    ///   e.g. `A:[&rate=0.9]0.12` or `A:0.12[&rate=0.9]`
+    /// - NHX blocks as vertex annotations, e.g. `A:0.12[&&NHX:S=human]`
+    fn parse_branch_comments<B: ByteSource>(
+        &mut self,
+        parser: &mut ByteParser<B>,
+        annotations: &mut ParsedAnnotations,
+    ) -> Result<(), ParsingError> {
+        if !self.parse_annotations {
+            return parser.skip_comment_and_whitespace();
+        }
+
+        loop {
+            parser.skip_whitespace();
+            if parser.peek_is_sequence(b"[&&NHX") {
+                let parsed = Self::parse_annotations(parser)?;
+                merge_annotations(&mut annotations.vertex, parsed);
+            } else if parser.peek_is_sequence(b"[&") {
+                let parsed = Self::parse_annotations(parser)?;
+                merge_annotations(&mut annotations.branch, parsed);
+            } else if !parser.skip_comment()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// If annotations were parsed, they are passed onward to the
+    /// [TreeBuilder] to handle, with branch annotations handled according
+    /// to the [BranchAnnotationPolicy].
     fn add_annotations(
         &mut self,
-        annotations: Option<HashMap<String, AnnotationValue>>,
+        annotations: ParsedAnnotations,
         vertex_index: <T as TreeBuilder>::VertexIdx,
     ) {
-        if let Some(annots) = annotations {
-            for (key, value) in annots.iter() {
-                self.tree_builder
-                    .add_annotation(key.clone(), vertex_index, value.clone());
+        let ParsedAnnotations { mut vertex, branch } = annotations;
+        if let Some(branch) = branch {
+            match self.branch_annotation_policy {
+                BranchAnnotationPolicy::Separate => {
+                    for (key, value) in branch {
+                        self.tree_builder
+                            .add_branch_annotation(key, vertex_index, value);
+                    }
+                }
+                BranchAnnotationPolicy::Merge => {
+                    // Vertex annotations take precedence
+                    let mut merged = branch;
+                    merged.extend(vertex.take().unwrap_or_default());
+                    vertex = Some(merged);
+                }
             }
         }
+        if let Some(vertex) = vertex {
+            for (key, value) in vertex {
+                self.tree_builder.add_annotation(key, vertex_index, value);
+            }
+        }
+    }
+}
+
+/// Skips whitespace and comments in between trees, but not comments with
+/// tree-level metadata (starting with `[&`, e.g. `[&R]`) of the next tree.
+fn skip_to_next_tree<B: ByteSource>(parser: &mut ByteParser<B>) -> Result<(), ParsingError> {
+    loop {
+        parser.skip_whitespace();
+        if parser.peek_is_sequence(b"[&") || !parser.skip_comment()? {
+            return Ok(());
+        }
+    }
+}
+
+/// Parses a tree weight given as number (`0.25`) or fraction (`1/4`).
+fn parse_weight(weight_str: &str) -> Option<f64> {
+    match weight_str.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator: f64 = numerator.trim().parse().ok()?;
+            let denominator: f64 = denominator.trim().parse().ok()?;
+            Some(numerator / denominator)
+        }
+        None => weight_str.parse().ok(),
+    }
+}
+
+/// Merges the given annotations into `target`, overwriting on conflicting keys.
+fn merge_annotations(
+    target: &mut Option<HashMap<String, AnnotationValue>>,
+    annotations: Option<HashMap<String, AnnotationValue>>,
+) {
+    match (target.as_mut(), annotations) {
+        (Some(target), Some(annotations)) => target.extend(annotations),
+        (None, annotations) => *target = annotations,
+        (Some(_), None) => {}
     }
 }
 
+/// Splits an extended Newick label `[label]#[type]id` of a hybrid vertex
+/// into label (might be empty) and hybrid identifier (e.g. `H1`);
+/// returns `None` if label contains no `#`.
+fn split_hybrid_label(label: &str) -> Option<(String, String)> {
+    label
+        .split_once('#')
+        .map(|(prefix, hybrid_id)| (prefix.to_string(), hybrid_id.to_string()))
+}
+
 // =#========================================================================#=
 // NEWICK ITERATOR (lazy parser)
 // =#========================================================================$=
 /// Iterator to parse Newick trees.
 ///
 /// Created by [NewickParser::into_iter()].
-/// Yields `Result<T::Tree, ParsingError>` for each tree.
+/// Yields `Result<T::Tree, ParsingError>` for each tree. Iteration stops
+/// after the first error, unless the parser is in lenient mode
+/// (see [NewickParser::with_error_recovery()]), in which case malformed
+/// trees are skipped and their errors recorded in the parser.
 ///
 /// After iteration, the underlying [NewickParser] can be retrieved
 /// via [into_parser()](Self::into_parser) to access the [TreeBuilder]
@@ -614,6 +1391,7 @@ where
 {
     parser: NewickParser<T>,
     byte_parser: ByteParser<B>,
+    tree_index: usize,
     done: bool,
 }
 
@@ -636,28 +1414,34 @@ where
     type Item = Result<T::Tree, ParsingError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
-            return None;
-        }
-
-        match self.parser.parse_str(&mut self.byte_parser) {
-            Ok(tree) => {
-                // Prepare for next call: skip whitespace and check EOF
-                if let Err(e) = self.byte_parser.skip_comment_and_whitespace() {
-                    self.done = true;
-                    return Some(Err(e));
-                }
-
-                if self.byte_parser.is_eof() {
+        while !self.done {
+            let result = self
+                .parser
+                .parse_str_or_recover(&mut self.byte_parser, self.tree_index);
+            self.tree_index += 1;
+
+            let tree = match result {
+                Ok(tree) => tree,
+                Err(err) => {
                     self.done = true;
+                    return Some(Err(err));
                 }
+            };
 
-                Some(Ok(tree))
+            // Prepare for next call: skip whitespace and check EOF
+            if let Err(e) = skip_to_next_tree(&mut self.byte_parser) {
+                self.done = true;
+                return Some(Err(e));
             }
-            Err(err) => {
+            if self.byte_parser.is_eof() {
                 self.done = true;
-                Some(Err(err))
+            }
+
+            // No tree if malformed tree skipped in lenient mode
+            if let Some(tree) = tree {
+                return Some(Ok(tree));
             }
         }
+        None
     }
 }
@@ -1,9 +1,10 @@
 //! Newick format file writing for
 
-use crate::model::CompactTree;
-use crate::model::leaf_label_map::LeafLabelMap;
+use crate::model::annotation::Annotations;
+use crate::model::leaf_label_map::{LabelIndex, LeafLabelMap};
 use crate::model::tree::VertexIndex;
 use crate::model::vertex::BranchLength;
+use crate::model::{AnnotationValue, CompactNetwork, CompactTree};
 use crate::parser::utils::escape_label;
 use std::fs::File;
 use std::io::{self, BufWriter, Write};
@@ -23,6 +24,20 @@ pub enum NewickStyle {
     OneIndexed,
 }
 
+/// Style for serializing vertex annotations to Newick format.
+///
+/// Branch annotations (see
+/// [GenTree::branch_annotations](crate::model::GenTree::branch_annotations))
+/// are written in BEAST style between colon and branch length in either
+/// style, e.g. `A:[&rate=0.9]0.12`.
+#[derive(Debug, Clone, Copy)]
+pub enum AnnotationStyle {
+    /// BEAST style `[&key=value,...]`, preceding the branch length
+    Beast,
+    /// NHX style `[&&NHX:key=value:...]`, following the branch length
+    Nhx,
+}
+
 /// Writes given list of trees to a file in Newick format, one tree per line.
 ///
 /// Each tree is written as a complete Newick string followed by a newline.
@@ -61,6 +76,7 @@ pub fn write_newick_file(
             &NewickStyle::Label,
             tree,
             leaf_label_map,
+            None,
             estimated_capacity,
         );
         writer.write_all(newick.as_bytes())?;
@@ -95,7 +111,7 @@ pub fn write_newick_file(
 /// let mut labels = LeafLabelMap::new(2);
 /// let a = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("A"));
 /// let b = tree.add_leaf(Some(BranchLength::new(2.0)), labels.get_or_insert("B"));
-/// tree.add_root_without_branch((a, b));
+/// tree.add_root_without_branch(vec![a, b]);
 ///
 /// let newick = tree.to_newick(&NewickStyle::Label, Some(&labels));
 /// assert_eq!(newick, "(A:1,B:2);");
@@ -111,7 +127,44 @@ pub fn to_newick(
     }
 
     let estimated_capacity = estimate_newick_len(style, tree, leaf_label_map);
-    to_newick_with_capacity(style, tree, leaf_label_map, estimated_capacity)
+    to_newick_with_capacity(style, tree, leaf_label_map, None, estimated_capacity)
+}
+
+/// Returns the Newick representation of this tree including its vertex
+/// annotations, with closing semicolon.
+///
+/// Annotations are written per vertex in the given [AnnotationStyle],
+/// with keys in sorted order, e.g. `(A[&rate=0.5]:1,B:2);` (BEAST) or
+/// `(A:1[&&NHX:S=human],B:2);` (NHX).
+///
+/// # Arguments
+/// * `style` - The [NewickStyle] used to represent leaf labels in the output
+/// * `annotation_style` - The [AnnotationStyle] used to write annotations
+/// * `tree` - The [CompactTree] to convert
+/// * `leaf_label_map` - [Mapping](LeafLabelMap) required when using [NewickStyle::Label], otherwise can be `None`
+///
+/// # Returns
+/// A Newick format string terminated with `;`. Returns an empty string if
+/// `NewickStyle::Label` is used without providing a [LeafLabelMap].
+pub fn to_newick_with_annotations(
+    style: &NewickStyle,
+    annotation_style: &AnnotationStyle,
+    tree: &CompactTree,
+    leaf_label_map: Option<&LeafLabelMap>,
+) -> String {
+    // Abort right away if arguments don't match
+    if matches!(style, NewickStyle::Label) && leaf_label_map.is_none() {
+        return String::new();
+    }
+
+    let estimated_capacity = estimate_newick_len(style, tree, leaf_label_map);
+    to_newick_with_capacity(
+        style,
+        tree,
+        leaf_label_map,
+        Some(annotation_style),
+        estimated_capacity,
+    )
 }
 
 /// Returns the Newick representation of a tree with pre-allocated capacity.
@@ -123,6 +176,7 @@ pub fn to_newick(
 /// * `style` - The [NewickStyle] used to represent leaf labels in the output
 /// * `tree` - The [GenTree] to convert
 /// * `leaf_label_map` - [Mapping](LeafLabelMap) required when using [NewickStyle::Label], otherwise can be `None`
+/// * `annotation_style` - The [AnnotationStyle] used to write annotations, or `None` to omit them
 /// * `estimated_capacity` - Pre-estimated string capacity/len to avoid reallocations
 ///
 /// # Returns
@@ -131,66 +185,338 @@ pub(crate) fn to_newick_with_capacity(
     style: &NewickStyle,
     tree: &CompactTree,
     leaf_label_map: Option<&LeafLabelMap>,
+    annotation_style: Option<&AnnotationStyle>,
     estimated_capacity: usize,
 ) -> String {
-    // Helper for adding branch lengths
-    fn build_newick_branch_length(newick: &mut String, branch_length: Option<BranchLength>) {
+    // Annotations to write, with keys in sorted order (for deterministic output)
+    struct AnnotationKeys<'a> {
+        style: &'a AnnotationStyle,
+        vertex: Vec<&'a String>,
+        branch: Vec<&'a String>,
+    }
+
+    // Helper for adding annotations of a vertex or branch
+    fn build_newick_annotations(
+        newick: &mut String,
+        annotations: Option<&Annotations>,
+        keys: &[&String],
+        index: VertexIndex,
+        annotation_style: &AnnotationStyle,
+    ) {
+        let Some(annotations) = annotations else {
+            return;
+        };
+        let pairs: Vec<(&String, AnnotationValue)> = keys
+            .iter()
+            .filter_map(|&key| Some((key, annotations.get(key, index)?)))
+            .collect();
+        if pairs.is_empty() {
+            return;
+        }
+
+        let (opening, separator) = match annotation_style {
+            AnnotationStyle::Beast => ("[&", ','),
+            AnnotationStyle::Nhx => ("[&&NHX:", ':'),
+        };
+        newick.push_str(opening);
+        for (i, (key, value)) in pairs.iter().enumerate() {
+            if i > 0 {
+                newick.push(separator);
+            }
+            newick.push_str(key);
+            newick.push('=');
+            newick.push_str(&value.to_string());
+        }
+        newick.push(']');
+    }
+
+    // Helper for adding branch lengths, with branch annotations after the colon
+    fn build_newick_branch_length(
+        tree: &CompactTree,
+        newick: &mut String,
+        index: VertexIndex,
+        branch_length: Option<BranchLength>,
+        annotation_keys: Option<&AnnotationKeys>,
+    ) {
         if let Some(branch_length) = branch_length {
             newick.push(':');
+            if let Some(keys) = annotation_keys {
+                let annotations = tree.branch_annotations();
+                let style = &AnnotationStyle::Beast;
+                build_newick_annotations(newick, annotations, &keys.branch, index, style);
+            }
             newick.push_str(&branch_length.to_string());
         }
     }
 
+    // Helper returning the children of a vertex with the branch lengths to write;
+    // for an unrooted tree whose basal polytomy the parser rooted at its first
+    // child, the added root child is dissolved to write the basal polytomy again,
+    // its branch length added to the first child
+    fn children_to_write(
+        tree: &CompactTree,
+        index: VertexIndex,
+    ) -> Vec<(VertexIndex, Option<BranchLength>)> {
+        let children = tree[index].children().unwrap();
+        let with_branch_length = |&child: &VertexIndex| (child, tree[child].branch_length());
+
+        if tree[index].is_root()
+            && tree.metadata().rooted_at_first_child()
+            && let [first, dissolved] = *children
+            && !tree[dissolved].is_leaf()
+        {
+            let branch_length = match (tree[first].branch_length(), tree[dissolved].branch_length())
+            {
+                (Some(a), Some(b)) => Some(BranchLength::new_signed(*a + *b)),
+                (a, b) => a.or(b),
+            };
+            let grandchildren = tree[dissolved].children().unwrap();
+            let mut written = vec![(first, branch_length)];
+            written.extend(grandchildren.iter().map(with_branch_length));
+            return written;
+        }
+
+        children.iter().map(with_branch_length).collect()
+    }
+
     // Recursive helper for building the Newick string
     fn build_newick(
         tree: &CompactTree,
         newick: &mut String,
         index: VertexIndex,
+        branch_length: Option<BranchLength>,
         style: &NewickStyle,
         leaf_label_map: Option<&LeafLabelMap>,
+        annotation_keys: Option<&AnnotationKeys>,
     ) {
         let vertex = &tree[index];
 
         if vertex.is_leaf() {
-            // Add label based on style
             let label_index = vertex.label().unwrap();
-            match style {
-                NewickStyle::Label => {
-                    let label = &leaf_label_map.unwrap()[*label_index];
-                    let escaped = escape_label(label);
-                    newick.push_str(&escaped);
-                }
-                NewickStyle::ZeroIndexed => {
-                    newick.push_str(&label_index.to_string());
-                }
-                NewickStyle::OneIndexed => {
-                    newick.push_str(&(label_index + 1).to_string());
-                }
-            }
-            build_newick_branch_length(newick, vertex.branch_length());
+            push_leaf_label(newick, *label_index, style, leaf_label_map);
         } else {
-            let (left, right) = vertex.children().unwrap();
-
             newick.push('(');
-            build_newick(tree, newick, left, style, leaf_label_map);
-            newick.push(',');
-            build_newick(tree, newick, right, style, leaf_label_map);
+            for (i, (child, child_branch_length)) in
+                children_to_write(tree, index).into_iter().enumerate()
+            {
+                if i > 0 {
+                    newick.push(',');
+                }
+                build_newick(
+                    tree,
+                    newick,
+                    child,
+                    child_branch_length,
+                    style,
+                    leaf_label_map,
+                    annotation_keys,
+                );
+            }
             newick.push(')');
 
-            if !vertex.is_root() {
-                build_newick_branch_length(newick, vertex.branch_length());
+            if let Some(label) = tree.internal_label(index) {
+                newick.push_str(&escape_label(label));
             }
         }
+
+        // BEAST annotations precede branch length, NHX annotations follow it
+        let annotations = tree.annotations();
+        if let Some(
+            keys @ AnnotationKeys {
+                style: AnnotationStyle::Beast,
+                ..
+            },
+        ) = annotation_keys
+        {
+            build_newick_annotations(newick, annotations, &keys.vertex, index, keys.style);
+        }
+        if !vertex.is_root() {
+            build_newick_branch_length(tree, newick, index, branch_length, annotation_keys);
+        }
+        if let Some(
+            keys @ AnnotationKeys {
+                style: AnnotationStyle::Nhx,
+                ..
+            },
+        ) = annotation_keys
+        {
+            build_newick_annotations(newick, annotations, &keys.vertex, index, keys.style);
+        }
+    }
+
+    // Helper for collecting sorted keys
+    fn sorted_keys(annotations: Option<&Annotations>) -> Vec<&String> {
+        let mut keys: Vec<&String> = annotations.map(|a| a.keys().collect()).unwrap_or_default();
+        keys.sort();
+        keys
     }
 
     let mut newick = String::with_capacity(estimated_capacity);
 
-    build_newick(tree, &mut newick, tree.root_index(), style, leaf_label_map);
+    let annotation_keys = annotation_style.map(|style| AnnotationKeys {
+        style,
+        vertex: sorted_keys(tree.annotations()),
+        branch: sorted_keys(tree.branch_annotations()),
+    });
+
+    build_newick(
+        tree,
+        &mut newick,
+        tree.root_index(),
+        None,
+        style,
+        leaf_label_map,
+        annotation_keys.as_ref(),
+    );
     newick.push(';');
 
     newick
 }
 
+/// Returns the extended Newick (eNewick) representation of a network
+/// with closing semicolon.
+///
+/// Each hybrid vertex is written once with its subtree and referenced as
+/// `#H1` (its identifier, or a generated one) by its other parents.
+/// Inheritance probabilities are written as third value of the edge:
+/// `#H1:branch_length::inheritance`.
+/// For example: `((A:1,(B:1)#H1:0.5::0.3):1,(#H1:0.2::0.7,C:1):1);`
+///
+/// # Arguments
+/// * `style` - The [NewickStyle] used to represent leaf labels in the output
+/// * `network` - The [CompactNetwork] to convert
+/// * `leaf_label_map` - [Mapping](LeafLabelMap) required when using [NewickStyle::Label], otherwise can be `None`
+///
+/// # Returns
+/// An eNewick format string terminated with `;`. Returns an empty string if
+/// `NewickStyle::Label` is used without providing a [LeafLabelMap].
+pub fn to_enewick(
+    style: &NewickStyle,
+    network: &CompactNetwork,
+    leaf_label_map: Option<&LeafLabelMap>,
+) -> String {
+    // Recursive helper for building the eNewick string
+    fn build_enewick(
+        network: &CompactNetwork,
+        newick: &mut String,
+        index: VertexIndex,
+        hybrid_ids: &[Option<String>],
+        written: &mut [bool],
+        style: &NewickStyle,
+        leaf_label_map: Option<&LeafLabelMap>,
+    ) {
+        let vertex = &network[index];
+
+        // Children are written only at first occurrence
+        if !written[index] {
+            written[index] = true;
+            if !vertex.is_leaf() {
+                newick.push('(');
+                for (i, &child) in vertex.children().iter().enumerate() {
+                    if i > 0 {
+                        newick.push(',');
+                    }
+                    build_enewick(
+                        network,
+                        newick,
+                        child,
+                        hybrid_ids,
+                        written,
+                        style,
+                        leaf_label_map,
+                    );
+                    // Incoming edge of child
+                    let edge = network[child]
+                        .parents()
+                        .iter()
+                        .find(|e| e.parent() == index);
+                    if let Some(edge) = edge {
+                        if let Some(branch_length) = edge.branch_length() {
+                            newick.push(':');
+                            newick.push_str(&branch_length.to_string());
+                        }
+                        if let Some(inheritance) = edge.inheritance() {
+                            if edge.branch_length().is_none() {
+                                newick.push(':');
+                            }
+                            newick.push_str("::");
+                            newick.push_str(&inheritance.to_string());
+                        }
+                    }
+                }
+                newick.push(')');
+            }
+            if let Some(label_index) = vertex.label() {
+                push_leaf_label(newick, *label_index, style, leaf_label_map);
+            } else if let Some(label) = network.internal_label(index) {
+                newick.push_str(&escape_label(label));
+            }
+        }
+
+        if let Some(hybrid_id) = &hybrid_ids[index] {
+            newick.push('#');
+            newick.push_str(hybrid_id);
+        }
+    }
+
+    // Abort right away if arguments don't match
+    if matches!(style, NewickStyle::Label) && leaf_label_map.is_none() {
+        return String::new();
+    }
+
+    // Identifiers of hybrids, generated if not set
+    let mut num_hybrids = 0;
+    let hybrid_ids: Vec<Option<String>> = network
+        .vertices()
+        .map(|vertex| {
+            vertex.is_hybrid().then(|| {
+                num_hybrids += 1;
+                vertex
+                    .hybrid_id()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("H{}", num_hybrids))
+            })
+        })
+        .collect();
+
+    let mut newick = String::new();
+    let mut written = vec![false; network.num_vertices()];
+    build_enewick(
+        network,
+        &mut newick,
+        network.root_index(),
+        &hybrid_ids,
+        &mut written,
+        style,
+        leaf_label_map,
+    );
+    newick.push(';');
+
+    newick
+}
+
+/// Appends the label of a leaf based on the given style.
+fn push_leaf_label(
+    newick: &mut String,
+    label_index: LabelIndex,
+    style: &NewickStyle,
+    leaf_label_map: Option<&LeafLabelMap>,
+) {
+    match style {
+        NewickStyle::Label => {
+            let label = &leaf_label_map.unwrap()[label_index];
+            let escaped = escape_label(label);
+            newick.push_str(&escaped);
+        }
+        NewickStyle::ZeroIndexed => {
+            newick.push_str(&label_index.to_string());
+        }
+        NewickStyle::OneIndexed => {
+            newick.push_str(&(label_index + 1).to_string());
+        }
+    }
+}
+
 /// Estimates the length of a Newick string for a given tree.
 ///
 /// This function calculates the expected number of characters needed to represent
@@ -209,14 +535,15 @@ pub(crate) fn estimate_newick_len(
     tree: &CompactTree,
     leaf_label_map: Option<&LeafLabelMap>,
 ) -> usize {
-    // Each internal node: "(,)" ~= 3 chars
-    const INTERNAL_NODE_CHARS: usize = 3; // "(,)"
+    // Each internal node: "()" = 2 chars, plus one "," per non-first child
+    const INTERNAL_NODE_CHARS: usize = 2; // "()"
     // Branch lengths: ~20 chars each (e.g., ":0.009529961339106089")
     const BRANCH_LENGTH_CHARS: usize = 20;
 
     // -> Structural
     let num_internal = tree.num_internal() + 1; // +1 for root
-    let structure_capacity = num_internal * INTERNAL_NODE_CHARS;
+    let num_commas = tree.num_vertices().saturating_sub(1 + num_internal);
+    let structure_capacity = num_internal * INTERNAL_NODE_CHARS + num_commas;
 
     // -> Labels
     let num_leaves = tree.num_leaves();
@@ -233,6 +560,14 @@ pub(crate) fn estimate_newick_len(
         NewickStyle::ZeroIndexed => calculate_index_digit_capacity(num_leaves, true),
         NewickStyle::OneIndexed => calculate_index_digit_capacity(num_leaves, false),
     };
+    let internal_label_capacity: usize = if tree.has_internal_labels() {
+        (0..tree.num_vertices())
+            .filter_map(|index| tree.internal_label(index))
+            .map(str::len)
+            .sum()
+    } else {
+        0
+    };
 
     // -> Branch lengths
     let branch_capacity = if tree.vertices_have_branch_lengths() {
@@ -242,7 +577,7 @@ pub(crate) fn estimate_newick_len(
     };
 
     // => Total
-    structure_capacity + label_capacity + branch_capacity + BUFFER_CHARS
+    structure_capacity + label_capacity + internal_label_capacity + branch_capacity + BUFFER_CHARS
 }
 
 /// Calculates the total number of characters needed to represent all indices.
@@ -0,0 +1,430 @@
+//! Low-level reader walking through the blocks of any Nexus file.
+//!
+//! [NexusBlockReader] yields each block as a [RawBlock] with its name and
+//! commands, where each [RawCommand] is split into [NexusToken]s. This allows
+//! reading blocks that the library does not model, e.g. `MRBAYES`, `PAUP`,
+//! `ASSUMPTIONS`, or `FIGTREE` blocks, without re-implementing Nexus
+//! tokenisation.
+//!
+//! # Tokenisation
+//! Commands end with a semicolon and are split into:
+//! * words, i.e. runs of characters up to whitespace or one of `;=,`, where
+//!   brackets within a word are kept, e.g. of label `Bact_[Eubacterium]_eligens`,
+//! * single (`'...'`, with `''` as escaped quote) or double quoted tokens,
+//! * comments (`[...]`) within a command, e.g. `[&R]`,
+//! * `=` and `,` as separate tokens.
+//!
+//! Comments between commands are skipped, as is anything between blocks.
+//! Keywords like `BEGIN` and `END` are matched case-insensitively as whole
+//! words, so any whitespace, newlines, and comments may separate tokens.
+//!
+//! The same tokenisation is used by the parsers of the blocks modelled by the
+//! library, e.g. of TAXA and TREES blocks.
+
+use crate::nexus::defs::{BLOCK_BEGIN, BLOCK_END_KEYWORDS, NEXUS_HEADER, NexusBlock};
+use crate::parser::buffered_byte_source::BufferedByteSource;
+use crate::parser::byte_parser::ByteParser;
+use crate::parser::byte_source::ByteSource;
+use crate::parser::in_memory_byte_source::InMemoryByteSource;
+use crate::parser::parsing_error::ParsingError;
+use std::path::Path;
+
+/// Delimiters of keywords and names, e.g. `BEGIN` or the name of a tree
+pub(crate) const WORD_DELIMITERS: &[u8] = b" \t\n\r;=,['\"";
+
+/// Delimiters of word tokens, which may contain brackets and quotes (but not start with them)
+const TOKEN_DELIMITERS: &[u8] = b" \t\n\r;=,";
+
+// =#========================================================================#=
+// NEXUS TOKEN
+// =#========================================================================$=
+/// Token of a Nexus command, see [module docs](self) for the tokenisation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NexusToken {
+    /// Unquoted word, e.g. `ngen`, `1000000`, or `Bact_[Eubacterium]_eligens`
+    Word(String),
+    /// Quoted token without its quotes, e.g. `Wilson's storm-petrel`
+    Quoted(String),
+    /// Comment without its brackets, e.g. `&R` of `[&R]`
+    Comment(String),
+    /// Equals sign `=`
+    Equals,
+    /// Comma `,`
+    Comma,
+}
+
+impl NexusToken {
+    /// Returns the text of a word or quoted token, `None` for other tokens.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            NexusToken::Word(text) | NexusToken::Quoted(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+// =#========================================================================#=
+// RAW COMMAND
+// =#========================================================================$=
+/// Command of a Nexus block, i.e. its name and the tokens up to its semicolon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawCommand {
+    name: String,
+    tokens: Vec<NexusToken>,
+}
+
+impl RawCommand {
+    /// Returns the name of the command as written, e.g. `mcmcp`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether the command has the given name (case-insensitive).
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    /// Returns the tokens following the command name (without semicolon).
+    pub fn tokens(&self) -> &[NexusToken] {
+        &self.tokens
+    }
+
+    /// Returns an iterator over the words and quoted tokens, skipping
+    /// comments, `=`, and `,`.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.tokens.iter().filter_map(NexusToken::as_str)
+    }
+
+    /// Returns an iterator over all `key = value` pairs of the command,
+    /// e.g. `("ngen", "1000000")` of `mcmcp ngen=1000000;`, ignoring
+    /// comments in between.
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        let tokens: Vec<&NexusToken> = self
+            .tokens
+            .iter()
+            .filter(|token| !matches!(token, NexusToken::Comment(_)))
+            .collect();
+        let pairs: Vec<(&str, &str)> = tokens
+            .windows(3)
+            .filter_map(|window| match window {
+                [key, NexusToken::Equals, value] => Some((key.as_str()?, value.as_str()?)),
+                _ => None,
+            })
+            .collect();
+        pairs.into_iter()
+    }
+
+    /// Returns the value of the first `key = value` pair with the given key
+    /// (case-insensitive), if any.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.pairs()
+            .find(|(pair_key, _)| pair_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+}
+
+// =#========================================================================#=
+// RAW BLOCK
+// =#========================================================================$=
+/// Block of a Nexus file with its name and commands (without the closing
+/// `END;`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawBlock {
+    name: String,
+    commands: Vec<RawCommand>,
+}
+
+impl RawBlock {
+    /// Returns the name of the block as written, e.g. `mrbayes`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the kind of block, [NexusBlock::UnknownBlock] for any block
+    /// not known to the library.
+    pub fn kind(&self) -> NexusBlock {
+        NexusBlock::from_name(&self.name)
+    }
+
+    /// Returns all commands of the block, in order.
+    pub fn commands(&self) -> &[RawCommand] {
+        &self.commands
+    }
+
+    /// Returns the first command with the given name (case-insensitive), if any.
+    pub fn command(&self, name: &str) -> Option<&RawCommand> {
+        self.commands.iter().find(|command| command.is(name))
+    }
+}
+
+// =#========================================================================#=
+// NEXUS BLOCK READER
+// =#========================================================================$=
+/// Reader yielding the blocks of a Nexus file as [RawBlock]s, one by one.
+///
+/// # Example
+/// ```
+/// use nexwick::nexus::{NexusBlock, NexusBlockReader};
+///
+/// let nexus = "#NEXUS
+///     begin mrbayes;
+///         set autoclose=yes;
+///         mcmcp ngen=1000000 samplefreq=1000 [every 1000th generation];
+///     end;";
+///
+/// for block in NexusBlockReader::for_str(nexus) {
+///     let block = block?;
+///     assert_eq!(block.kind(), NexusBlock::UnknownBlock(String::from("mrbayes")));
+///     let mcmcp = block.command("MCMCP").unwrap();
+///     assert_eq!(mcmcp.value("ngen"), Some("1000000"));
+/// }
+/// # Ok::<(), nexwick::parser::ParsingError>(())
+/// ```
+pub struct NexusBlockReader<B: ByteSource> {
+    parser: ByteParser<B>,
+    /// Whether the `#NEXUS` header has been parsed
+    header_parsed: bool,
+    /// Whether the end of the file or an error has been reached
+    finished: bool,
+}
+
+impl NexusBlockReader<InMemoryByteSource> {
+    /// Creates a reader for a Nexus file, reading the entire file into memory.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the file cannot be read
+    pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self::new(ByteParser::from_file_in_memory(path)?))
+    }
+
+    /// Creates a reader for Nexus content given as string.
+    pub fn for_str(input: &str) -> Self {
+        Self::new(ByteParser::for_str(input))
+    }
+}
+
+impl NexusBlockReader<BufferedByteSource> {
+    /// Creates a reader for a Nexus file, reading it through a buffered reader.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the file cannot be opened
+    pub fn from_file_buffered<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self::new(ByteParser::from_file_buffered(path)?))
+    }
+}
+
+impl<B: ByteSource> NexusBlockReader<B> {
+    /// Creates a reader on top of a [ByteParser] positioned at the start
+    /// of a Nexus file.
+    pub fn new(parser: ByteParser<B>) -> Self {
+        Self {
+            parser,
+            header_parsed: false,
+            finished: false,
+        }
+    }
+
+    /// Parses and returns the next block, or `None` at the end of the file.
+    ///
+    /// # Errors
+    /// Returns a [ParsingError] if the `#NEXUS` header is missing, a block
+    /// does not start with `BEGIN <name>;`, or a block, command, quoted
+    /// token, or comment is not closed.
+    pub fn next_block(&mut self) -> Result<Option<RawBlock>, ParsingError> {
+        if !self.header_parsed {
+            self.parser.skip_comment_and_whitespace()?;
+            if !self.parser.consume_if_sequence(NEXUS_HEADER) {
+                return Err(ParsingError::missing_nexus_header(&mut self.parser));
+            }
+            self.header_parsed = true;
+        }
+
+        // "BEGIN <name>;"
+        self.parser.skip_comment_and_whitespace()?;
+        if self.parser.is_eof() {
+            return Ok(None);
+        }
+        if !self.parser.consume_if_keyword(BLOCK_BEGIN, WORD_DELIMITERS) {
+            return Err(ParsingError::invalid_formatting(&mut self.parser));
+        }
+        let Some(header) = next_command(&mut self.parser)? else {
+            return Err(ParsingError::invalid_block_name(&mut self.parser));
+        };
+        let name = header.name;
+
+        // Commands until "END;" (or "ENDBLOCK;")
+        let mut commands = Vec::new();
+        loop {
+            self.parser.skip_comment_and_whitespace()?;
+            if consume_block_end(&mut self.parser)? {
+                break;
+            }
+            if let Some(command) = next_command(&mut self.parser)? {
+                commands.push(command);
+            }
+        }
+
+        Ok(Some(RawBlock { name, commands }))
+    }
+}
+
+// =#========================================================================#=
+// COMMAND TOKENISATION
+// =#========================================================================$=
+/// Parses the next command including its semicolon, skipping comments
+/// before its name; returns `None` for an empty command.
+///
+/// # Errors
+/// Returns a [ParsingError] if the command, a quoted token, or a comment
+/// is not closed.
+pub(crate) fn next_command<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+) -> Result<Option<RawCommand>, ParsingError> {
+    let mut name = None;
+    let mut tokens = Vec::new();
+    while let Some(token) = next_token(parser)? {
+        match (&name, token) {
+            (None, NexusToken::Comment(_)) => {}
+            (None, token) => name = Some(token.as_str().unwrap_or_default().to_string()),
+            (Some(_), token) => tokens.push(token),
+        }
+    }
+    Ok(name.map(|name| RawCommand { name, tokens }))
+}
+
+/// Parses the next token of a command, or returns `None` after consuming
+/// the semicolon ending the command.
+///
+/// # Errors
+/// Returns a [ParsingError] at the end of input, or if a quoted token or
+/// comment is not closed.
+pub(crate) fn next_token<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+) -> Result<Option<NexusToken>, ParsingError> {
+    parser.skip_whitespace();
+    let token = match parser.peek() {
+        None => return Err(ParsingError::unexpected_eof(parser)),
+        Some(b';') => {
+            parser.next_byte();
+            return Ok(None);
+        }
+        Some(b'=') => {
+            parser.next_byte();
+            NexusToken::Equals
+        }
+        Some(b',') => {
+            parser.next_byte();
+            NexusToken::Comma
+        }
+        Some(b'[') => NexusToken::Comment(parse_delimited(parser, b'[', b']')?),
+        Some(b'"') => NexusToken::Quoted(parse_delimited(parser, b'"', b'"')?),
+        Some(b'\'') => NexusToken::Quoted(parser.parse_quoted_label()?),
+        Some(_) => NexusToken::Word(parser.parse_unquoted_label(TOKEN_DELIMITERS)?),
+    };
+    Ok(Some(token))
+}
+
+/// Parses the text enclosed by `open` and `close`, e.g. of a comment;
+/// nested comments are kept in the text, e.g. `a [b] c` of `[a [b] c]`.
+fn parse_delimited<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    open: u8,
+    close: u8,
+) -> Result<String, ParsingError> {
+    parser.consume_if(open);
+    let mut text = Vec::new();
+    let mut depth = 0;
+    loop {
+        match parser.next_byte() {
+            Some(byte) if byte == close && depth == 0 => {
+                return Ok(String::from_utf8_lossy(&text).into());
+            }
+            Some(byte) if open == b'[' && (byte == open || byte == close) => {
+                depth = if byte == open { depth + 1 } else { depth - 1 };
+                text.push(byte);
+            }
+            Some(byte) => text.push(byte),
+            None if open == b'[' => return Err(ParsingError::unclosed_comment(parser)),
+            None => return Err(ParsingError::unexpected_eof(parser)),
+        }
+    }
+}
+
+/// Skips the rest of the current command including its semicolon,
+/// respecting quotes and comments.
+pub(crate) fn skip_command<B: ByteSource>(parser: &mut ByteParser<B>) -> Result<(), ParsingError> {
+    while next_token(parser)?.is_some() {}
+    Ok(())
+}
+
+/// Returns whether the parser is positioned at `END;` or `ENDBLOCK;`
+/// (case-insensitive), without consuming it.
+pub(crate) fn peek_is_block_end<B: ByteSource>(parser: &mut ByteParser<B>) -> bool {
+    BLOCK_END_KEYWORDS
+        .iter()
+        .any(|keyword| parser.peek_is_keyword(keyword, WORD_DELIMITERS))
+}
+
+/// Consumes `END;` or `ENDBLOCK;` (case-insensitive, with optional whitespace
+/// and comments before the semicolon), if the parser is positioned at it;
+/// otherwise the position is not changed.
+///
+/// # Errors
+/// Returns a [ParsingError] if a comment after the keyword is not closed.
+pub(crate) fn consume_block_end<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+) -> Result<bool, ParsingError> {
+    let keyword = BLOCK_END_KEYWORDS
+        .iter()
+        .find(|keyword| parser.peek_is_keyword(keyword, WORD_DELIMITERS));
+    let Some(keyword) = keyword else {
+        return Ok(false);
+    };
+
+    let start_pos = parser.position();
+    parser.consume_if_sequence(keyword);
+    parser.skip_comment_and_whitespace()?;
+    if parser.consume_if(b';') {
+        return Ok(true);
+    }
+
+    // (Not followed by semicolon, so just a word starting like the keyword)
+    parser.set_position(start_pos);
+    Ok(false)
+}
+
+/// Skips the rest of the current block including its `END;`, token by token.
+///
+/// Quoted tokens and comments are skipped as a whole, so an `END;` within
+/// them does not end the block; commands need not be terminated, though, so
+/// that blocks with arbitrary content (e.g. of other programs) can be skipped.
+///
+/// # Errors
+/// Returns a [ParsingError] if the block, a quoted token, or a comment is
+/// not closed.
+pub(crate) fn skip_block<B: ByteSource>(parser: &mut ByteParser<B>) -> Result<(), ParsingError> {
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if consume_block_end(parser)? {
+            return Ok(());
+        }
+        next_token(parser)?;
+    }
+}
+
+impl<B: ByteSource> Iterator for NexusBlockReader<B> {
+    type Item = Result<RawBlock, ParsingError>;
+
+    /// Returns the next block, stopping after the end of the file or the
+    /// first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let result = self.next_block().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.finished = true;
+        }
+        result
+    }
+}
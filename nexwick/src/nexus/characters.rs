@@ -0,0 +1,372 @@
+//! Parsing of Nexus `DATA` and `CHARACTERS` blocks into an [Alignment].
+//!
+//! Supported commands (case-insensitive), any others are skipped:
+//! * `DIMENSIONS [NTAX=<n>] NCHAR=<m>;`
+//! * `FORMAT [DATATYPE=<type>] [MISSING=<symbol>] [GAP=<symbol>]
+//!   [MATCHCHAR=<symbol>] [INTERLEAVE[=YES|NO]];`
+//! * `MATRIX <label> <sequence> ...;`, sequential or interleaved
+//!
+//! Symbols of molecular data types are stored in uppercase. Polymorphic or
+//! uncertain states, e.g. `(01)` or `{AG}`, are stored as missing data.
+
+use crate::model::{Alignment, DataType};
+use crate::nexus::block_reader::{consume_block_end, skip_command};
+use crate::nexus::defs::NEXUS_LABEL_DELIMITERS;
+use crate::parser::byte_parser::{ByteParser, ConsumeMode::Inclusive};
+use crate::parser::byte_source::ByteSource;
+use crate::parser::parsing_error::ParsingError;
+use std::collections::HashMap;
+
+/// Delimiters of command names as well as keys and values of their options
+pub(super) const OPTION_DELIMITERS: &[u8] = b" \t\n\r=;";
+
+/// Format of a character matrix, given by `DIMENSIONS` and `FORMAT` commands
+struct MatrixFormat {
+    num_taxa: Option<usize>,
+    num_chars: Option<usize>,
+    data_type: DataType,
+    missing: u8,
+    gap: u8,
+    match_char: Option<u8>,
+    interleave: bool,
+}
+
+impl Default for MatrixFormat {
+    fn default() -> Self {
+        Self {
+            num_taxa: None,
+            num_chars: None,
+            data_type: DataType::default(),
+            missing: b'?',
+            gap: b'-',
+            match_char: None,
+            interleave: false,
+        }
+    }
+}
+
+impl MatrixFormat {
+    /// Normalizes a symbol, i.e. to uppercase for molecular data types
+    fn normalize(&self, symbol: u8) -> u8 {
+        if self.data_type.is_molecular() {
+            symbol.to_ascii_uppercase()
+        } else {
+            symbol
+        }
+    }
+}
+
+/// Parses a `DATA` or `CHARACTERS` block into an [Alignment], including
+/// its closing `END;`.
+///
+/// Assumes the parser is positioned right after the block header.
+///
+/// # Arguments
+/// * `parser` - The byte parser positioned after `BEGIN DATA;`
+/// * `taxa` - Taxon labels in order of the file (e.g. of the TAXA block) to
+///   order the sequences by; taxa without sequence get only missing data.
+///   If empty, sequences are kept in order of the `MATRIX` command.
+///
+/// # Errors
+/// Returns a [ParsingError] if the block is not properly closed,
+/// the data type is not supported, or the matrix is inconsistent with the
+/// given dimensions or taxa.
+pub(crate) fn parse_characters_block<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    taxa: &[String],
+) -> Result<Alignment, ParsingError> {
+    let mut format = MatrixFormat::default();
+    let mut rows = None;
+
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if consume_block_end(parser)? {
+            break;
+        }
+        if parser.is_eof() {
+            return Err(ParsingError::unexpected_eof(parser));
+        }
+
+        let command = parser.parse_unquoted_label(OPTION_DELIMITERS)?;
+        match command.to_ascii_lowercase().as_str() {
+            "dimensions" => parse_dimensions(parser, &mut format)?,
+            "format" => parse_format(parser, &mut format)?,
+            "matrix" => rows = Some(parse_matrix(parser, &format)?),
+            // Skip any other command
+            _ => skip_command(parser)?,
+        }
+    }
+
+    let Some(rows) = rows else {
+        return Err(ParsingError::invalid_characters_block(
+            parser,
+            String::from("Missing MATRIX command."),
+        ));
+    };
+    build_alignment(parser, rows, &format, taxa)
+}
+
+/// Parses the options of `DIMENSIONS` command, i.e. `NTAX` and `NCHAR`.
+fn parse_dimensions<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &mut MatrixFormat,
+) -> Result<(), ParsingError> {
+    for (key, value) in parse_options(parser)? {
+        let count = match (key.as_str(), &value) {
+            ("ntax" | "nchar", Some(value)) => value.parse::<usize>().map_err(|_| {
+                ParsingError::invalid_characters_block(
+                    parser,
+                    format!("Cannot parse `{key}` value: {value}"),
+                )
+            })?,
+            _ => continue,
+        };
+        if key == "ntax" {
+            format.num_taxa = Some(count);
+        } else {
+            format.num_chars = Some(count);
+        }
+    }
+    Ok(())
+}
+
+/// Parses the options of `FORMAT` command relevant for the [Alignment].
+fn parse_format<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &mut MatrixFormat,
+) -> Result<(), ParsingError> {
+    for (key, value) in parse_options(parser)? {
+        let symbol = value.as_ref().and_then(|value| value.bytes().next());
+        match key.as_str() {
+            "datatype" => {
+                let name = value.unwrap_or_default();
+                format.data_type = DataType::from_name(&name).ok_or_else(|| {
+                    ParsingError::invalid_characters_block(
+                        parser,
+                        format!("Unsupported datatype: {name}"),
+                    )
+                })?;
+            }
+            "missing" => format.missing = symbol.unwrap_or(format.missing),
+            "gap" => format.gap = symbol.unwrap_or(format.gap),
+            "matchchar" => format.match_char = symbol,
+            "interleave" => {
+                format.interleave = value.is_none_or(|value| value.eq_ignore_ascii_case("yes"))
+            }
+            _ => {}
+        }
+    }
+
+    format.missing = format.normalize(format.missing);
+    format.gap = format.normalize(format.gap);
+    format.match_char = format.match_char.map(|symbol| format.normalize(symbol));
+    Ok(())
+}
+
+/// Parses the options `key[=value]` of a command until and including its
+/// semicolon; keys are lowercased and values might be quoted
+/// (`"..."` or `'...'`).
+pub(super) fn parse_options<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+) -> Result<Vec<(String, Option<String>)>, ParsingError> {
+    let mut options = Vec::new();
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if parser.consume_if(b';') {
+            return Ok(options);
+        }
+
+        let key = parser.parse_unquoted_label(OPTION_DELIMITERS)?;
+        parser.skip_comment_and_whitespace()?;
+        let value = if parser.consume_if(b'=') {
+            parser.skip_comment_and_whitespace()?;
+            Some(parse_option_value(parser)?)
+        } else {
+            None
+        };
+        options.push((key.to_ascii_lowercase(), value));
+    }
+}
+
+/// Parses the value of an option, which might be double quoted (`"0 1 2"`)
+/// or single quoted.
+fn parse_option_value<B: ByteSource>(parser: &mut ByteParser<B>) -> Result<String, ParsingError> {
+    if !parser.consume_if(b'"') {
+        return parser.parse_label(OPTION_DELIMITERS);
+    }
+
+    let mut value = Vec::new();
+    loop {
+        match parser.next_byte() {
+            Some(b'"') => return Ok(String::from_utf8_lossy(&value).into_owned()),
+            Some(byte) => value.push(byte),
+            None => return Err(ParsingError::unexpected_eof(parser)),
+        }
+    }
+}
+
+/// Parses the rows of the `MATRIX` command until and including its
+/// semicolon, returning the label and sequence of each row (in order).
+///
+/// Sequential rows are read until `NCHAR` characters are parsed, even
+/// across lines; interleaved rows only until the end of the line and
+/// get appended to earlier rows of the same label.
+fn parse_matrix<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &MatrixFormat,
+) -> Result<Vec<(String, Vec<u8>)>, ParsingError> {
+    let num_chars = match (format.interleave, format.num_chars) {
+        (true, _) => None,
+        (false, Some(num_chars)) => Some(num_chars),
+        (false, None) => {
+            return Err(ParsingError::invalid_characters_block(
+                parser,
+                String::from("Expected 'DIMENSIONS NCHAR=<m>' before MATRIX."),
+            ));
+        }
+    };
+
+    let mut rows: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut row_of_label: HashMap<String, usize> = HashMap::new();
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if parser.consume_if(b';') {
+            break;
+        }
+        if parser.is_eof() {
+            return Err(ParsingError::unexpected_eof(parser));
+        }
+
+        let label = parser.parse_label(NEXUS_LABEL_DELIMITERS)?;
+        let row = match row_of_label.get(&label) {
+            Some(&row) => row,
+            None => {
+                row_of_label.insert(label.clone(), rows.len());
+                rows.push((label, Vec::new()));
+                rows.len() - 1
+            }
+        };
+        parse_states(parser, format, &mut rows[row].1, num_chars)?;
+    }
+
+    // Replace match characters by the character of the first row
+    if let Some(match_char) = format.match_char
+        && let Some(((_, first), others)) = rows.split_first_mut()
+    {
+        for (_, sequence) in others {
+            for (site, symbol) in sequence.iter_mut().enumerate() {
+                if *symbol == match_char
+                    && let Some(&first_symbol) = first.get(site)
+                {
+                    *symbol = first_symbol;
+                }
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Parses the states (characters) of a row in the `MATRIX` command and
+/// appends them to `sequence`, until the sequence has `num_chars` many
+/// characters or, if `None`, until the end of the line (interleaved);
+/// stops early at the semicolon ending the matrix.
+fn parse_states<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &MatrixFormat,
+    sequence: &mut Vec<u8>,
+    num_chars: Option<usize>,
+) -> Result<(), ParsingError> {
+    loop {
+        if num_chars.is_some_and(|num_chars| sequence.len() >= num_chars) {
+            return Ok(());
+        }
+
+        match parser.peek() {
+            None => return Err(ParsingError::unexpected_eof(parser)),
+            Some(b';') => return Ok(()),
+            Some(b'\n' | b'\r') if num_chars.is_none() => return Ok(()),
+            Some(b' ' | b'\t' | b'\n' | b'\r') => {
+                parser.next_byte();
+            }
+            Some(b'[') => {
+                parser.skip_comment()?;
+            }
+            // Polymorphic or uncertain state stored as missing data
+            Some(open @ (b'(' | b'{')) => {
+                let close = if open == b'(' { b')' } else { b'}' };
+                if !parser.consume_until(close, Inclusive) {
+                    return Err(ParsingError::unexpected_eof(parser));
+                }
+                sequence.push(format.missing);
+            }
+            Some(symbol) => {
+                parser.next_byte();
+                sequence.push(format.normalize(symbol));
+            }
+        }
+    }
+}
+
+/// Builds the [Alignment] from the parsed rows after checking them against
+/// the dimensions, ordering them by `taxa` (if not empty).
+fn build_alignment<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    rows: Vec<(String, Vec<u8>)>,
+    format: &MatrixFormat,
+    taxa: &[String],
+) -> Result<Alignment, ParsingError> {
+    if let Some(num_taxa) = format.num_taxa
+        && rows.len() != num_taxa
+    {
+        return Err(ParsingError::invalid_characters_block(
+            parser,
+            format!(
+                "Number of sequences ({}) did not match ntax value ({}).",
+                rows.len(),
+                num_taxa
+            ),
+        ));
+    }
+
+    let num_chars = format
+        .num_chars
+        .unwrap_or_else(|| rows.first().map_or(0, |(_, sequence)| sequence.len()));
+    if let Some((label, sequence)) = rows.iter().find(|(_, seq)| seq.len() != num_chars) {
+        return Err(ParsingError::invalid_characters_block(
+            parser,
+            format!(
+                "Sequence of taxon '{}' has {} instead of {} characters.",
+                label,
+                sequence.len(),
+                num_chars
+            ),
+        ));
+    }
+
+    let mut alignment = Alignment::new(format.data_type)
+        .with_missing(format.missing)
+        .with_gap(format.gap);
+    if taxa.is_empty() {
+        for (label, sequence) in rows {
+            alignment.add_sequence(&label, sequence);
+        }
+        return Ok(alignment);
+    }
+
+    let mut sequences: HashMap<String, Vec<u8>> = rows.into_iter().collect();
+    for taxon in taxa {
+        let sequence = sequences
+            .remove(taxon)
+            .unwrap_or_else(|| vec![format.missing; num_chars]);
+        alignment.add_sequence(taxon, sequence);
+    }
+    if let Some(label) = sequences.keys().next() {
+        return Err(ParsingError::invalid_characters_block(
+            parser,
+            format!("Unknown taxon '{label}' in MATRIX."),
+        ));
+    }
+
+    Ok(alignment)
+}
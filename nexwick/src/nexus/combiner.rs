@@ -0,0 +1,289 @@
+//! Combining the trees of several Nexus files, e.g. independent MCMC chains
+//! of BEAST or MrBayes, into one sample (like LogCombiner).
+//!
+//! This module provides [NexusCombiner] to configure burnin, skip-first, and
+//! thinning per file, and [CombinedTrees] to iterate over all trees, each
+//! tagged with the index of its chain as [ChainTree]. The files must have
+//! the same taxa; trees of all files refer to one shared [LeafLabelMap],
+//! namely the one of the first file.
+
+use crate::model::{CompactTree, CompactTreeBuilder, LabelIndex, LeafLabelMap};
+use crate::nexus::parser::{Burnin, NexusParser, NexusParserBuilder, Thinning};
+use crate::parser::parsing_error::{ParsingError, ParsingErrorType};
+use std::path::{Path, PathBuf};
+
+// =#========================================================================#=
+// NEXUS COMBINER
+// =#========================================================================$=
+/// Builder for combining the trees of several Nexus files (chains) into one
+/// stream of trees.
+///
+/// Burnin, skip-first, and thinning apply to each file separately, as
+/// configured for a [NexusParserBuilder]. Trees that are discarded are only
+/// skipped, never fully parsed.
+///
+/// # Example
+/// ```no_run
+/// use nexwick::nexus::{Burnin, NexusCombiner, Thinning};
+///
+/// let (trees, label_map) = NexusCombiner::for_files(["run1.trees", "run2.trees"])
+///     .with_burnin(Burnin::Percentage(0.1))
+///     .with_thinning(Thinning::Every(10))
+///     .combine()?;
+/// for chain_tree in &trees {
+///     println!("Chain {}: {:?}", chain_tree.chain(), chain_tree.tree().name());
+/// }
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct NexusCombiner {
+    paths: Vec<PathBuf>,
+    burnin: Burnin,
+    skip_first: bool,
+    thinning: Option<Thinning>,
+    parse_annotations: bool,
+}
+
+impl NexusCombiner {
+    /// Creates a new combiner of the given files (in order of their chain
+    /// index), without burnin and thinning.
+    ///
+    /// # Arguments
+    /// * `paths` - Paths to the files (accepting `&str`, `String`, `Path`, or `PathBuf`)
+    pub fn for_files<I, P>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        Self {
+            paths: paths
+                .into_iter()
+                .map(|path| path.as_ref().to_path_buf())
+                .collect(),
+            burnin: Burnin::Count(0),
+            skip_first: false,
+            thinning: None,
+            parse_annotations: false,
+        }
+    }
+
+    /// Configure burnin of each file,
+    /// see [NexusParserBuilder::with_burnin()].
+    pub fn with_burnin(mut self, burnin: Burnin) -> Self {
+        self.burnin = burnin;
+        self
+    }
+
+    /// Configure to skip the first tree of each file,
+    /// see [NexusParserBuilder::with_skip_first()].
+    pub fn with_skip_first(mut self) -> Self {
+        self.skip_first = true;
+        self
+    }
+
+    /// Configure thinning of each file (after burnin),
+    /// see [NexusParserBuilder::with_thinning()].
+    pub fn with_thinning(mut self, thinning: Thinning) -> Self {
+        self.thinning = Some(thinning);
+        self
+    }
+
+    /// Configure to parse vertex annotations,
+    /// see [NexusParserBuilder::with_annotations()].
+    pub fn with_annotations(mut self) -> Self {
+        self.parse_annotations = true;
+        self
+    }
+
+    /// Opens all files and checks that their taxa are identical, returning
+    /// [CombinedTrees] to lazily iterate over the trees of all files.
+    ///
+    /// # Errors
+    /// Returns a [ParsingError] if a file cannot be read or parsed, or
+    /// [ParsingErrorType::InvalidTaxaBlock] if its taxa differ from those
+    /// of the first file. Taxa of files without TAXA block are derived from
+    /// their first tree; leaves of later trees that are not among these give
+    /// a [ParsingError] when iterating.
+    pub fn build(self) -> Result<CombinedTrees, ParsingError> {
+        let mut parsers = Vec::with_capacity(self.paths.len());
+        for path in &self.paths {
+            let mut builder = NexusParserBuilder::for_file(path)?
+                .lazy()
+                .with_burnin(self.burnin);
+            if self.skip_first {
+                builder = builder.with_skip_first();
+            }
+            if let Some(thinning) = self.thinning {
+                builder = builder.with_thinning(thinning);
+            }
+            if self.parse_annotations {
+                builder = builder.with_annotations();
+            }
+            parsers.push(builder.build()?);
+        }
+
+        // Map label indices of each file to those of the first file
+        let label_map = match parsers.first() {
+            Some(parser) => parser.label_storage().clone(),
+            None => LeafLabelMap::new(0),
+        };
+        let mut mappings = Vec::with_capacity(parsers.len());
+        for (parser, path) in parsers.iter().zip(&self.paths) {
+            let Some(mapping) = label_mapping(parser.label_storage(), &label_map) else {
+                return Err(ParsingError::without_context(
+                    ParsingErrorType::InvalidTaxaBlock(format!(
+                        "Taxa of '{}' differ from those of '{}'.",
+                        path.display(),
+                        self.paths[0].display()
+                    )),
+                ));
+            };
+            // (No remapping needed if taxa are in the same order)
+            let is_identity = mapping.iter().enumerate().all(|(i, &j)| i == j);
+            mappings.push((!is_identity).then_some(mapping));
+        }
+
+        Ok(CombinedTrees {
+            parsers,
+            mappings,
+            label_map,
+            chain: 0,
+        })
+    }
+
+    /// Parses the trees of all files into a single vector, in order of
+    /// the files, each tagged with its chain.
+    ///
+    /// # Returns
+    /// The trees of all files and their shared [LeafLabelMap]
+    ///
+    /// # Errors
+    /// Returns a [ParsingError] as [build()](Self::build) does, or if a tree
+    /// cannot be parsed.
+    pub fn combine(self) -> Result<(Vec<ChainTree>, LeafLabelMap), ParsingError> {
+        let mut combined = self.build()?;
+        let mut trees = Vec::with_capacity(combined.num_trees());
+        for chain_tree in &mut combined {
+            trees.push(chain_tree?);
+        }
+        Ok((trees, combined.label_map))
+    }
+}
+
+/// Returns the mapping of the label indices of `labels` to those of
+/// `shared`, or `None` if their labels are not the same.
+fn label_mapping(labels: &LeafLabelMap, shared: &LeafLabelMap) -> Option<Vec<LabelIndex>> {
+    if labels.num_labels() != shared.num_labels() {
+        return None;
+    }
+    labels
+        .labels()
+        .iter()
+        .map(|label| shared.get_index(label))
+        .collect()
+}
+
+// =#========================================================================#=
+// COMBINED TREES
+// =#========================================================================$=
+/// Iterator over the trees of several Nexus files combined by
+/// [NexusCombiner], in order of the files.
+///
+/// Trees are parsed lazily and refer to the shared
+/// [label_map()](Self::label_map), i.e. the [LeafLabelMap] of the first file.
+pub struct CombinedTrees {
+    /// Lazy parsers of the files
+    parsers: Vec<NexusParser<CompactTreeBuilder>>,
+    /// Mapping of label indices of each file to the shared ones, if not identical
+    mappings: Vec<Option<Vec<LabelIndex>>>,
+    /// Shared label map of all trees
+    label_map: LeafLabelMap,
+    /// Index of the file currently parsed
+    chain: usize,
+}
+
+impl CombinedTrees {
+    /// Returns the [LeafLabelMap] shared by the trees of all files.
+    pub fn label_map(&self) -> &LeafLabelMap {
+        &self.label_map
+    }
+
+    /// Returns the number of files/chains.
+    pub fn num_chains(&self) -> usize {
+        self.parsers.len()
+    }
+
+    /// Returns the total number of trees of all files (without skipped,
+    /// burnin, and thinned out trees).
+    pub fn num_trees(&self) -> usize {
+        self.parsers.iter().map(|parser| parser.num_trees()).sum()
+    }
+}
+
+impl Iterator for CombinedTrees {
+    type Item = Result<ChainTree, ParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(parser) = self.parsers.get_mut(self.chain) {
+            match parser.next_tree() {
+                Ok(Some(mut tree)) => {
+                    // (Leaves are taxa of the file, so this only guards remapping)
+                    let num_labels = self.label_map.num_labels();
+                    if let Some(label) = tree
+                        .pre_order_iter()
+                        .filter_map(|vertex| vertex.label())
+                        .find(|&&label| label >= num_labels)
+                    {
+                        self.chain = self.parsers.len();
+                        return Some(Err(ParsingError::without_context(
+                            ParsingErrorType::UnresolvedLabel(format!(
+                                "Leaf label index {label} of chain {} is not a taxon of the first file.",
+                                self.chain
+                            )),
+                        )));
+                    }
+                    if let Some(mapping) = &self.mappings[self.chain] {
+                        tree.remap_labels(mapping);
+                    }
+                    let chain = self.chain;
+                    return Some(Ok(ChainTree { chain, tree }));
+                }
+                Ok(None) => self.chain += 1,
+                Err(err) => {
+                    // (Stop after an error)
+                    self.chain = self.parsers.len();
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
+// =#========================================================================#=
+// CHAIN TREE
+// =#========================================================================$=
+/// Tree of combined files, tagged with the (0-based) index of its file/chain.
+#[derive(Debug, Clone)]
+pub struct ChainTree {
+    chain: usize,
+    tree: CompactTree,
+}
+
+impl ChainTree {
+    /// Returns the index of the file/chain of the tree, in order given to
+    /// [NexusCombiner::for_files()].
+    pub fn chain(&self) -> usize {
+        self.chain
+    }
+
+    /// Returns the tree.
+    pub fn tree(&self) -> &CompactTree {
+        &self.tree
+    }
+
+    /// Consumes this [ChainTree] and returns the tree.
+    pub fn into_tree(self) -> CompactTree {
+        self.tree
+    }
+}
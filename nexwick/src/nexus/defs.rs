@@ -15,6 +15,9 @@ pub(crate) const BLOCK_BEGIN: &[u8] = b"Begin";
 /// NEXUS block end keyword "End;" (with semicolon)
 pub(crate) const BLOCK_END: &[u8] = b"End;";
 
+/// NEXUS block end keywords "End" and "EndBlock" (without semicolon)
+pub(crate) const BLOCK_END_KEYWORDS: [&[u8]; 2] = [b"End", b"EndBlock"];
+
 // Taxa block keywords
 /// TAXA block identifier "taxa;" (with semicolon)
 pub(crate) const TAXA: &[u8] = b"taxa;";
@@ -38,6 +41,16 @@ pub(crate) const TRANSLATE: &[u8] = b"Translate";
 /// Individual tree declaration keyword "tree"
 pub(crate) const TREE: &[u8] = b"tree";
 
+// Distances block keywords
+/// DISTANCES block identifier "distances;" (with semicolon)
+pub(crate) const DISTANCES: &[u8] = b"distances;";
+
+/// Format command keyword "Format"
+pub(crate) const FORMAT: &[u8] = b"Format";
+
+/// Matrix command keyword "Matrix"
+pub(crate) const MATRIX: &[u8] = b"Matrix";
+
 /// NEXUS block types
 #[derive(Debug, PartialEq, Clone)]
 pub enum NexusBlock {
@@ -48,6 +61,8 @@ pub enum NexusBlock {
     Distances,
     Sets,
     Assumptions,
+    /// Any other block with its name, e.g. `mrbayes`; its content can be read
+    /// with [NexusBlockReader](crate::nexus::NexusBlockReader)
     UnknownBlock(String),
 }
 
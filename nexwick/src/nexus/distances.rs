@@ -0,0 +1,316 @@
+//! Parsing of Nexus `DISTANCES` blocks into a [DistanceMatrix].
+//!
+//! Supported commands (case-insensitive), any others are skipped:
+//! * `DIMENSIONS [NEWTAXA] NTAX=<n>;`
+//! * `FORMAT [TRIANGLE=LOWER|UPPER|BOTH] [DIAGONAL|NODIAGONAL]
+//!   [LABELS|NOLABELS] [MISSING=<symbol>];`
+//! * `TAXLABELS <label> ...;`, defining the taxa (and their order) of
+//!   the matrix, e.g. for unlabelled matrices
+//! * `MATRIX [<label>] <distance> ...;`, one row per taxon
+//!
+//! Defaults are `TRIANGLE=LOWER`, `DIAGONAL`, `LABELS`, and `MISSING=?`.
+//! Interleaved matrices are not supported.
+
+use crate::model::DistanceMatrix;
+use crate::nexus::block_reader::{consume_block_end, skip_command};
+use crate::nexus::characters::{OPTION_DELIMITERS, parse_options};
+use crate::nexus::defs::NEXUS_LABEL_DELIMITERS;
+use crate::parser::byte_parser::ByteParser;
+use crate::parser::byte_source::ByteSource;
+use crate::parser::parsing_error::ParsingError;
+use std::collections::HashMap;
+
+/// Delimiters of distance values in the `MATRIX` command
+const VALUE_DELIMITERS: &[u8] = b" \t\n\r;";
+
+/// Part of the matrix given in the `MATRIX` command
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Triangle {
+    Lower,
+    Upper,
+    Both,
+}
+
+/// Format of a distance matrix, given by `DIMENSIONS` and `FORMAT` commands
+struct DistancesFormat {
+    num_taxa: Option<usize>,
+    triangle: Triangle,
+    diagonal: bool,
+    labels: bool,
+    missing: String,
+}
+
+impl Default for DistancesFormat {
+    fn default() -> Self {
+        Self {
+            num_taxa: None,
+            triangle: Triangle::Lower,
+            diagonal: true,
+            labels: true,
+            missing: String::from("?"),
+        }
+    }
+}
+
+impl DistancesFormat {
+    /// Returns the (0-based) columns given in the `row`-th row of the matrix
+    fn columns(&self, row: usize, num_taxa: usize) -> impl Iterator<Item = usize> + use<> {
+        let diagonal = self.diagonal;
+        let range = match self.triangle {
+            Triangle::Lower => 0..row + 1,
+            Triangle::Upper => row..num_taxa,
+            Triangle::Both => 0..num_taxa,
+        };
+        range.filter(move |&column| diagonal || column != row)
+    }
+}
+
+/// Parses a `DISTANCES` block into a [DistanceMatrix], including
+/// its closing `END;`.
+///
+/// Assumes the parser is positioned right after the block header.
+///
+/// # Arguments
+/// * `parser` - The byte parser positioned after `BEGIN DISTANCES;`
+/// * `taxa` - Taxon labels in order of the file (e.g. of the TAXA block) to
+///   index the matrix by; distances of taxa without row are missing.
+///   If empty, taxa are kept in order of the `MATRIX` command.
+///
+/// # Errors
+/// Returns a [ParsingError] if the block is not properly closed, a distance
+/// cannot be parsed, or the matrix is inconsistent with the given dimensions
+/// or taxa.
+pub(crate) fn parse_distances_block<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    taxa: &[String],
+) -> Result<DistanceMatrix, ParsingError> {
+    let mut format = DistancesFormat::default();
+    let mut block_taxa = None;
+    let mut matrix = None;
+
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if consume_block_end(parser)? {
+            break;
+        }
+        if parser.is_eof() {
+            return Err(ParsingError::unexpected_eof(parser));
+        }
+
+        let command = parser.parse_unquoted_label(OPTION_DELIMITERS)?;
+        match command.to_ascii_lowercase().as_str() {
+            "dimensions" => parse_dimensions(parser, &mut format)?,
+            "format" => parse_format(parser, &mut format)?,
+            "taxlabels" => block_taxa = Some(parse_taxlabels(parser)?),
+            "matrix" => {
+                let row_taxa = block_taxa.as_deref().unwrap_or(taxa);
+                matrix = Some(parse_matrix(parser, &format, row_taxa, taxa)?);
+            }
+            // Skip any other command
+            _ => skip_command(parser)?,
+        }
+    }
+
+    matrix.ok_or_else(|| {
+        ParsingError::invalid_distances_block(parser, String::from("Missing MATRIX command."))
+    })
+}
+
+/// Parses the options of `DIMENSIONS` command, i.e. `NTAX`.
+fn parse_dimensions<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &mut DistancesFormat,
+) -> Result<(), ParsingError> {
+    for (key, value) in parse_options(parser)? {
+        if let ("ntax", Some(value)) = (key.as_str(), &value) {
+            let num_taxa = value.parse::<usize>().map_err(|_| {
+                ParsingError::invalid_distances_block(
+                    parser,
+                    format!("Cannot parse `ntax` value: {value}"),
+                )
+            })?;
+            format.num_taxa = Some(num_taxa);
+        }
+    }
+    Ok(())
+}
+
+/// Parses the options of `FORMAT` command.
+fn parse_format<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &mut DistancesFormat,
+) -> Result<(), ParsingError> {
+    for (key, value) in parse_options(parser)? {
+        match key.as_str() {
+            "triangle" => {
+                let value = value.unwrap_or_default();
+                format.triangle = match value.to_ascii_lowercase().as_str() {
+                    "lower" => Triangle::Lower,
+                    "upper" => Triangle::Upper,
+                    "both" => Triangle::Both,
+                    _ => {
+                        return Err(ParsingError::invalid_distances_block(
+                            parser,
+                            format!("Unknown triangle: {value}"),
+                        ));
+                    }
+                };
+            }
+            "diagonal" => format.diagonal = true,
+            "nodiagonal" => format.diagonal = false,
+            "labels" => format.labels = true,
+            "nolabels" => format.labels = false,
+            "missing" => format.missing = value.unwrap_or(format.missing.clone()),
+            "interleave" if value.is_none_or(|value| value.eq_ignore_ascii_case("yes")) => {
+                return Err(ParsingError::invalid_distances_block(
+                    parser,
+                    String::from("Interleaved matrices are not supported."),
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses the labels of `TAXLABELS` command until and including its semicolon.
+fn parse_taxlabels<B: ByteSource>(parser: &mut ByteParser<B>) -> Result<Vec<String>, ParsingError> {
+    let mut labels = Vec::new();
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if parser.consume_if(b';') {
+            return Ok(labels);
+        }
+        if parser.is_eof() {
+            return Err(ParsingError::unexpected_eof(parser));
+        }
+        labels.push(parser.parse_label(NEXUS_LABEL_DELIMITERS)?);
+    }
+}
+
+/// Parses the rows of the `MATRIX` command until and including its semicolon
+/// into a [DistanceMatrix].
+///
+/// # Arguments
+/// * `format` - Format of the matrix
+/// * `row_taxa` - Taxa in order of the rows, required for unlabelled matrices
+/// * `taxa` - Taxa to index the matrix by; if empty, the taxa of the rows
+fn parse_matrix<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &DistancesFormat,
+    row_taxa: &[String],
+    taxa: &[String],
+) -> Result<DistanceMatrix, ParsingError> {
+    let num_rows = match (format.num_taxa, row_taxa.len()) {
+        (Some(num_taxa), _) => num_taxa,
+        (None, n) if n > 0 => n,
+        _ => {
+            return Err(ParsingError::invalid_distances_block(
+                parser,
+                String::from("Expected 'DIMENSIONS NTAX=<n>' or taxa before MATRIX."),
+            ));
+        }
+    };
+    if !format.labels && row_taxa.len() < num_rows {
+        return Err(ParsingError::invalid_distances_block(
+            parser,
+            String::from("Unlabelled MATRIX requires taxa, e.g. of a TAXA block."),
+        ));
+    }
+
+    // Parse rows as labels and their distances
+    let mut labels = Vec::with_capacity(num_rows);
+    let mut rows = Vec::with_capacity(num_rows);
+    for row in 0..num_rows {
+        parser.skip_comment_and_whitespace()?;
+        let label = match (format.labels, row_taxa.get(row)) {
+            (false, Some(label)) => label.clone(),
+            _ => parser.parse_label(NEXUS_LABEL_DELIMITERS)?,
+        };
+
+        let mut distances = Vec::new();
+        for _ in format.columns(row, num_rows) {
+            distances.push(parse_distance(parser, format, &label)?);
+        }
+        labels.push(label);
+        rows.push(distances);
+    }
+
+    parser.skip_comment_and_whitespace()?;
+    if !parser.consume_if(b';') {
+        return Err(ParsingError::invalid_distances_block(
+            parser,
+            format!("Expected ';' after {num_rows} rows of MATRIX."),
+        ));
+    }
+
+    // Index rows by taxa, where taxa without row only have missing distances
+    let taxa = if taxa.is_empty() {
+        labels.clone()
+    } else {
+        taxa.to_vec()
+    };
+    let index_of_taxon: HashMap<&str, usize> = taxa
+        .iter()
+        .enumerate()
+        .map(|(index, taxon)| (taxon.as_str(), index))
+        .collect();
+    let mut indices = Vec::with_capacity(num_rows);
+    for label in &labels {
+        match index_of_taxon.get(label.as_str()) {
+            Some(&index) => indices.push(index),
+            None => {
+                return Err(ParsingError::invalid_distances_block(
+                    parser,
+                    format!("Unknown taxon '{label}' in MATRIX."),
+                ));
+            }
+        }
+    }
+
+    let mut matrix = DistanceMatrix::new(taxa);
+    for i in 0..matrix.num_taxa() {
+        for j in 0..matrix.num_taxa() {
+            matrix.set_directed(i, j, if i == j { 0.0 } else { f64::NAN });
+        }
+    }
+    for (row, distances) in rows.into_iter().enumerate() {
+        for (column, distance) in format.columns(row, num_rows).zip(distances) {
+            let (i, j) = (indices[row], indices[column]);
+            if format.triangle == Triangle::Both {
+                matrix.set_directed(i, j, distance);
+            } else {
+                matrix.set(i, j, distance);
+            }
+        }
+    }
+
+    Ok(matrix)
+}
+
+/// Parses a single distance of the row of taxon `label`,
+/// with the missing symbol giving `NaN`.
+fn parse_distance<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    format: &DistancesFormat,
+    label: &str,
+) -> Result<f64, ParsingError> {
+    parser.skip_comment_and_whitespace()?;
+    if parser.peek() == Some(b';') {
+        return Err(ParsingError::invalid_distances_block(
+            parser,
+            format!("Row of taxon '{label}' has too few distances."),
+        ));
+    }
+
+    let value = parser.parse_unquoted_label(VALUE_DELIMITERS)?;
+    if value == format.missing {
+        return Ok(f64::NAN);
+    }
+    value.parse::<f64>().map_err(|_| {
+        ParsingError::invalid_distances_block(
+            parser,
+            format!("Cannot parse distance '{value}' of taxon '{label}'."),
+        )
+    })
+}
@@ -9,7 +9,9 @@
 //! - [NexusWriter] — for writing NEXUS files
 //!
 //! Supports eager (load all trees) and lazy (parse on-demand) modes,
-//! with options for burnin and skipping initial trees.
+//! with options for burnin and skipping initial trees, as well as a lenient
+//! mode skipping malformed trees
+//! (see [with_error_recovery()](NexusParserBuilder::with_error_recovery)).
 //!
 //! # Quick API
 //! For simple use cases with default settings:
@@ -22,7 +24,13 @@
 //! - Optional TRANSLATE commands mapping short keys to full taxon labels
 //!
 //! ## Assumptions
-//! * A `TAXA` and a `TREES` block are present, in this order
+//! * A `TREES` block is present, preceded by a `TAXA` block if there is one
+//!   - Further `TAXA` and `TREES` blocks are only considered by
+//!     [NexusParser::into_trees_blocks] (see below)
+//!   - Blocks may start with `TITLE <title>;` and TREES blocks may refer to
+//!     a TAXA block by `LINK TAXA = <title>;`, otherwise to the last one
+//!   - Without `TAXA` block, the taxa are derived from the `TRANSLATE` command
+//!     (in its order) or, if there is none either, from the first tree
 //! * A `TRANSLATE` command, if present, precedes any `TREE` command, with following details:
 //!   - Command is a comma seperated list of pairs of "id/short label":
 //!     `TRANSLATE [<key1=short1/id1> <label1>, ...];`
@@ -38,16 +46,69 @@
 //!     e.g. `[cool seabird] stormy 'Wilson''s_storm-petrel',`
 //! * Trees come in semicolon separated list of tree commands
 //! * One tree command has format `tree <name> = <Newick string>;`
+//!   - Comments `[&R]`/`[&U]` (rooted/unrooted) and `[&W <weight>]` before the
+//!     Newick string are kept as [TreeMetadata](crate::model::TreeMetadata)
 //!   - Each pair is separated by a comma, optional whitespace and comments
 //!   - Only one mapping per taxon allowed
 //!   - Same label rules apply
 //! * Each tree has at least two leaves.
+//!
+//! # Networks
+//! TREES blocks containing phylogenetic networks in extended Newick format
+//! can be parsed by configuring the builder with a
+//! [NetworkBuilder](crate::model::NetworkBuilder) via
+//! [with_tree_builder()](NexusParserBuilder::with_tree_builder), and written
+//! with [NexusWriter::write_nexus_networks].
+//!
+//! # Multiple TREES blocks
+//! Files written by Mesquite (and some pipelines) contain several TAXA and
+//! TREES blocks, linked by `TITLE` and `LINK` commands. By default, only the
+//! first TREES block is parsed; [NexusParser::into_trees_blocks] returns all
+//! of them as [NexusTreesBlock]s, each with its own label storage and title.
+//!
+//! # Other blocks
+//! Blocks not modelled by the library, e.g. `MRBAYES` or `PAUP` blocks, can
+//! be read with [NexusBlockReader], which yields each block with its
+//! commands split into [NexusToken]s.
+//!
+//! # Alignments
+//! `DATA` and `CHARACTERS` blocks (DNA, RNA, protein, or standard data,
+//! sequential or interleaved) can be parsed into an
+//! [Alignment](crate::model::Alignment) alongside the trees, see
+//! [with_alignment()](NexusParserBuilder::with_alignment).
+//!
+//! # Distances
+//! `DISTANCES` blocks (lower, upper, or both triangles, with or without
+//! diagonal and labels) can be parsed into a
+//! [DistanceMatrix](crate::model::DistanceMatrix), indexed like the taxa of
+//! the trees, see [with_distances()](NexusParserBuilder::with_distances),
+//! and written with [NexusWriter::write_nexus_distances].
+//!
+//! # Combining chains
+//! The trees of several files with the same taxa, e.g. independent MCMC
+//! chains, can be combined into one sample with [NexusCombiner], applying
+//! burnin and thinning to each file (like LogCombiner).
+//!
+//! # Sets
+//! `TAXSET` and `TREESET` definitions of `SETS` blocks, e.g. outgroups or
+//! clade constraints, can be resolved against the taxa and trees into
+//! [NexusSets], see [with_sets()](NexusParserBuilder::with_sets).
 
+pub mod block_reader;
+mod characters;
+pub mod combiner;
 mod defs;
+mod distances;
 pub mod parser;
+mod sets;
+mod tree_index;
 pub mod writer;
 
-pub use parser::{Burnin, NexusParser, NexusParserBuilder};
+pub use block_reader::{NexusBlockReader, NexusToken, RawBlock, RawCommand};
+pub use combiner::{ChainTree, CombinedTrees, NexusCombiner};
+pub use defs::NexusBlock;
+pub use parser::{Burnin, NexusParser, NexusParserBuilder, NexusTreesBlock, Thinning};
+pub use sets::NexusSets;
 pub use writer::NexusWriter;
 
 use crate::CompactTree;
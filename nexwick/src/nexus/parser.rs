@@ -5,17 +5,31 @@
 
 use crate::model::label_storage::LabelStorage;
 use crate::model::tree_builder::TreeBuilder;
-use crate::model::{CompactTreeBuilder, LabelResolver};
-use crate::newick::NewickParser;
+use crate::model::{Alignment, DistanceMatrix};
+use crate::model::{CompactTreeBuilder, LabelResolver, TreeMetadata};
+use crate::newick::{BranchAnnotationPolicy, BranchLengthPolicy, NewickParser, UnrootedTreePolicy};
+use crate::nexus::block_reader::{
+    NexusToken, RawCommand, WORD_DELIMITERS, consume_block_end, next_command, peek_is_block_end,
+    skip_block,
+};
+use crate::nexus::characters::parse_characters_block;
 use crate::nexus::defs::*;
+use crate::nexus::distances::parse_distances_block;
 use crate::nexus::parser::ReadStrategy::Automatic;
+use crate::nexus::sets::{NexusSets, SetDefinition, parse_sets_block, resolve_sets};
+use crate::nexus::tree_index::TreeIndexFile;
 use crate::parser::buffered_byte_source::BufferedByteSource;
-use crate::parser::byte_parser::{ByteParser, ConsumeMode::*};
+use crate::parser::byte_parser::ByteParser;
 use crate::parser::byte_source::ByteSource;
+use crate::parser::decompressing_byte_source::Compression;
 use crate::parser::in_memory_byte_source::InMemoryByteSource;
 use crate::parser::parsing_error::ParsingError;
+use crate::parser::reader_byte_source::ReaderByteSource;
 use std::collections::HashMap;
+use std::io::Read;
+use std::ops::{Bound, RangeBounds};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 // =#========================================================================#=
 // PARSING MODE
@@ -31,6 +45,32 @@ enum TreeParsingMode<T: TreeBuilder> {
     },
 }
 
+/// State of [NexusParser] in follow mode, see [NexusParserBuilder::follow()],
+/// or streaming from a reader, see [NexusParserBuilder::for_reader()].
+struct FollowState {
+    /// Time to wait before checking again for new trees, or `None` for
+    /// streams, whose reads block until data is available
+    poll_interval: Option<Duration>,
+    /// Whether the end of the TREES block has been read
+    finished: bool,
+    /// Number of trees read after burnin and in state range, for thinning
+    num_candidates: usize,
+    /// Whether the taxa are still to be derived from the first tree, once
+    /// it has been written completely
+    taxa_pending: bool,
+}
+
+/// Commands of a TREES block before its first tree.
+#[derive(Default)]
+struct TreesBlockHeader {
+    /// Title of the block (`TITLE <title>;`)
+    title: Option<String>,
+    /// Title of the linked TAXA block (`LINK TAXA = <title>;`)
+    link: Option<String>,
+    /// Pairs of key and label of the `TRANSLATE` command (in order)
+    translation: Option<Vec<(String, String)>>,
+}
+
 // =#========================================================================#=
 // BURNIN
 // =#========================================================================€=
@@ -89,6 +129,58 @@ impl Burnin {
     }
 }
 
+// =#========================================================================#=
+// THINNING
+// =#========================================================================€=
+/// Specifies how to thin out the trees remaining after burnin.
+///
+/// Thinning is commonly used to reduce large MCMC samples (e.g. 100k trees)
+/// to a manageable number of (less autocorrelated) trees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Thinning {
+    /// Keep every k-th tree, starting with the first one; `0` is treated as `1`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::Thinning;
+    /// let thinning = Thinning::Every(10); // Keep trees 0, 10, 20, ...
+    /// ```
+    Every(usize),
+
+    /// Keep (at most) a fixed number of evenly spaced trees,
+    /// starting with the first one.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::Thinning;
+    /// let thinning = Thinning::Target(1000); // Keep 1000 evenly spaced trees
+    /// ```
+    Target(usize),
+}
+
+impl Thinning {
+    /// Selects the trees to keep among the given (ordered) tree indices.
+    ///
+    /// # Arguments
+    /// * `indices` - Indices of the trees remaining after burnin (and state range)
+    ///
+    /// # Returns
+    /// The indices of the trees to keep, in order
+    pub(crate) fn select(&self, indices: Vec<usize>) -> Vec<usize> {
+        match *self {
+            Thinning::Every(k) => indices.into_iter().step_by(k.max(1)).collect(),
+            Thinning::Target(n) if n >= indices.len() => indices,
+            Thinning::Target(n) => {
+                let m = indices.len();
+                (0..n).map(|i| indices[i * m / n]).collect()
+            }
+        }
+    }
+}
+
+/// Range of MCMC states, as given to [NexusParserBuilder::with_state_range()]
+type StateRange = (Bound<u64>, Bound<u64>);
+
 // =#========================================================================#=
 // BYTE SOURCE SETTING
 // =#========================================================================€=
@@ -99,6 +191,10 @@ impl Burnin {
 /// [with_buffered_source()](NexusParserBuilder::with_buffered_source) or
 /// [with_in_memory_source()](NexusParserBuilder::with_in_memory_source)
 /// to override this.
+///
+/// Compressed files (gzip, zstd, or bzip2, with the respective cargo feature
+/// enabled) are detected by their magic bytes and either decompressed into
+/// memory or, if read buffered, streamed through a decompressor.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ReadStrategy {
     /// Read the file in chunks through a buffered I/O reader.
@@ -108,7 +204,8 @@ pub enum ReadStrategy {
     InMemory,
 
     /// Automatically choose between [ReadStrategy::Buffered] and
-    /// [ReadStrategy::InMemory] based on file size.
+    /// [ReadStrategy::InMemory] based on file size (of compressed files
+    /// estimated as ten times their size).
     /// This is the default.
     Automatic,
 }
@@ -145,9 +242,49 @@ pub enum ReadStrategy {
 ///   - [`with_burnin()`](NexusParserBuilder::with_burnin)
 ///     — Skip a fixed count or percentage
 ///
+/// * **Thinning and state range**: Select a subset of the remaining trees
+///   - [`with_thinning()`](NexusParserBuilder::with_thinning)
+///     — Keep every k-th tree or a target number of evenly spaced trees
+///   - [`with_state_range()`](NexusParserBuilder::with_state_range)
+///     — Keep trees whose MCMC state (e.g. `STATE_1000`) is in a range
+///
+/// * **Tree index**: Store the byte offsets of all trees in a sidecar file
+///   - [`with_tree_index()`](NexusParserBuilder::with_tree_index)
+///     — Skip counting trees when reopening a file
+///
+/// * **Follow mode**: Read trees of a file that is still being written
+///   - [`follow()`](NexusParserBuilder::follow)
+///     — Wait for new trees instead of failing on incomplete data
+///
+/// * **Readers**: Read from stdin, pipes, sockets, etc. instead of a file
+///   - [`for_reader()`](NexusParserBuilder::for_reader)
+///     — Read into memory or, in lazy mode, stream trees as they come
+///
 /// * **Annotations**: Parse vertex annotations instead of treating them as comments
 ///   - [`with_annotations()`](Self::with_annotations)
 ///     — Enable parsing of `[&key=value,...]` blocks
+///   - [`with_unrooted_tree_policy()`](Self::with_unrooted_tree_policy)
+///     — Keep basal trifurcation of unrooted trees or root them
+///   - [`with_branch_length_policy()`](Self::with_branch_length_policy)
+///     — Reject, clamp, or keep negative/NaN/infinite branch lengths
+///   - [`with_branch_annotation_policy()`](Self::with_branch_annotation_policy)
+///     — Keep branch annotations (`A:[&rate=0.9]0.12`) separate or merge them
+///
+/// * **Alignment**: Parse `DATA`/`CHARACTERS` blocks
+///   - [`with_alignment()`](Self::with_alignment)
+///     — Parse character matrices into [Alignment]s
+///
+/// * **Distances**: Parse `DISTANCES` blocks
+///   - [`with_distances()`](Self::with_distances)
+///     — Parse distance matrices into [DistanceMatrix]s
+///
+/// * **Sets**: Parse `SETS` blocks
+///   - [`with_sets()`](Self::with_sets)
+///     — Resolve `TAXSET`s and `TREESET`s into [NexusSets]
+///
+/// * **Error recovery**: Skip malformed trees instead of failing
+///   - [`with_error_recovery()`](Self::with_error_recovery)
+///     — Lenient mode, reporting errors of skipped trees
 ///
 /// # Example
 /// ```no_run
@@ -169,10 +306,22 @@ pub enum ReadStrategy {
 pub struct NexusParserBuilder<T: TreeBuilder> {
     mode: TreeParsingMode<T>,
     path: PathBuf,
+    reader: Option<Box<dyn Read + Send>>,
     read_strategy: ReadStrategy,
     burnin: Burnin,
     skip_first: bool,
+    thinning: Option<Thinning>,
+    state_range: Option<StateRange>,
+    tree_index_path: Option<PathBuf>,
+    follow: Option<Duration>,
     parse_annotations: bool,
+    branch_annotation_policy: BranchAnnotationPolicy,
+    branch_length_policy: BranchLengthPolicy,
+    unrooted_tree_policy: UnrootedTreePolicy,
+    error_recovery: bool,
+    parse_alignment: bool,
+    parse_distances: bool,
+    parse_sets: bool,
     tree_builder: T,
 }
 
@@ -186,7 +335,7 @@ impl NexusParserBuilder<CompactTreeBuilder> {
     /// The builder is initialized with default settings:
     /// - Eager parser mode
     /// - First tree not skipped
-    /// - No burnin (all trees included)
+    /// - No burnin and no thinning (all trees included)
     ///
     /// # Arguments
     /// * `path` - Path to the file (accepting `&str`, `String`, `Path`, or `PathBuf`)
@@ -210,15 +359,80 @@ impl NexusParserBuilder<CompactTreeBuilder> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn for_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
-        Ok(NexusParserBuilder {
+        Ok(Self::new(path.as_ref().to_path_buf(), None))
+    }
+
+    /// Creates a new builder reading from a reader, e.g. stdin, a pipe, a
+    /// socket, or an archive entry, with the same defaults as
+    /// [for_file()](Self::for_file).
+    ///
+    /// As a reader can neither be rewound nor re-opened:
+    /// * In eager mode (or with [in-memory source](Self::with_in_memory_source)),
+    ///   the entire input is read into memory first, so that all settings
+    ///   are supported.
+    /// * In lazy mode, the input is streamed and trees are read as they come,
+    ///   without counting them first, like in [follow mode](Self::follow)
+    ///   (but blocking on reads instead of polling): [Burnin::Percentage]
+    ///   and [Thinning::Target] are rejected by [build()](Self::build),
+    ///   [num_trees()](NexusParser::num_trees) gives the number of trees
+    ///   read so far, [reset()](NexusParser::reset) has no effect, and
+    ///   [tree_at()](NexusParser::tree_at) returns `Ok(None)`. DATA,
+    ///   DISTANCES, and SETS blocks are only parsed (see e.g.
+    ///   [with_alignment()](Self::with_alignment)) if they precede the TREES
+    ///   block; those after it are never read.
+    ///
+    /// Compressed input is not detected (wrap the reader in a decompressor
+    /// instead) and a [tree index](Self::with_tree_index) is not used.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader to read the Nexus content from (unbuffered is fine)
+    ///
+    /// # Returns
+    /// A builder configured with defaults, ready for method chaining
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::{Burnin, NexusParserBuilder};
+    ///
+    /// // E.g. `cat run.trees | my_tool`
+    /// let mut parser = NexusParserBuilder::for_reader(std::io::stdin())
+    ///     .with_burnin(Burnin::Count(1000))
+    ///     .lazy()
+    ///     .build()?;
+    /// while let Some(tree) = parser.next_tree()? {
+    ///     println!("{:?}: {}", tree.name(), tree.height());
+    /// }
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn for_reader<R: Read + Send + 'static>(reader: R) -> Self {
+        Self::new(PathBuf::new(), Some(Box::new(reader)))
+    }
+
+    /// Creates a new builder with default settings for a file at `path`,
+    /// or for `reader` if given.
+    fn new(path: PathBuf, reader: Option<Box<dyn Read + Send>>) -> Self {
+        NexusParserBuilder {
             mode: TreeParsingMode::Eager { trees: Vec::new() },
-            path: path.as_ref().to_path_buf(),
+            path,
+            reader,
             read_strategy: Automatic,
             burnin: Burnin::Count(0),
             skip_first: false,
+            thinning: None,
+            state_range: None,
+            tree_index_path: None,
+            follow: None,
             parse_annotations: false,
+            branch_annotation_policy: BranchAnnotationPolicy::default(),
+            branch_length_policy: BranchLengthPolicy::default(),
+            unrooted_tree_policy: UnrootedTreePolicy::default(),
+            error_recovery: false,
+            parse_alignment: false,
+            parse_distances: false,
+            parse_sets: false,
             tree_builder: CompactTreeBuilder::new(),
-        })
+        }
     }
 }
 
@@ -356,13 +570,308 @@ impl<T: TreeBuilder> NexusParserBuilder<T> {
         self
     }
 
+    /// Configure thinning, i.e. keep only a subset of the trees remaining
+    /// after skip-first, burnin, and state range.
+    ///
+    /// Trees that are thinned out are only skipped, never fully parsed,
+    /// in both eager and lazy mode.
+    ///
+    /// # Returns
+    /// The builder with thinning configured
+    ///
+    /// # Arguments
+    /// * `thinning` - The thinning specification ([Thinning::Every] or [Thinning::Target])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::{NexusParserBuilder, Burnin, Thinning};
+    ///
+    /// // Discard 10% burnin, then keep every 100th tree
+    /// let parser = NexusParserBuilder::for_file("phasianidae.trees")?
+    ///     .with_burnin(Burnin::Percentage(0.1))
+    ///     .with_thinning(Thinning::Every(100))
+    ///     .build()?;
+    ///
+    /// // Keep 1000 evenly spaced trees
+    /// let parser = NexusParserBuilder::for_file("phasianidae.trees")?
+    ///     .with_thinning(Thinning::Target(1000))
+    ///     .build()?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_thinning(mut self, thinning: Thinning) -> Self {
+        self.thinning = Some(thinning);
+        self
+    }
+
+    /// Configure the parser to keep only trees whose MCMC state, as given by
+    /// their name (e.g. `STATE_1000` or `gen.1000`, see
+    /// [TreeMetadata::state_from_name()]), lies in the given range.
+    ///
+    /// Trees without state in their name are discarded. The range is applied
+    /// after skip-first and burnin, but before thinning.
+    ///
+    /// # Returns
+    /// The builder with state range configured
+    ///
+    /// # Arguments
+    /// * `range` - Range of states to keep, e.g. `1_000_000..` or `0..=500_000`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// // Keep trees from state 1,000,000 on
+    /// let parser = NexusParserBuilder::for_file("cracidae.trees")?
+    ///     .with_state_range(1_000_000..)
+    ///     .build()?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_state_range<R: RangeBounds<u64>>(mut self, range: R) -> Self {
+        self.state_range = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+        self
+    }
+
+    /// Configure the parser to use a sidecar file storing the byte offsets
+    /// of all trees (**tree index**).
+    ///
+    /// If the index file exists and matches the Nexus file (same length,
+    /// modification time, and hash of its first and last kilobyte), the trees
+    /// are not counted again on [build()](Self::build); otherwise they are
+    /// counted and the index file is (re)written. Writing the index is best
+    /// effort: if it fails, e.g. in a read-only directory, parsing goes on
+    /// without it. With a [state range](Self::with_state_range), trees are
+    /// always counted to determine their states.
+    ///
+    /// The offsets give random access to trees via
+    /// [tree_at()](NexusParser::tree_at) in lazy mode, which is available
+    /// without tree index as well.
+    ///
+    /// # Arguments
+    /// * `index_path` - Path of the index file, e.g. `<file>.idx`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// // The first run counts the trees and writes the index,
+    /// // later runs read it instead
+    /// let mut parser = NexusParserBuilder::for_file("ie_languages.trees")?
+    ///     .lazy()
+    ///     .with_tree_index("ie_languages.trees.idx")
+    ///     .build()?;
+    /// let tree = parser.tree_at(4242)?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_tree_index<P: AsRef<Path>>(mut self, index_path: P) -> Self {
+        self.tree_index_path = Some(index_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Configure the parser to **follow** a file that is still being
+    /// written, e.g. by a running BEAST or MrBayes analysis (like `tail -f`).
+    ///
+    /// Trees are read lazily from a buffered source as they appear: instead
+    /// of failing on an incomplete trailing `TREE` command or a missing
+    /// `END;`, [next_tree()](NexusParser::next_tree) waits for the file to
+    /// grow, checking every `poll_interval`, and returns `Ok(None)` only once
+    /// the TREES block is closed. [next_available_tree()](NexusParser::next_available_tree)
+    /// returns `Ok(None)` instead of waiting.
+    ///
+    /// The file must contain everything up to the first tree (e.g. TAXA block
+    /// and TRANSLATE command) when building the parser; without both, the
+    /// taxa are derived from the first tree once it has been written. As the
+    /// number of trees is not known upfront, [Burnin::Percentage] and
+    /// [Thinning::Target] are rejected by [build()](Self::build), as are
+    /// compressed files (which cannot be decompressed while being written);
+    /// [num_trees()](NexusParser::num_trees) and
+    /// [num_total_trees()](NexusParser::num_total_trees) give the numbers of
+    /// trees read so far.
+    ///
+    /// # Arguments
+    /// * `poll_interval` - Time to wait before checking again for new trees
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::{Burnin, NexusParserBuilder};
+    /// use std::time::Duration;
+    ///
+    /// let mut parser = NexusParserBuilder::for_file("running.trees")?
+    ///     .with_burnin(Burnin::Count(100))
+    ///     .follow(Duration::from_secs(1))
+    ///     .build()?;
+    /// // Blocks until the next tree is written or the TREES block is closed
+    /// while let Some(tree) = parser.next_tree()? {
+    ///     println!("{:?}: {}", tree.name(), tree.height());
+    /// }
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn follow(mut self, poll_interval: Duration) -> Self {
+        self.follow = Some(poll_interval);
+        self
+    }
+
     /// Configure the parser to parse vertex annotations
-    /// (e.g. `[&rate=0.5,pop_size=1.2]`) instead of treating them as comments.
+    /// (e.g. `[&rate=0.5,pop_size=1.2]` or `[&&NHX:S=human]`) instead of
+    /// treating them as comments.
     pub fn with_annotations(mut self) -> Self {
         self.parse_annotations = true;
         self
     }
 
+    /// Configure how the parser handles unrooted trees written with a basal
+    /// trifurcation (e.g. `(A,B,(C,D));`), see [UnrootedTreePolicy]
+    /// (default: [UnrootedTreePolicy::Keep]).
+    pub fn with_unrooted_tree_policy(mut self, policy: UnrootedTreePolicy) -> Self {
+        self.unrooted_tree_policy = policy;
+        self
+    }
+
+    /// Configure how the parser handles negative, NaN, and infinite branch
+    /// lengths, see [BranchLengthPolicy] (default: [BranchLengthPolicy::Error]).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::newick::BranchLengthPolicy;
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// // Neighbour-joining trees might have negative branch lengths
+    /// let parser = NexusParserBuilder::for_file("nj_trees.nex")?
+    ///     .with_branch_length_policy(BranchLengthPolicy::ClampToZero)
+    ///     .build()?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_branch_length_policy(mut self, policy: BranchLengthPolicy) -> Self {
+        self.branch_length_policy = policy;
+        self
+    }
+
+    /// Configure how the parser handles branch annotations, i.e. annotations
+    /// after the colon of a branch length (e.g. `A:[&rate=0.9]0.12`), see
+    /// [BranchAnnotationPolicy]; only relevant with
+    /// [with_annotations()](Self::with_annotations).
+    pub fn with_branch_annotation_policy(mut self, policy: BranchAnnotationPolicy) -> Self {
+        self.branch_annotation_policy = policy;
+        self
+    }
+
+    /// Configure the parser to skip malformed trees (**lenient mode**)
+    /// instead of failing on the first one.
+    ///
+    /// When a `TREE` command cannot be parsed, the parser resynchronizes after
+    /// its semicolon and continues with the next `TREE` command. The errors
+    /// of skipped trees, each with the index and name of the tree, are
+    /// available via [errors()](NexusParser::errors) or
+    /// [into_results_with_errors()](NexusParser::into_results_with_errors).
+    /// Skipped trees still count towards [num_total_trees()](NexusParser::num_total_trees)
+    /// and thus burnin.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// let parser = NexusParserBuilder::for_file("corvidae.trees")?
+    ///     .with_error_recovery()
+    ///     .build()?;
+    /// let (trees, label_storage, errors) = parser.into_results_with_errors()?;
+    /// for error in &errors {
+    ///     eprintln!("Skipped tree {:?}: {error}", error.tree_name());
+    /// }
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_error_recovery(mut self) -> Self {
+        self.error_recovery = true;
+        self
+    }
+
+    /// Configure the parser to parse `DATA` and `CHARACTERS` blocks
+    /// (before or after the TREES block) into [Alignment]s, instead of
+    /// skipping them.
+    ///
+    /// Sequences are ordered like the taxa of the TAXA block, so that the
+    /// index of a taxon in the alignment equals its index in the
+    /// [LeafLabelMap](crate::model::LeafLabelMap). Without TAXA block, a
+    /// `DATA` block preceding the TREES block defines the taxa. The file
+    /// then does not need to have a TREES block at all.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// let parser = NexusParserBuilder::for_file("anatidae.nex")?
+    ///     .with_alignment()
+    ///     .build()?;
+    /// let alignment = &parser.alignments()[0];
+    /// println!("{} sites of {}", alignment.num_chars(), alignment.data_type());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_alignment(mut self) -> Self {
+        self.parse_alignment = true;
+        self
+    }
+
+    /// Configure the parser to parse `DISTANCES` blocks (before or after the
+    /// TREES block) into [DistanceMatrix]s, instead of skipping them.
+    ///
+    /// Rows and columns are indexed like the taxa of the TAXA block, so that
+    /// distances can be looked up by the [LabelIndex](crate::model::LabelIndex)
+    /// of the [LeafLabelMap](crate::model::LeafLabelMap) shared with the trees.
+    /// Without TAXA block, a `DISTANCES` block preceding the TREES block
+    /// defines the taxa. The file then does not need to have a TREES block
+    /// at all, e.g. as written by PAUP* or SplitsTree.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// let parser = NexusParserBuilder::for_file("laridae.dist")?
+    ///     .with_distances()
+    ///     .build()?;
+    /// let matrix = &parser.distance_matrices()[0];
+    /// println!("d(0, 1) = {:?}", matrix.get(0, 1));
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_distances(mut self) -> Self {
+        self.parse_distances = true;
+        self
+    }
+
+    /// Configure the parser to parse `SETS` blocks (before or after the
+    /// TREES block) and resolve their `TAXSET` and `TREESET` definitions
+    /// into [NexusSets], instead of skipping them.
+    ///
+    /// Elements of sets can be labels or names, (1-based) numbers, ranges
+    /// like `1-5` or `1-.` with optional step like `1-10\3`, or names of
+    /// earlier sets. Taxa are resolved to their
+    /// [LabelIndex](crate::model::LabelIndex) in the label storage, trees
+    /// to their (0-based) index among all trees in the file, including
+    /// skipped and burnin trees.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// let parser = NexusParserBuilder::for_file("procellariidae.trees")?
+    ///     .with_sets()
+    ///     .build()?;
+    /// if let Some(outgroup) = parser.sets().taxset("outgroup") {
+    ///     println!("Outgroup taxa: {outgroup:?}");
+    /// }
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_sets(mut self) -> Self {
+        self.parse_sets = true;
+        self
+    }
+
     /// Configure the parser to read the file using a **buffered reader**.
     ///
     /// The file is read in chunks through a buffered I/O reader, keeping
@@ -419,10 +928,22 @@ impl<T: TreeBuilder> NexusParserBuilder<T> {
                 }
             },
             path: self.path,
+            reader: self.reader,
             read_strategy: self.read_strategy,
             burnin: self.burnin,
             skip_first: self.skip_first,
+            thinning: self.thinning,
+            state_range: self.state_range,
+            tree_index_path: self.tree_index_path,
+            follow: self.follow,
             parse_annotations: self.parse_annotations,
+            branch_annotation_policy: self.branch_annotation_policy,
+            branch_length_policy: self.branch_length_policy,
+            unrooted_tree_policy: self.unrooted_tree_policy,
+            error_recovery: self.error_recovery,
+            parse_alignment: self.parse_alignment,
+            parse_distances: self.parse_distances,
+            parse_sets: self.parse_sets,
             tree_builder,
         }
     }
@@ -430,11 +951,12 @@ impl<T: TreeBuilder> NexusParserBuilder<T> {
     /// Builds and initializes the [NexusParser] with the configured settings.
     ///
     /// This method:
-    /// 1. Creates the parser with the configured options
-    /// 2. Parses the NEXUS header and TAXA block
+    /// 1. Creates the parser with the configured options (decompressing
+    ///    compressed files, detected by their magic bytes)
+    /// 2. Parses the NEXUS header and TAXA block (if present)
     /// 3. Parses the TRANSLATE command (if present) in the TREES block
     /// 4. Counts total trees and if burnin set, applies both burnin
-    ///    and skip-first setting
+    ///    and skip-first setting, then state range and thinning
     /// 5. In eager mode: parses and stores all remaining trees
     /// 6. In lazy mode: positions the parser at the first tree to return
     ///
@@ -448,9 +970,12 @@ impl<T: TreeBuilder> NexusParserBuilder<T> {
     /// # Errors
     /// Returns a [ParsingError] if:
     /// - The file is not a valid NEXUS file
-    /// - Required blocks (TAXA, TREES) are missing
+    /// - Required TREES block is missing
     /// - The NEXUS format is malformed
     /// - Tree parser fails (in eager mode) for some reason
+    /// - The file is compressed in a format whose cargo feature is not enabled
+    /// - The settings cannot be combined, e.g. [follow()](Self::follow) with
+    ///   a compressed file
     ///
     /// # Example
     /// ```no_run
@@ -466,56 +991,221 @@ impl<T: TreeBuilder> NexusParserBuilder<T> {
     ///
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn build(self) -> Result<NexusParser<T>, ParsingError> {
+    pub fn build(mut self) -> Result<NexusParser<T>, ParsingError> {
         /// File size threshold (in bytes) for automatic read strategy.
         /// Files smaller than this are read into memory; larger files use buffered I/O.
         const AUTO_IN_MEMORY_THRESHOLD: u64 = 100 * 1024 * 1024; // 100 MB
 
-        let use_buffered = match self.read_strategy {
+        /// Assumed ratio of decompressed to compressed size of compressed
+        /// files, to estimate their size for the automatic read strategy.
+        const ASSUMED_COMPRESSION_RATIO: u64 = 10;
+
+        // (Follow mode needs to read the file while it grows, so buffered)
+        let read_strategy = match self.follow {
+            Some(_) => ReadStrategy::Buffered,
+            None => self.read_strategy,
+        };
+        let follow = self.follow.map(|poll_interval| FollowState {
+            poll_interval: Some(poll_interval),
+            finished: false,
+            num_candidates: 0,
+            taxa_pending: false,
+        });
+        if follow.is_some() {
+            self.check_tree_count_not_needed("in follow mode")?;
+        }
+
+        // Readers cannot be rewound, so they are read into memory or,
+        // in lazy mode, streamed without counting trees first
+        if let Some(reader) = self.reader.take() {
+            self.tree_index_path = None;
+            let is_lazy = matches!(self.mode, TreeParsingMode::Lazy { .. }) || follow.is_some();
+            if is_lazy && read_strategy != ReadStrategy::InMemory {
+                self.check_tree_count_not_needed("when streaming from a reader")?;
+                let stream = FollowState {
+                    poll_interval: None,
+                    finished: false,
+                    num_candidates: 0,
+                    taxa_pending: false,
+                };
+                let byte_parser = ByteParser::from_reader(reader);
+                return Ok(NexusParser::Streamed(
+                    self.build_inner(byte_parser, Some(stream))?,
+                ));
+            }
+            let byte_parser = ByteParser::from_reader_in_memory(reader)?;
+            return Ok(NexusParser::InMemory(self.build_inner(byte_parser, None)?));
+        }
+
+        let compression = Compression::detect(&self.path)?;
+        if follow.is_some()
+            && let Some(compression) = compression
+        {
+            return Err(ParsingError::invalid_configuration(format!(
+                "Cannot follow a {compression:?} compressed file, as it cannot be read while being written."
+            )));
+        }
+
+        let use_buffered = match read_strategy {
             ReadStrategy::Buffered => true,
             ReadStrategy::InMemory => false,
             ReadStrategy::Automatic => {
-                let file_size = std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+                let mut file_size = std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+                if compression.is_some() {
+                    file_size = file_size.saturating_mul(ASSUMED_COMPRESSION_RATIO);
+                }
                 file_size >= AUTO_IN_MEMORY_THRESHOLD
             }
         };
 
+        // (Compressed files cannot be read buffered, but streamed through a
+        // decompressor; read into memory, they are decompressed upfront)
+        match (use_buffered, compression) {
+            (true, None) => {
+                let byte_parser = ByteParser::from_file_buffered(&self.path)?;
+                Ok(NexusParser::Buffered(
+                    self.build_inner(byte_parser, follow)?,
+                ))
+            }
+            (true, Some(_)) => {
+                let byte_parser = ByteParser::from_file_decompressing(&self.path)?;
+                Ok(NexusParser::Streamed(
+                    self.build_inner(byte_parser, follow)?,
+                ))
+            }
+            (false, _) => {
+                let byte_parser = ByteParser::from_file_in_memory(&self.path)?;
+                Ok(NexusParser::InMemory(
+                    self.build_inner(byte_parser, follow)?,
+                ))
+            }
+        }
+    }
+
+    /// Checks that neither burnin nor thinning need the number of trees, which
+    /// is not known upfront when reading trees as they come (`context`, e.g.
+    /// `"in follow mode"`).
+    ///
+    /// # Errors
+    /// Returns [ParsingErrorType::InvalidConfiguration] for [Burnin::Percentage]
+    /// and [Thinning::Target].
+    fn check_tree_count_not_needed(&self, context: &str) -> Result<(), ParsingError> {
+        if let Burnin::Percentage(p) = self.burnin {
+            return Err(ParsingError::invalid_configuration(format!(
+                "Burnin::Percentage({p}) needs the number of trees, which is unknown {context}; use Burnin::Count instead."
+            )));
+        }
+        if let Some(Thinning::Target(n)) = self.thinning {
+            return Err(ParsingError::invalid_configuration(format!(
+                "Thinning::Target({n}) needs the number of trees, which is unknown {context}; use Thinning::Every instead."
+            )));
+        }
+        Ok(())
+    }
+
+    /// Creates the inner parser on the given byte source and initializes it,
+    /// in follow mode (or streaming) if `follow` is given.
+    fn build_inner<B: ByteSource>(
+        self,
+        byte_parser: ByteParser<B>,
+        follow: Option<FollowState>,
+    ) -> Result<NexusParserInner<B, T>, ParsingError> {
+        // (Follow mode needs to read the file while it grows, so lazily)
+        let mut mode = self.mode;
+        if follow.is_some() {
+            mode = TreeParsingMode::Lazy { start_byte_pos: 0 };
+        }
+
+        let tree_index = match &self.tree_index_path {
+            Some(index_path) => Some(TreeIndexFile::new(index_path, &self.path)?),
+            None => None,
+        };
+
         let mut newick_parser = NewickParser::new(self.tree_builder);
         newick_parser.set_parse_annotations(self.parse_annotations);
+        newick_parser.set_branch_annotation_policy(self.branch_annotation_policy);
+        newick_parser.set_branch_length_policy(self.branch_length_policy);
+        newick_parser.set_unrooted_tree_policy(self.unrooted_tree_policy);
+
+        let mut inner = NexusParserInner {
+            mode,
+            newick_parser,
+            byte_parser,
+            num_leaves: 0,
+            num_total_trees: 0,
+            num_trees: 0,
+            start_tree_pos: 0,
+            tree_pos: 0,
+            burnin: self.burnin,
+            skip_first: self.skip_first,
+            thinning: self.thinning,
+            state_range: self.state_range,
+            tree_states: Vec::new(),
+            selected_trees: None,
+            tree_offsets: Vec::new(),
+            tree_index,
+            follow,
+            error_recovery: self.error_recovery,
+            errors: Vec::new(),
+            current_tree_name: None,
+            parse_alignment: self.parse_alignment,
+            parse_distances: self.parse_distances,
+            taxa: Vec::new(),
+            alignments: Vec::new(),
+            distance_matrices: Vec::new(),
+            parse_sets: self.parse_sets,
+            set_definitions: Vec::new(),
+            sets: NexusSets::default(),
+            taxa_blocks: Vec::new(),
+            trees_title: None,
+            taxa_title: None,
+            first_tree_pos: None,
+        };
+        inner.init()?;
+        Ok(inner)
+    }
+}
 
-        if use_buffered {
-            let byte_parser = ByteParser::from_file_buffered(&self.path)?;
-            let mut inner = NexusParserInner {
-                mode: self.mode,
-                newick_parser,
-                byte_parser,
-                num_leaves: 0,
-                num_total_trees: 0,
-                num_trees: 0,
-                start_tree_pos: 0,
-                tree_pos: 0,
-                burnin: self.burnin,
-                skip_first: self.skip_first,
-            };
-            inner.init()?;
-            Ok(NexusParser::Buffered(inner))
-        } else {
-            let byte_parser = ByteParser::from_file_in_memory(&self.path)?;
-            let mut inner = NexusParserInner {
-                mode: self.mode,
-                newick_parser,
-                byte_parser,
-                num_leaves: 0,
-                num_total_trees: 0,
-                num_trees: 0,
-                start_tree_pos: 0,
-                tree_pos: 0,
-                burnin: self.burnin,
-                skip_first: self.skip_first,
-            };
-            inner.init()?;
-            Ok(NexusParser::InMemory(inner))
-        }
+// =#========================================================================#=
+// NEXUS TREES BLOCK
+// =#========================================================================$=
+/// Trees of a single TREES block together with the [LabelStorage] of their
+/// taxa, as returned by [NexusParser::into_trees_blocks()].
+///
+/// Files written by Mesquite (and some pipelines) contain several TAXA and
+/// TREES blocks, identified by `TITLE` commands and linked by
+/// `LINK TAXA = <title>;` commands.
+pub struct NexusTreesBlock<T: TreeBuilder> {
+    title: Option<String>,
+    taxa_title: Option<String>,
+    trees: Vec<T::Tree>,
+    label_storage: T::Storage,
+}
+
+impl<T: TreeBuilder> NexusTreesBlock<T> {
+    /// Returns the title of the TREES block, if given by a `TITLE` command.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the title of the TAXA block the trees refer to, if given.
+    pub fn taxa_title(&self) -> Option<&str> {
+        self.taxa_title.as_deref()
+    }
+
+    /// Returns the trees of the block (without skipped and burnin trees).
+    pub fn trees(&self) -> &[T::Tree] {
+        &self.trees
+    }
+
+    /// Returns the [LabelStorage] of the taxa of the block.
+    pub fn label_storage(&self) -> &T::Storage {
+        &self.label_storage
+    }
+
+    /// Consumes the block and returns its trees and [LabelStorage].
+    pub fn into_results(self) -> (Vec<T::Tree>, T::Storage) {
+        (self.trees, self.label_storage)
     }
 }
 
@@ -596,6 +1286,9 @@ pub enum NexusParser<T: TreeBuilder> {
     Buffered(NexusParserInner<BufferedByteSource, T>),
     /// Nexus Parser with in-memory file read
     InMemory(NexusParserInner<InMemoryByteSource, T>),
+    /// Nexus Parser streaming a compressed file through a decompressor,
+    /// or streaming from a reader
+    Streamed(NexusParserInner<ReaderByteSource<Box<dyn Read + Send>>, T>),
 }
 
 /// Helper macro to delegate a method call to the inner parser variant.
@@ -604,6 +1297,7 @@ macro_rules! delegate {
         match $self {
             NexusParser::Buffered(inner) => inner.$method($($arg),*),
             NexusParser::InMemory(inner) => inner.$method($($arg),*),
+            NexusParser::Streamed(inner) => inner.$method($($arg),*),
         }
     };
 }
@@ -625,12 +1319,78 @@ impl<T: TreeBuilder> NexusParser<T> {
         delegate!(self, into_results)
     }
 
-    /// Get the number of leaves/taxa based on TAXA block.
-    pub fn num_leaves(&self) -> usize {
+    /// Consumes this [NexusParser] and returns the trees of all TREES blocks
+    /// of the file, each with the [LabelStorage] of its own taxa, in order
+    /// of the file.
+    ///
+    /// The first block is as retrieved by [into_results()](Self::into_results);
+    /// burnin and skipping the first tree apply to each block. Each block uses
+    /// the taxa of the TAXA block linked by `LINK TAXA = <title>;` or else
+    /// of the last preceding TAXA block (or its own TRANSLATE command).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// let parser = NexusParserBuilder::for_file("mesquite_project.nex")?.build()?;
+    /// for block in parser.into_trees_blocks()? {
+    ///     println!("{:?}: {} trees", block.title(), block.trees().len());
+    /// }
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn into_trees_blocks(self) -> Result<Vec<NexusTreesBlock<T>>, ParsingError> {
+        delegate!(self, into_trees_blocks)
+    }
+
+    /// Consumes this [NexusParser] and returns the resulting trees,
+    /// [LabelStorage], and errors of malformed trees skipped in lenient mode
+    /// (see [with_error_recovery()](NexusParserBuilder::with_error_recovery)).
+    #[allow(clippy::type_complexity)]
+    pub fn into_results_with_errors(
+        self,
+    ) -> Result<(Vec<T::Tree>, T::Storage, Vec<ParsingError>), ParsingError> {
+        delegate!(self, into_results_with_errors)
+    }
+
+    /// Get the errors of malformed trees skipped so far in lenient mode
+    /// (see [with_error_recovery()](NexusParserBuilder::with_error_recovery)),
+    /// each with the index and name of the tree.
+    pub fn errors(&self) -> &[ParsingError] {
+        delegate!(self, errors)
+    }
+
+    /// Get the [Alignment]s of all `DATA`/`CHARACTERS` blocks, if configured
+    /// to parse them (see [with_alignment()](NexusParserBuilder::with_alignment)).
+    pub fn alignments(&self) -> &[Alignment] {
+        delegate!(self, alignments)
+    }
+
+    /// Get the [DistanceMatrix]s of all `DISTANCES` blocks, if configured
+    /// to parse them (see [with_distances()](NexusParserBuilder::with_distances)).
+    pub fn distance_matrices(&self) -> &[DistanceMatrix] {
+        delegate!(self, distance_matrices)
+    }
+
+    /// Get the [NexusSets] of all `SETS` blocks, if configured to parse them
+    /// (see [with_sets()](NexusParserBuilder::with_sets)); empty otherwise.
+    pub fn sets(&self) -> &NexusSets {
+        delegate!(self, sets)
+    }
+
+    /// Get the number of leaves/taxa based on TAXA block
+    /// (or TRANSLATE command or first tree, if no TAXA block).
+    pub fn num_leaves(&self) -> usize {
         delegate!(self, num_leaves)
     }
 
-    /// Get ref to [LabelStorage] of all taxa based on TAXA block.
+    /// Get the title of the (first) TREES block, if given by a `TITLE` command.
+    pub fn trees_title(&self) -> Option<&str> {
+        delegate!(self, trees_title)
+    }
+
+    /// Get ref to [LabelStorage] of all taxa based on TAXA block
+    /// (or TRANSLATE command or first tree, if no TAXA block).
     pub fn label_storage(&self) -> &T::Storage {
         delegate!(self, label_storage)
     }
@@ -658,9 +1418,67 @@ impl<T: TreeBuilder> NexusParser<T> {
     /// Intended for **lazy mode** only. In eager mode returns `Ok(None)` as
     /// trees are already parsed — use [next_tree_ref()](Self::next_tree_ref)
     /// or [into_results()](Self::into_results) instead.
+    ///
+    /// In [follow mode](NexusParserBuilder::follow), waits until the next
+    /// tree has been written or the TREES block is closed.
     pub fn next_tree(&mut self) -> Result<Option<T::Tree>, ParsingError> {
         delegate!(self, next_tree)
     }
+
+    /// Parses and returns the next tree if it has been written already.
+    ///
+    /// In [follow mode](NexusParserBuilder::follow), returns `Ok(None)`
+    /// instead of waiting if the next tree has not been written completely
+    /// yet (see [is_finished()](Self::is_finished)); otherwise the same as
+    /// [next_tree()](Self::next_tree).
+    pub fn next_available_tree(&mut self) -> Result<Option<T::Tree>, ParsingError> {
+        delegate!(self, next_available_tree)
+    }
+
+    /// Returns whether the end of the TREES block has been read in
+    /// [follow mode](NexusParserBuilder::follow) (or when streaming from a
+    /// [reader](NexusParserBuilder::for_reader)), i.e. no more trees will
+    /// be written; always true otherwise.
+    pub fn is_finished(&self) -> bool {
+        delegate!(self, is_finished)
+    }
+
+    /// Returns a reference to the `index`-th tree (without skipped, burnin,
+    /// and thinned out trees), or `None` if out of bounds.
+    ///
+    /// Intended for **eager mode** only. In lazy mode, trees aren't stored,
+    /// so this always returns `None` — use [tree_at()](Self::tree_at) instead.
+    pub fn tree_ref_at(&self, index: usize) -> Option<&T::Tree> {
+        delegate!(self, tree_ref_at, index)
+    }
+
+    /// Parses and returns the `index`-th tree (without skipped, burnin, and
+    /// thinned out trees), or `Ok(None)` if out of bounds.
+    ///
+    /// Moves directly to the tree by its byte offset recorded when counting
+    /// the trees (or loaded from the [tree index](NexusParserBuilder::with_tree_index)),
+    /// without affecting [next_tree()](Self::next_tree).
+    ///
+    /// Intended for **lazy mode** only. In eager mode returns `Ok(None)` —
+    /// use [tree_ref_at()](Self::tree_ref_at) instead.
+    ///
+    /// # Errors
+    /// Returns a [ParsingError] if the tree is malformed (also in lenient mode).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use nexwick::nexus::NexusParserBuilder;
+    ///
+    /// let mut parser = NexusParserBuilder::for_file("turdidae.trees")?
+    ///     .lazy()
+    ///     .build()?;
+    /// let last = parser.tree_at(parser.num_trees() - 1)?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn tree_at(&mut self, index: usize) -> Result<Option<T::Tree>, ParsingError> {
+        delegate!(self, tree_at, index)
+    }
 }
 
 // =#========================================================================#=
@@ -679,6 +1497,55 @@ struct NexusParserInner<B: ByteSource, T: TreeBuilder> {
     skip_first: bool,
     /// Amount of burnin to discard/skip
     burnin: Burnin,
+    /// Thinning of the trees remaining after burnin, if any
+    thinning: Option<Thinning>,
+    /// Range of MCMC states of trees to keep, if any
+    state_range: Option<StateRange>,
+    /// MCMC states of all trees (from their names), only collected
+    /// when counting trees with a state range configured
+    tree_states: Vec<Option<u64>>,
+    /// Indices of the trees selected by state range and thinning (in order),
+    /// or `None` if all trees from `start_tree_pos` on are considered
+    selected_trees: Option<Vec<usize>>,
+    /// Byte offsets of all `TREE` commands, recorded when counting trees
+    tree_offsets: Vec<usize>,
+    /// Sidecar file to load tree offsets from or save them to, if configured
+    tree_index: Option<TreeIndexFile>,
+    /// State of follow mode, if configured
+    follow: Option<FollowState>,
+    /// Whether to skip malformed trees instead of failing (lenient mode)
+    error_recovery: bool,
+    /// Errors of malformed trees skipped in lenient mode
+    errors: Vec<ParsingError>,
+    /// Name of the `TREE` command being parsed, attached to its errors
+    current_tree_name: Option<String>,
+
+    /// Whether to parse DATA/CHARACTERS blocks
+    parse_alignment: bool,
+    /// Whether to parse DISTANCES blocks
+    parse_distances: bool,
+    /// Labels of taxa in order of TAXA block (or TRANSLATE command/DATA block),
+    /// if known; used to order sequences of alignments and distance matrices
+    taxa: Vec<String>,
+    /// Alignments of parsed DATA/CHARACTERS blocks
+    alignments: Vec<Alignment>,
+    /// Distance matrices of parsed DISTANCES blocks
+    distance_matrices: Vec<DistanceMatrix>,
+    /// Whether to parse SETS blocks
+    parse_sets: bool,
+    /// Definitions of TAXSETs/TREESETs of SETS blocks, resolved at the end of init
+    set_definitions: Vec<SetDefinition>,
+    /// Resolved sets of SETS blocks
+    sets: NexusSets,
+
+    /// Title and labels of each TAXA block parsed so far
+    taxa_blocks: Vec<(Option<String>, Vec<String>)>,
+    /// Title of the (current) TREES block, if given by `TITLE` command
+    trees_title: Option<String>,
+    /// Title of the TAXA block the (current) TREES block refers to, if any
+    taxa_title: Option<String>,
+    /// Position of the first `TREE` command, if there is a TREES block
+    first_tree_pos: Option<usize>,
 
     /// Number of leaves/taxa in all TAXA block and all trees (must be consistent)
     num_leaves: usize,
@@ -703,6 +1570,8 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
     ///
     /// Parses the header and TAXA block of the Nexus file, counts the number
     /// of trees, applies burnin and moves to first tree to being parsed.
+    /// If there is no TAXA block, the taxa are derived from the TRANSLATE
+    /// command or, if there is none either, from the leaves of the first tree.
     /// If configured to be in eager mode, also parses all trees.
     ///
     /// # Returns
@@ -712,24 +1581,73 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         // > Header
         self.parse_nexus_header()?;
 
-        // > TAXA block
-        self.skip_until_block(NexusBlock::Taxa)?;
-        let label_storage = self.parse_taxa_block()?;
+        // > TAXA block (optional) and, if configured, DATA/CHARACTERS,
+        // DISTANCES, and SETS blocks
+        let mut targets = vec![NexusBlock::Taxa, NexusBlock::Trees];
+        if self.parse_alignment {
+            targets.extend([NexusBlock::Data, NexusBlock::Characters]);
+        }
+        if self.parse_distances {
+            targets.push(NexusBlock::Distances);
+        }
+        if self.parse_sets {
+            targets.push(NexusBlock::Sets);
+        }
+        let has_trees_block = loop {
+            match self.skip_until_block(&targets)? {
+                Some(NexusBlock::Taxa) => self.parse_taxa_block()?,
+                Some(NexusBlock::Trees) => break true,
+                Some(block) => self.parse_data_block(&block)?,
+                // (Without TREES block, only of interest for its data blocks)
+                None if self.parse_any_data_block() => break false,
+                None => return Err(ParsingError::unexpected_eof(&mut self.byte_parser)),
+            }
+        };
 
-        // > TREES block
-        // Skip until TREES block and ...
-        self.skip_until_block(NexusBlock::Trees)?;
-        // ... handle TRANSLATE command
-        let map = self.parse_tree_block_translate()?;
+        // ... without TAXA block, a DATA or DISTANCES block defines the taxa
+        let default_taxa = match self.taxa_blocks.is_empty() {
+            true => self.taxa_of_first_data_block(),
+            false => None,
+        };
 
-        // ... and based on whether it exists, pick the appropriate label resolver
-        let resolver = self.choose_resolver(label_storage, map)?;
-        self.newick_parser
-            .set_num_leaves(self.num_leaves)
-            .set_resolver(resolver);
+        // ... and without TREES block, there are no trees to consider
+        if !has_trees_block {
+            let taxa = self.taxa_blocks.last().map(|(_, taxa)| taxa.clone());
+            let label_storage = match taxa.or(default_taxa) {
+                Some(taxa) => self.label_storage_from_taxa(taxa),
+                None => T::create_storage(0),
+            };
+            let resolver = self.choose_resolver(label_storage, None)?;
+            self.newick_parser.set_resolver(resolver);
+            self.configure_tree_counts(0);
+            self.mode = match self.mode {
+                TreeParsingMode::Eager { .. } => TreeParsingMode::Eager { trees: Vec::new() },
+                TreeParsingMode::Lazy { .. } => TreeParsingMode::Lazy {
+                    start_byte_pos: self.byte_parser.position(),
+                },
+            };
+            return self.resolve_sets(None);
+        }
 
-        // Then move to the first tree
-        self.byte_parser.skip_comment_and_whitespace()?;
+        // > TREES block
+        self.init_trees_block(default_taxa)?;
+        let first_tree_pos = self.byte_parser.position();
+        self.first_tree_pos = Some(first_tree_pos);
+
+        // In follow mode, the file might still be written (and streams cannot
+        // be rewound), so trees are neither counted nor is anything after
+        // them parsed
+        if let Some(follow) = &self.follow {
+            let taxa_pending = follow.taxa_pending;
+            self.mode = TreeParsingMode::Lazy {
+                start_byte_pos: first_tree_pos,
+            };
+            // (Sets are resolved once the taxa are known)
+            return match taxa_pending {
+                true => Ok(()),
+                false => self.resolve_sets(None),
+            };
+        }
 
         // Decide which scenario to use based on mode and burnin significance
         // Scenario 1: Lazy mode - always count first
@@ -738,22 +1656,37 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         // Scenario 3: Eager + insignificant burnin - parse all, then discard
         // -> one pass over the trees block
         let is_eager = matches!(self.mode, TreeParsingMode::Eager { .. });
-        let use_two_pass = !is_eager || self.burnin.significant();
+        // (Lenient mode always counts first, so that skipped trees count towards burnin,
+        // and so do thinning and state range, so that unselected trees are only skipped)
+        let use_two_pass = !is_eager
+            || self.burnin.significant()
+            || self.error_recovery
+            || self.thinning.is_some()
+            || self.state_range.is_some()
+            || self.tree_index.is_some();
 
         if use_two_pass {
-            // Scenarios 1 & 2: Count trees, configure counts, skip unwanted trees
-            let total_trees = self.count_trees()?;
+            // Scenarios 1 & 2: Count trees (or load their offsets), configure counts,
+            // skip unwanted trees
+            let total_trees = self.count_trees_with_index()?;
             self.configure_tree_counts(total_trees);
 
-            // Skip past the trees we don't want
-            for _ in 0..self.start_tree_pos {
-                self.skip_tree()?;
+            // Skip past the trees we don't want, directly by offset if possible
+            match self.tree_offsets.get(self.start_tree_pos) {
+                Some(&offset) => self.byte_parser.set_position(offset),
+                None => {
+                    for _ in 0..self.start_tree_pos {
+                        self.skip_tree()?;
+                    }
+                }
             }
 
             // For eager mode, parse the trees we want to keep
             if is_eager {
                 let mut trees = Vec::with_capacity(self.num_trees);
                 self.parse_tree_block_trees(&mut trees)?;
+                // (Fewer trees than counted if malformed ones were skipped)
+                self.num_trees = trees.len();
                 self.mode = TreeParsingMode::Eager { trees };
             } else {
                 // For lazy mode, capture byte position for reset capability
@@ -781,6 +1714,14 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
             self.mode = TreeParsingMode::Eager { trees };
         }
 
+        // > DATA/CHARACTERS, DISTANCES, and SETS blocks after TREES block (if configured)
+        if self.parse_any_data_block() {
+            self.parse_data_blocks_after_trees()?;
+        }
+
+        // > Resolve TAXSETs and TREESETs (if any)
+        self.resolve_sets(Some(first_tree_pos))?;
+
         Ok(())
     }
 
@@ -809,48 +1750,262 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         // The position where actual trees start (after skipped and burnin trees)
         self.start_tree_pos = skip_count;
         self.tree_pos = skip_count;
+
+        // Select among the remaining trees by state range, then thin them out
+        self.selected_trees = None;
+        if self.thinning.is_some() || self.state_range.is_some() {
+            let mut selected: Vec<usize> = (skip_count..num_total_trees)
+                .filter(|&index| self.is_in_state_range(index))
+                .collect();
+            if let Some(thinning) = &self.thinning {
+                selected = thinning.select(selected);
+            }
+            self.num_trees = selected.len();
+            self.selected_trees = Some(selected);
+        }
+    }
+
+    /// Returns whether the MCMC state of the tree at `index` lies in the
+    /// configured state range (or whether no range is configured).
+    fn is_in_state_range(&self, index: usize) -> bool {
+        match &self.state_range {
+            Some(range) => self
+                .tree_states
+                .get(index)
+                .copied()
+                .flatten()
+                .is_some_and(|state| range.contains(&state)),
+            None => true,
+        }
+    }
+
+    /// Returns whether the tree at `index` is selected by state range and
+    /// thinning (always true if neither is configured).
+    fn is_selected(&self, index: usize) -> bool {
+        match &self.selected_trees {
+            Some(selected) => selected.binary_search(&index).is_ok(),
+            None => true,
+        }
+    }
+
+    /// Returns the index after the last tree to consider.
+    fn end_tree_pos(&self) -> usize {
+        match &self.selected_trees {
+            Some(selected) => selected
+                .last()
+                .map_or(self.start_tree_pos, |&index| index + 1),
+            None => self.start_tree_pos + self.num_trees,
+        }
+    }
+
+    /// Initializes parsing a TREES block, whose header has just been consumed,
+    /// and moves to its first tree.
+    ///
+    /// Handles `TITLE` and `LINK` commands (in any order before the first
+    /// tree, see [TreesBlockHeader]), picks the taxa of the linked
+    /// TAXA block (or otherwise the last one, or `default_taxa`), and sets up
+    /// the label storage and resolver based on the `TRANSLATE` command.
+    /// Without any taxa, these are derived from the `TRANSLATE` command or,
+    /// if there is none either, from the leaves of the first tree.
+    fn init_trees_block(&mut self, default_taxa: Option<Vec<String>>) -> Result<(), ParsingError> {
+        // Handle TITLE, LINK, and TRANSLATE commands ...
+        let TreesBlockHeader {
+            title,
+            link,
+            translation,
+        } = self.parse_tree_block_header()?;
+        let taxa_block = match &link {
+            Some(link) => {
+                let linked = self.taxa_blocks.iter().find(|(taxa_title, _)| {
+                    taxa_title
+                        .as_ref()
+                        .is_some_and(|taxa_title| taxa_title.eq_ignore_ascii_case(link))
+                });
+                if linked.is_none() {
+                    return Err(ParsingError::invalid_trees_block(
+                        &mut self.byte_parser,
+                        format!("Linked TAXA block '{link}' not found."),
+                    ));
+                }
+                linked
+            }
+            None => self.taxa_blocks.last(),
+        };
+        self.trees_title = title;
+        self.taxa_title = taxa_block.and_then(|(taxa_title, _)| taxa_title.clone());
+        let taxa = taxa_block.map(|(_, taxa)| taxa.clone()).or(default_taxa);
+
+        // ... and without TAXA block, derive taxa from TRANSLATE command (if it exists)
+        let derive_from_first_tree = taxa.is_none() && translation.is_none();
+        let label_storage = match (taxa, &translation) {
+            (Some(taxa), _) => self.label_storage_from_taxa(taxa),
+            (None, Some(translation)) => {
+                let taxa = translation.iter().map(|(_, label)| label.clone()).collect();
+                self.label_storage_from_taxa(taxa)
+            }
+            (None, None) => self.label_storage_from_taxa(Vec::new()),
+        };
+
+        // ... and based on whether it exists, pick the appropriate label resolver
+        let resolver = self.choose_resolver(label_storage, translation)?;
+        self.newick_parser.set_resolver(resolver);
+
+        // Then move to the first tree
+        self.byte_parser.skip_comment_and_whitespace()?;
+
+        // ... and if neither TAXA block nor TRANSLATE command,
+        // derive taxa from the leaves of the first tree (when following a
+        // file, once the first tree has been written completely)
+        if derive_from_first_tree {
+            match &mut self.follow {
+                Some(follow) if follow.poll_interval.is_some() => {
+                    follow.taxa_pending = true;
+                    return Ok(());
+                }
+                _ => self.derive_taxa_from_first_tree()?,
+            }
+        }
+        self.newick_parser.set_num_leaves(self.num_leaves);
+        Ok(())
+    }
+
+    /// Helper method to bundle the trees of the current TREES block with
+    /// its titles and label storage, which is taken from the Newick parser.
+    fn take_trees_block(&mut self, trees: Vec<T::Tree>) -> NexusTreesBlock<T> {
+        let empty_resolver = LabelResolver::new_verbatim_labels_resolver(T::create_storage(0));
+        let resolver = self.newick_parser.replace_resolver(empty_resolver);
+        NexusTreesBlock {
+            title: self.trees_title.take(),
+            taxa_title: self.taxa_title.take(),
+            trees,
+            label_storage: resolver.into_label_storage(),
+        }
     }
 
     /// Helper method to pick and configure the right [LabelResolver]
     /// at initialization.
+    ///
+    /// The `translation` of a TRANSLATE command may cover only some of the
+    /// taxa (partial TRANSLATE), with the others referenced in trees by
+    /// their number or label, see [LabelResolver::NexusLabels].
+    ///
+    /// # Errors
+    /// Returns [ParsingError::InvalidTranslateCommand] if keys are not unique
+    /// or a label is not a taxon of the label storage.
     fn choose_resolver(
         &mut self,
         label_storage: T::Storage,
-        map: Option<HashMap<String, String>>,
+        translation: Option<Vec<(String, String)>>,
     ) -> Result<LabelResolver<T::Storage>, ParsingError> {
-        Ok(match map {
-            None => LabelResolver::new_verbatim_labels_resolver(label_storage),
-            Some(map) => {
-                // Assert that labels match those provided in TAXA block
-                let labels_consistent = map.len() == label_storage.num_labels()
-                    && map
-                        .values()
-                        .all(|label| label_storage.check_and_ref(label).is_some());
-                if !labels_consistent {
-                    return Err(ParsingError::invalid_translate_command(
-                        &mut self.byte_parser,
-                    ));
-                }
+        // Without TRANSLATE command, leaves must be taxa of the TAXA block
+        // (unless there are none yet, as these get derived from the first tree)
+        let Some(translation) = translation else {
+            return Ok(match label_storage.num_labels() {
+                0 => LabelResolver::new_verbatim_labels_resolver(label_storage),
+                _ => LabelResolver::new_taxa_labels_resolver(label_storage),
+            });
+        };
 
-                // Check if all keys are integers to use the more efficient NexusIntegerLabels resolver
-                let all_keys_are_integers = map.keys().all(|key| key.parse::<usize>().is_ok());
+        let num_pairs = translation.len();
+        let map: HashMap<String, String> = translation.into_iter().collect();
+        if map.len() < num_pairs {
+            return Err(ParsingError::invalid_translate_command(
+                &mut self.byte_parser,
+                String::from("Keys are not unique."),
+            ));
+        }
 
-                if all_keys_are_integers {
-                    LabelResolver::new_nexus_integer_labels_resolver(map, label_storage)
-                } else {
-                    LabelResolver::new_nexus_labels_resolver(map, label_storage)
-                }
-            }
+        // Check that labels match those provided in TAXA block,
+        // though the TRANSLATE command may cover only some of them
+        if let Some(label) = map
+            .values()
+            .find(|label| label_storage.check_and_ref(label).is_none())
+        {
+            return Err(ParsingError::invalid_translate_command(
+                &mut self.byte_parser,
+                format!("Label '{label}' is not a taxon of the TAXA block."),
+            ));
+        }
+
+        // Check if the keys are the integers 1..=n of all n taxa to use the more
+        // efficient NexusIntegerLabels resolver; for partial TRANSLATE commands
+        // (or other keys), the NexusLabels resolver falls back to taxon numbers
+        // and labels of the TAXA block
+        let num_labels = label_storage.num_labels();
+        let is_complete_integer_map = map.len() == num_labels
+            && map.keys().all(|key| {
+                key.parse::<usize>()
+                    .is_ok_and(|i| (1..=num_labels).contains(&i))
+            });
+
+        let resolver = if is_complete_integer_map {
+            LabelResolver::new_nexus_integer_labels_resolver(map, label_storage)
+        } else {
+            LabelResolver::new_nexus_labels_resolver(map, label_storage)
+        };
+        resolver.map_err(|err| {
+            ParsingError::invalid_translate_command(&mut self.byte_parser, err.to_string())
         })
     }
 
+    /// Helper method to create the label storage from taxa (in the given
+    /// order), e.g. of the TAXA block or, if there is none, from the labels
+    /// of the TRANSLATE command.
+    fn label_storage_from_taxa(&mut self, taxa: Vec<String>) -> T::Storage {
+        let mut label_storage = T::create_storage(taxa.len());
+        for label in &taxa {
+            label_storage.store_and_ref(label);
+        }
+        self.num_leaves = label_storage.num_labels();
+        self.taxa = taxa;
+        label_storage
+    }
+
+    /// Helper method to derive the taxa from the leaves of the first tree,
+    /// if there is neither a TAXA block nor a TRANSLATE command.
+    ///
+    /// Parses the first tree with the verbatim label resolver, which thereby
+    /// stores its labels, and then moves back to the start of the first tree.
+    /// The stored labels become the taxa (in order of the leaves), and later
+    /// trees are resolved against them like against a TAXA block.
+    fn derive_taxa_from_first_tree(&mut self) -> Result<(), ParsingError> {
+        let start_pos = self.byte_parser.position();
+        if let Err(err) = self.parse_single_tree() {
+            return Err(err.with_tree(0, None));
+        }
+        self.byte_parser.set_position(start_pos);
+
+        let label_storage = self.newick_parser.label_storage();
+        self.num_leaves = label_storage.num_labels();
+        self.taxa = (0..self.num_leaves)
+            .filter_map(|index| label_storage.get_label(index).map(String::from))
+            .collect();
+
+        // Leaves that are not taxa now give an error instead of new labels
+        let empty_resolver = LabelResolver::new_verbatim_labels_resolver(T::create_storage(0));
+        let resolver = self.newick_parser.replace_resolver(empty_resolver);
+        let resolver = LabelResolver::new_taxa_labels_resolver(resolver.into_label_storage());
+        self.newick_parser.set_resolver(resolver);
+        Ok(())
+    }
+
     /// Reset to first tree (respecting skip-first and burnin setting)
     pub fn reset(&mut self) {
+        // (Streams cannot be read again)
+        if self.is_stream() {
+            return;
+        }
         self.tree_pos = self.start_tree_pos;
 
         // In lazy mode, also reset the byte parser position
+        // (and errors, as trees get parsed again)
         if let TreeParsingMode::Lazy { start_byte_pos } = self.mode {
             self.byte_parser.set_position(start_byte_pos);
+            self.errors.clear();
+        }
+        if let Some(follow) = &mut self.follow {
+            follow.finished = false;
+            follow.num_candidates = 0;
         }
     }
 }
@@ -881,10 +2036,24 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
     /// # Returns
     /// A vector of the `T::Tree`s and the corresponding [LabelStorage]
     /// from the parsed Nexus file.
-    pub fn into_results(mut self) -> Result<(Vec<T::Tree>, T::Storage), ParsingError> {
+    pub fn into_results(self) -> Result<(Vec<T::Tree>, T::Storage), ParsingError> {
+        let (trees, label_storage, _) = self.into_results_with_errors()?;
+        Ok((trees, label_storage))
+    }
+
+    /// Consumes this [NexusParser] and returns the resulting `T::Tree`s,
+    /// [LabelStorage], and errors of skipped malformed trees (lenient mode).
+    ///
+    /// # Returns
+    /// A vector of the `T::Tree`s, the corresponding [LabelStorage],
+    /// and a vector of the errors of trees skipped in lenient mode.
+    #[allow(clippy::type_complexity)]
+    pub fn into_results_with_errors(
+        mut self,
+    ) -> Result<(Vec<T::Tree>, T::Storage, Vec<ParsingError>), ParsingError> {
         match self.mode {
             TreeParsingMode::Eager { trees } => {
-                Ok((trees, self.newick_parser.into_label_storage()))
+                Ok((trees, self.newick_parser.into_label_storage(), self.errors))
             }
             TreeParsingMode::Lazy { .. } => {
                 let mut all_trees = Vec::new();
@@ -892,9 +2061,67 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
                 while let Some(tree) = self.next_tree()? {
                     all_trees.push(tree);
                 }
-                Ok((all_trees, self.newick_parser.into_label_storage()))
+                Ok((
+                    all_trees,
+                    self.newick_parser.into_label_storage(),
+                    self.errors,
+                ))
+            }
+        }
+    }
+
+    /// Consumes this [NexusParser] and returns the trees of all TREES blocks,
+    /// each with the [LabelStorage] of its taxa.
+    ///
+    /// # Returns
+    /// A vector of [NexusTreesBlock]s in order of the file; empty if there
+    /// is no TREES block.
+    pub fn into_trees_blocks(mut self) -> Result<Vec<NexusTreesBlock<T>>, ParsingError> {
+        let Some(first_tree_pos) = self.first_tree_pos else {
+            return Ok(Vec::new());
+        };
+
+        // > First TREES block, as configured (eager or lazy)
+        let placeholder = TreeParsingMode::Eager { trees: Vec::new() };
+        let trees = match std::mem::replace(&mut self.mode, placeholder) {
+            TreeParsingMode::Eager { trees } => trees,
+            TreeParsingMode::Lazy { start_byte_pos } => {
+                self.mode = TreeParsingMode::Lazy { start_byte_pos };
+                let mut trees = Vec::new();
+                self.reset();
+                while let Some(tree) = self.next_tree()? {
+                    trees.push(tree);
+                }
+                trees
+            }
+        };
+        let mut blocks = vec![self.take_trees_block(trees)];
+
+        // > Further TAXA and TREES blocks
+        self.byte_parser.set_position(first_tree_pos);
+        while self.skip_tree()? {}
+        self.skip_to_block_end()?;
+        loop {
+            match self.skip_until_block(&[NexusBlock::Taxa, NexusBlock::Trees])? {
+                None => break,
+                Some(NexusBlock::Taxa) => self.parse_taxa_block()?,
+                Some(_) => {
+                    self.init_trees_block(None)?;
+                    let total_trees = self.count_trees()?;
+                    self.configure_tree_counts(total_trees);
+                    for _ in 0..self.start_tree_pos {
+                        self.skip_tree()?;
+                    }
+
+                    let mut trees = Vec::with_capacity(self.num_trees);
+                    self.parse_tree_block_trees(&mut trees)?;
+                    self.skip_to_block_end()?;
+                    blocks.push(self.take_trees_block(trees));
+                }
             }
         }
+
+        Ok(blocks)
     }
 }
 
@@ -907,6 +2134,11 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         self.num_leaves
     }
 
+    /// Get the title of the (first) TREES block, if given
+    pub fn trees_title(&self) -> Option<&str> {
+        self.trees_title.as_deref()
+    }
+
     /// Get ref to [LabelStorage] of all taxa based on TAXA block
     pub fn label_storage(&self) -> &T::Storage {
         self.newick_parser.label_storage()
@@ -922,6 +2154,26 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         self.num_total_trees
     }
 
+    /// Get the errors of malformed trees skipped in lenient mode
+    pub fn errors(&self) -> &[ParsingError] {
+        &self.errors
+    }
+
+    /// Get the alignments of parsed DATA/CHARACTERS blocks
+    pub fn alignments(&self) -> &[Alignment] {
+        &self.alignments
+    }
+
+    /// Get the distance matrices of parsed DISTANCES blocks
+    pub fn distance_matrices(&self) -> &[DistanceMatrix] {
+        &self.distance_matrices
+    }
+
+    /// Get the resolved sets of parsed SETS blocks
+    pub fn sets(&self) -> &NexusSets {
+        &self.sets
+    }
+
     /// Returns a reference to the next tree.
     ///
     /// Intended for **eager mode** only. In lazy mode, trees aren't stored,
@@ -945,6 +2197,50 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         }
     }
 
+    /// Returns a reference to the `index`-th tree.
+    ///
+    /// Intended for **eager mode** only. In lazy mode, trees aren't stored,
+    /// so this always returns `None`.
+    pub fn tree_ref_at(&self, index: usize) -> Option<&T::Tree> {
+        match &self.mode {
+            TreeParsingMode::Eager { trees } => trees.get(index),
+            TreeParsingMode::Lazy { .. } => None,
+        }
+    }
+
+    /// Parses and returns the `index`-th tree, moving directly to its
+    /// recorded byte offset and back afterwards.
+    ///
+    /// Intended for **lazy mode** only. In eager mode returns `Ok(None)`.
+    ///
+    /// # Returns
+    /// * `Ok(Some(Tree))` - The `index`-th tree (lazy mode only)
+    /// * `Ok(None)` - Index out of bounds, or in eager mode
+    /// * `Err(ParsingError)` - Parsing failed (lazy mode only)
+    pub fn tree_at(&mut self, index: usize) -> Result<Option<T::Tree>, ParsingError> {
+        if matches!(self.mode, TreeParsingMode::Eager { .. }) || index >= self.num_trees {
+            return Ok(None);
+        }
+        let tree_index = match &self.selected_trees {
+            Some(selected) => selected[index],
+            None => self.start_tree_pos + index,
+        };
+        let Some(&offset) = self.tree_offsets.get(tree_index) else {
+            return Ok(None);
+        };
+
+        // Parse tree at its offset, then move back to continue with next_tree()
+        let saved_pos = self.byte_parser.position();
+        self.byte_parser.set_position(offset);
+        let result = match self.parse_single_tree() {
+            Ok(Some(tree)) => Ok(Some(tree)),
+            Ok(None) => Err(ParsingError::unexpected_eof(&mut self.byte_parser)),
+            Err(err) => Err(self.attach_tree_to_error(err, tree_index)),
+        };
+        self.byte_parser.set_position(saved_pos);
+        result
+    }
+
     /// Parses and returns the next tree.
     ///
     /// Intended for **lazy mode** only. In eager mode return `Ok(None)` as
@@ -961,22 +2257,201 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
                 // Trees already parsed — use next_tree_ref() instead
                 Ok(None)
             }
+            TreeParsingMode::Lazy { .. } if self.follow.is_some() => {
+                // Wait for the next tree in follow mode
+                self.next_tree_following(true)
+            }
             TreeParsingMode::Lazy { .. } => {
-                // Check if we've reached the end
-                if self.tree_pos >= self.start_tree_pos + self.num_trees {
+                // (Loop only continues if malformed trees are skipped in lenient mode)
+                loop {
+                    // Check if we've reached the end
+                    if self.tree_pos >= self.end_tree_pos() {
+                        return Ok(None);
+                    }
+
+                    // Skip trees not selected by state range or thinning
+                    let tree_index = self.tree_pos;
+                    self.tree_pos += 1;
+                    if !self.is_selected(tree_index) {
+                        self.skip_tree()?;
+                        continue;
+                    }
+
+                    // Parse next tree on demand
+                    match self.parse_single_tree() {
+                        Ok(Some(tree)) => return Ok(Some(tree)),
+                        Ok(None) => {
+                            return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
+                        }
+                        Err(err) => self.recover_from_tree_error(err, tree_index)?,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the next tree if it is available, without waiting in
+    /// follow mode; otherwise the same as [next_tree()](Self::next_tree).
+    ///
+    /// # Returns
+    /// * `Ok(Some(Tree))` - The next tree (lazy mode only)
+    /// * `Ok(None)` - No more trees or next tree not written yet, or in eager mode
+    /// * `Err(ParsingError)` - Parsing failed (lazy mode only)
+    pub fn next_available_tree(&mut self) -> Result<Option<T::Tree>, ParsingError> {
+        match (&self.mode, &self.follow) {
+            (TreeParsingMode::Lazy { .. }, Some(_)) => self.next_tree_following(false),
+            _ => self.next_tree(),
+        }
+    }
+
+    /// Returns whether the end of the TREES block has been read in
+    /// follow mode; always true otherwise.
+    pub fn is_finished(&self) -> bool {
+        self.follow.as_ref().is_none_or(|follow| follow.finished)
+    }
+}
+
+// ============================================================================
+// Follow mode (private)
+// ============================================================================
+impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
+    /// Returns the next tree in follow mode, once it has been written
+    /// completely.
+    ///
+    /// # Arguments
+    /// * `wait` - Whether to wait for the next tree (or the end of the TREES
+    ///   block) if not available yet, or return `Ok(None)` instead
+    ///
+    /// # Returns
+    /// * `Ok(Some(Tree))` - The next tree
+    /// * `Ok(None)` - End of TREES block, or next tree not available (if not waiting)
+    /// * `Err(ParsingError)` - Parsing failed
+    fn next_tree_following(&mut self, wait: bool) -> Result<Option<T::Tree>, ParsingError> {
+        // (Loop continues while waiting and for skipped trees)
+        loop {
+            let (poll_interval, finished) = match &self.follow {
+                Some(follow) => (follow.poll_interval, follow.finished),
+                None => return Ok(None),
+            };
+            if finished {
+                return Ok(None);
+            }
+
+            // Wait until the next command has been written completely
+            // (not needed for streams, whose reads block instead)
+            if let Some(poll_interval) = poll_interval
+                && !self.is_next_command_complete()
+            {
+                if !wait {
                     return Ok(None);
                 }
+                std::thread::sleep(poll_interval);
+                continue;
+            }
 
-                // Parse next tree on demand
-                let tree = self.parse_single_tree()?;
-                if tree.is_none() {
-                    return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
+            self.byte_parser.skip_comment_and_whitespace()?;
+            if consume_block_end(&mut self.byte_parser)? {
+                if let Some(follow) = &mut self.follow {
+                    follow.finished = true;
                 }
-                self.tree_pos += 1;
-                Ok(tree)
+                return Ok(None);
+            }
+            if self.byte_parser.is_eof() {
+                return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
+            }
+            if self
+                .follow
+                .as_ref()
+                .is_some_and(|follow| follow.taxa_pending)
+            {
+                self.derive_pending_taxa()?;
+            }
+
+            // Count tree (if read for the first time) and skip it if not selected
+            let tree_index = self.tree_pos;
+            self.tree_pos += 1;
+            let is_new = tree_index == self.num_total_trees;
+            if is_new {
+                self.num_total_trees += 1;
+            }
+            if !self.is_selected_following(tree_index)? {
+                self.skip_tree()?;
+                continue;
+            }
+            if is_new {
+                self.num_trees += 1;
+            }
+
+            match self.parse_single_tree() {
+                Ok(Some(tree)) => return Ok(Some(tree)),
+                Ok(None) => return Err(ParsingError::unexpected_eof(&mut self.byte_parser)),
+                Err(err) => self.recover_from_tree_error(err, tree_index)?,
             }
         }
     }
+
+    /// Returns whether the parser streams from a reader, which cannot be
+    /// rewound, see [NexusParserBuilder::for_reader()].
+    fn is_stream(&self) -> bool {
+        self.follow
+            .as_ref()
+            .is_some_and(|follow| follow.poll_interval.is_none())
+    }
+
+    /// Derives the taxa from the first tree, deferred in follow mode until
+    /// it has been written completely, and resolves the sets against them.
+    fn derive_pending_taxa(&mut self) -> Result<(), ParsingError> {
+        self.derive_taxa_from_first_tree()?;
+        self.newick_parser.set_num_leaves(self.num_leaves);
+        if let Some(follow) = &mut self.follow {
+            follow.taxa_pending = false;
+        }
+        self.resolve_sets(None)
+    }
+
+    /// Returns whether the next command (e.g. `TREE` command or `END;`) has
+    /// been written completely, i.e. up to its semicolon (outside of quotes
+    /// and comments), without moving the parser.
+    fn is_next_command_complete(&mut self) -> bool {
+        let start_pos = self.byte_parser.position();
+        let is_complete = self.byte_parser.skip_comment_and_whitespace().is_ok()
+            && self.byte_parser.consume_until_unquoted(b';');
+        self.byte_parser.set_position(start_pos);
+        is_complete
+    }
+
+    /// Returns whether the tree at `index` is selected in follow mode, i.e.
+    /// is not skipped, not burnin, in the state range, and not thinned out.
+    ///
+    /// Assumes the parser is positioned at the start of the `TREE` command.
+    fn is_selected_following(&mut self, index: usize) -> Result<bool, ParsingError> {
+        // (Number of trees unknown, so Burnin::Percentage is rejected by the builder)
+        let skip_count = usize::from(self.skip_first) + self.burnin.get_count(0);
+        if index < skip_count {
+            return Ok(false);
+        }
+        if let Some(range) = &self.state_range {
+            let range = *range;
+            if !self
+                .peek_tree_state()?
+                .is_some_and(|state| range.contains(&state))
+            {
+                return Ok(false);
+            }
+        }
+
+        // Thinning among trees after burnin and in state range
+        let Some(follow) = &mut self.follow else {
+            return Ok(true);
+        };
+        let candidate = follow.num_candidates;
+        follow.num_candidates += 1;
+        // (Thinning::Target is rejected by the builder)
+        Ok(match self.thinning {
+            Some(Thinning::Every(k)) => candidate % k.max(1) == 0,
+            Some(Thinning::Target(_)) | None => true,
+        })
+    }
 }
 
 // ============================================================================
@@ -995,19 +2470,23 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         Ok(())
     }
 
-    /// Skips Nexus blocks until we encounter the target block type, whose
-    /// header is consumed;
-    /// returns `[ParsingError::UnexpectedEOF]` if block not found.
-    fn skip_until_block(&mut self, target: NexusBlock) -> Result<(), ParsingError> {
+    /// Skips Nexus blocks until we encounter one of the target block types,
+    /// whose header is consumed and which is returned;
+    /// returns `None` if no such block found before the end of the file.
+    fn skip_until_block(
+        &mut self,
+        targets: &[NexusBlock],
+    ) -> Result<Option<NexusBlock>, ParsingError> {
         loop {
+            self.byte_parser.skip_comment_and_whitespace()?;
             if self.byte_parser.is_eof() {
-                return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
+                return Ok(None);
             }
 
             let block_type = self.detect_next_block()?;
 
-            if block_type == target {
-                return Ok(());
+            if targets.contains(&block_type) {
+                return Ok(Some(block_type));
             }
 
             self.skip_to_block_end()?;
@@ -1020,284 +2499,352 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
     fn detect_next_block(&mut self) -> Result<NexusBlock, ParsingError> {
         self.byte_parser.skip_comment_and_whitespace()?;
 
-        if !self.byte_parser.consume_if_sequence(BLOCK_BEGIN) {
+        if !self
+            .byte_parser
+            .consume_if_keyword(BLOCK_BEGIN, WORD_DELIMITERS)
+        {
             return Err(ParsingError::invalid_formatting(&mut self.byte_parser));
         }
-        self.byte_parser.skip_comment_and_whitespace()?;
 
-        let block_name = self.byte_parser.parse_unquoted_label(b";")?;
-
-        self.byte_parser.next_byte(); // consume the ';' now (already know that this is next byte)
+        // Block name is the first token of the remaining command, e.g. of "BEGIN TAXA ;"
+        let Some(header) = next_command(&mut self.byte_parser)? else {
+            return Err(ParsingError::invalid_block_name(&mut self.byte_parser));
+        };
 
-        Ok(NexusBlock::from_name(&block_name))
+        Ok(NexusBlock::from_name(header.name()))
     }
 
     /// Skips block, e.g. continuing until encountering and consuming `END;`.
     fn skip_to_block_end(&mut self) -> Result<(), ParsingError> {
-        if !self
-            .byte_parser
-            .consume_until_sequence(BLOCK_END, Inclusive)
+        skip_block(&mut self.byte_parser)
+    }
+
+    /// Parses DATA/CHARACTERS block into an [Alignment], DISTANCES block
+    /// into a [DistanceMatrix], or SETS block into set definitions, if
+    /// configured to do so (otherwise skips it), with sequences or distances
+    /// ordered like the taxa (if known).
+    fn parse_data_block(&mut self, block: &NexusBlock) -> Result<(), ParsingError> {
+        match block {
+            NexusBlock::Data | NexusBlock::Characters if self.parse_alignment => {
+                let alignment = parse_characters_block(&mut self.byte_parser, &self.taxa)?;
+                self.alignments.push(alignment);
+            }
+            NexusBlock::Distances if self.parse_distances => {
+                let matrix = parse_distances_block(&mut self.byte_parser, &self.taxa)?;
+                self.distance_matrices.push(matrix);
+            }
+            NexusBlock::Sets if self.parse_sets => {
+                let definitions = parse_sets_block(&mut self.byte_parser)?;
+                self.set_definitions.extend(definitions);
+            }
+            _ => self.skip_to_block_end()?,
+        }
+        Ok(())
+    }
+
+    /// Returns whether any of DATA/CHARACTERS, DISTANCES, or SETS blocks
+    /// are configured to be parsed.
+    fn parse_any_data_block(&self) -> bool {
+        self.parse_alignment || self.parse_distances || self.parse_sets
+    }
+
+    /// Resolves the definitions of parsed SETS blocks against the taxa and,
+    /// if there are TREESETs, the names of the trees starting at
+    /// `first_tree_pos` (`None` if there is no TREES block).
+    fn resolve_sets(&mut self, first_tree_pos: Option<usize>) -> Result<(), ParsingError> {
+        if self.set_definitions.is_empty() {
+            return Ok(());
+        }
+
+        let mut tree_names = Vec::new();
+        if let Some(first_tree_pos) = first_tree_pos
+            && self.set_definitions.iter().any(|d| d.is_treeset())
         {
-            return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
+            let saved_pos = self.byte_parser.position();
+            self.byte_parser.set_position(first_tree_pos);
+            while let Some(name) = self.next_tree_name()? {
+                tree_names.push(name);
+            }
+            self.byte_parser.set_position(saved_pos);
+        }
+
+        self.sets = resolve_sets(&self.set_definitions, &self.taxa, &tree_names)
+            .map_err(|msg| ParsingError::invalid_sets_block(&mut self.byte_parser, msg))?;
+        Ok(())
+    }
+
+    /// Returns the name of the next tree and skips it,
+    /// or `None` if at the end of the TREES block.
+    fn next_tree_name(&mut self) -> Result<Option<String>, ParsingError> {
+        self.byte_parser.skip_comment_and_whitespace()?;
+        let start_pos = self.byte_parser.position();
+        let name = self.parse_tree_name()?.unwrap_or_default();
+
+        self.byte_parser.set_position(start_pos);
+        Ok(self.skip_tree()?.then_some(name))
+    }
+
+    /// Returns the taxa of the first parsed DATA/CHARACTERS or DISTANCES block,
+    /// if any; used to define the taxa without TAXA block.
+    fn taxa_of_first_data_block(&self) -> Option<Vec<String>> {
+        self.alignments
+            .first()
+            .map(|alignment| alignment.taxa().to_vec())
+            .or_else(|| {
+                self.distance_matrices
+                    .first()
+                    .map(|matrix| matrix.taxa().to_vec())
+            })
+    }
+
+    /// Parses DATA/CHARACTERS and DISTANCES blocks following the TREES block
+    /// (as configured) and afterwards moves back to the current position.
+    fn parse_data_blocks_after_trees(&mut self) -> Result<(), ParsingError> {
+        let saved_pos = self.byte_parser.position();
+
+        // Skip remaining trees and "END;" of TREES block
+        while self.skip_tree()? {}
+        self.skip_to_block_end()?;
+
+        loop {
+            self.byte_parser.skip_comment_and_whitespace()?;
+            if self.byte_parser.is_eof() {
+                break;
+            }
+            let block = self.detect_next_block()?;
+            self.parse_data_block(&block)?;
         }
 
+        self.byte_parser.set_position(saved_pos);
         Ok(())
     }
 
-    /// Parses TAXA block extracting number of taxa from `ntax` command
-    /// and taxon list from `TAXLABEL` command, ignoring any other command and comments.
+    /// Parses TAXA block extracting number of taxa from `DIMENSIONS` command
+    /// and taxon list from `TAXLABELS` command, ignoring any other command and comments.
+    ///
+    /// Records the title and labels of the block (see `taxa_blocks`), with
+    /// the labels also becoming the current taxa.
     ///
     /// # Assumptions
-    /// * First command must be `DIMENSIONS NTAX=<value>;` (case-insensitive)
-    ///   - `<value>` must be integer and followed by a semicolon
-    ///   - No comment within command allowed
-    /// * Followed by list of labels command `TAXLABEL [label1 label2 ...];`,
-    ///   with the following details:
-    ///   - Space separated list of labels
-    ///   - Terminated by semicolon
-    ///   - Comments allowed
-    /// * Comments allowed outside the two commands
+    /// * Must contain a `DIMENSIONS NTAX=<value>;` command (case-insensitive)
+    ///   - `<value>` must be integer
+    /// * Labels are given by one or more `TAXLABELS [label1 label2 ...];` commands,
+    ///   whose labels are concatenated (in order)
+    /// * Optional `TITLE <title>;` command names the block
+    /// * Commands may appear in any order and tokens may be separated by any
+    ///   whitespace, newlines, and comments, e.g. `DIMENSIONS NTAX = 5 ;`
     ///
     /// # Errors
     /// Returns [ParsingError::UnexpectedEOF] if block, command, or comment
-    /// not properly closed, and [ParsingErrror::InvalidTaxaBlock] if commands
-    /// not encountered in expected order (as specified above).
-    fn parse_taxa_block(&mut self) -> Result<T::Storage, ParsingError> {
-        // 1. Parse number of taxa command "DIMENSIONS NTAX=n;"
-        self.parse_taxa_block_ntax()?;
-
-        // 2. Parse list of taxa labels in `TAXLABEL` command, including consuming closing ";"
-        let label_storage = self.parse_taxa_block_labels()?;
-
-        // 3. Move to end of block
-        self.skip_to_block_end()?;
-        // Would expect only "END;" besides whitespace and comments, but not enforced here
+    /// not properly closed, and [ParsingErrror::InvalidTaxaBlock] if `NTAX`
+    /// is missing or does not match the number of labels.
+    fn parse_taxa_block(&mut self) -> Result<(), ParsingError> {
+        let mut title = None;
+        let mut num_taxa = None;
+        let mut taxa = Vec::new();
+
+        // Parse commands until "END;"
+        loop {
+            self.byte_parser.skip_comment_and_whitespace()?;
+            if consume_block_end(&mut self.byte_parser)? {
+                break;
+            }
+            let Some(command) = next_command(&mut self.byte_parser)? else {
+                continue;
+            };
 
-        Ok(label_storage)
-    }
+            match command.name().to_ascii_lowercase().as_str() {
+                "title" => title = command.words().next().map(String::from),
+                "dimensions" => num_taxa = Some(self.parse_taxa_block_ntax(&command)?),
+                "taxlabels" => taxa.extend(command.words().map(String::from)),
+                // Ignore any other command
+                _ => {}
+            }
+        }
 
-    /// Helps parsings TAXA block, responsible for parsing the `ntax` command
-    /// and returning the result, i.e. the number of taxa.
-    fn parse_taxa_block_ntax(&mut self) -> Result<(), ParsingError> {
-        // This could be a replaced with a general parser structure for such a type of command,
-        // but for our nexus files we only have this one.
-        // a) Parse "DIMENSIONS NTAX="
-        self.byte_parser.skip_comment_and_whitespace()?;
-        if !self.byte_parser.consume_if_sequence(DIMENSIONS) {
+        // Check that `ntax` many labels parsed
+        let Some(num_taxa) = num_taxa else {
             return Err(ParsingError::invalid_taxa_block(
                 &mut self.byte_parser,
-                String::from("Expected 'DIMENSIONS' in TAXA block."),
+                String::from("Expected 'DIMENSIONS NTAX=<n>' in TAXA block."),
             ));
-        }
-
-        self.byte_parser.skip_whitespace();
-        if !self.byte_parser.consume_if_sequence(NTAX) {
+        };
+        if taxa.len() != num_taxa {
             return Err(ParsingError::invalid_taxa_block(
                 &mut self.byte_parser,
-                String::from("Expected 'NTAX' in TAXA block."),
+                format!(
+                    "Number of parsed labels ({}) did not match ntax value ({}).",
+                    taxa.len(),
+                    num_taxa
+                ),
             ));
         }
 
-        self.byte_parser.skip_whitespace();
-        if !self.byte_parser.consume_if(b'=') {
+        self.num_leaves = num_taxa;
+        self.taxa = taxa.clone();
+        self.taxa_blocks.push((title, taxa));
+        Ok(())
+    }
+
+    /// Helps parsing TAXA block, responsible for extracting the `NTAX` value
+    /// of the `DIMENSIONS` command, i.e. the number of taxa.
+    fn parse_taxa_block_ntax(&mut self, command: &RawCommand) -> Result<usize, ParsingError> {
+        let Some(ntax_str) = command.value("ntax") else {
             return Err(ParsingError::invalid_taxa_block(
                 &mut self.byte_parser,
-                String::from("Expected '=' in TAXA block."),
+                String::from("Expected 'NTAX=<n>' in DIMENSIONS command of TAXA block."),
             ));
-        }
+        };
 
-        // b) Read the number `n` and consume ";"
-        self.byte_parser.skip_whitespace();
-        let ntax_str = self.byte_parser.parse_unquoted_label(b";")?;
-        let ntax: usize = ntax_str.parse().map_err(|_| {
+        ntax_str.parse().map_err(|_| {
             ParsingError::invalid_taxa_block(
                 &mut self.byte_parser,
                 format!("Cannot parse `ntax` value: {}", ntax_str),
             )
-        })?;
-        self.byte_parser.next_byte(); // consume the semicolon
-
-        self.num_leaves = ntax;
-        Ok(())
+        })
     }
 
-    /// Helps parsing TAXA block, responsible for parsing the `TAXLABEL`
-    /// command and returning the parsed taxa as [LabelStorage].
-    fn parse_taxa_block_labels(&mut self) -> Result<T::Storage, ParsingError> {
-        // a) Parse "TAXLABELS"
-        self.byte_parser.skip_comment_and_whitespace()?;
-        if !self.byte_parser.consume_if_sequence(TAXLABELS) {
-            return Err(ParsingError::invalid_taxa_block(
-                &mut self.byte_parser,
-                String::from("Expected 'TAXLABELS' in TAXA block."),
-            ));
-        }
-
-        // b) Read labels until semicolon
-        let mut label_storage = T::create_storage(self.num_leaves);
-        let mut count = 0;
+    /// Helps parsing TREES block, responsible for parsing all commands before
+    /// the first `TREE` command, i.e. optional `TITLE <title>;` and
+    /// `LINK TAXA = <title>;` commands (as written by Mesquite) and the
+    /// optional `TRANSLATE` command; any other command is skipped.
+    ///
+    /// After this method, the parser will be positioned at the start of the
+    /// first `TREE` command (or the end of the block).
+    fn parse_tree_block_header(&mut self) -> Result<TreesBlockHeader, ParsingError> {
+        let mut header = TreesBlockHeader::default();
         loop {
             self.byte_parser.skip_comment_and_whitespace()?;
-
-            // Stop once encountering semicolon (end of labels command)
-            if self.byte_parser.peek() == Some(b';') {
-                self.byte_parser.next_byte();
-                break;
+            if self.byte_parser.peek_is_keyword(TREE, WORD_DELIMITERS)
+                || peek_is_block_end(&mut self.byte_parser)
+            {
+                return Ok(header);
             }
+            let Some(command) = next_command(&mut self.byte_parser)? else {
+                continue;
+            };
 
-            // Read one label (word until whitespace or semicolon)
-            let label = self.byte_parser.parse_label(NEXUS_LABEL_DELIMITERS)?;
-
-            if !label.is_empty() {
-                label_storage.store_and_ref(&label);
-                count += 1;
+            match command.name().to_ascii_lowercase().as_str() {
+                "title" => header.title = command.words().next().map(String::from),
+                // (Links to other kinds of blocks are ignored)
+                "link" => {
+                    if let Some(link) = command.value("taxa") {
+                        header.link = Some(link.to_string());
+                    }
+                }
+                "translate" => {
+                    header.translation = Some(self.parse_tree_block_translate(&command)?)
+                }
+                // Skip any other command
+                _ => {}
             }
         }
-
-        // c) Check that `num_taxa` many labels parsed
-        if count != self.num_leaves {
-            return Err(ParsingError::invalid_taxa_block(
-                &mut self.byte_parser,
-                format!(
-                    "Number of parsed labels ({}) did not match ntax value ({}).",
-                    count, self.num_leaves
-                ),
-            ));
-        }
-
-        Ok(label_storage)
     }
 
-    /// Helps parsing TREES block, responsible for parsing `TRANSLATE` command.
-    /// If command exists, returns parsed mapping, `None` otherwise.
+    /// Helps parsing TREES block, responsible for parsing the `TRANSLATE`
+    /// command into pairs of key and label (in order).
     ///
-    /// Assumes the parser is positioned at the start of the `TRANSLATE` command
-    /// (after any whitespace/comments).
-    /// After this method, the parser will be positioned after the semicolon of
-    /// the `TRANSLATE` command.
+    /// Pairs are separated by commas, while key and label may be separated
+    /// by any whitespace and comments.
     fn parse_tree_block_translate(
         &mut self,
-    ) -> Result<Option<HashMap<String, String>>, ParsingError> {
-        // a) Parse "TRANSLATE"
-        self.byte_parser.skip_comment_and_whitespace()?;
-        if !self.byte_parser.consume_if_sequence(TRANSLATE) {
-            // there might be no TRANSLATE command, which is fine if the next command is a TREE
-            return if self.byte_parser.peek_is_sequence(TREE) {
-                Ok(None)
-            } else {
-                Err(ParsingError::invalid_taxa_block(
-                    &mut self.byte_parser,
-                    String::from("Expected 'TRANSLATE' or first 'TREE' in TREES block."),
-                ))
+        command: &RawCommand,
+    ) -> Result<Vec<(String, String)>, ParsingError> {
+        let tokens: Vec<&NexusToken> = command
+            .tokens()
+            .iter()
+            .filter(|token| !matches!(token, NexusToken::Comment(_)))
+            .collect();
+
+        let mut translation = Vec::with_capacity(self.num_leaves);
+        for (index, pair) in tokens
+            .split(|token| **token == NexusToken::Comma)
+            .enumerate()
+        {
+            let (key, label) = match pair {
+                // (Tolerate trailing comma)
+                [] => continue,
+                [key, label] => (key.as_str(), label.as_str()),
+                _ => (None, None),
             };
-        }
-
-        // b) Parse pairs "id/short label"
-        let mut map: HashMap<String, String> = HashMap::with_capacity(self.num_leaves);
-        loop {
-            self.byte_parser.skip_comment_and_whitespace()?;
-
-            // Read key (short label or id)
-            let key = self.byte_parser.parse_label(NEXUS_LABEL_DELIMITERS)?;
-
-            // Expect a space
-            if !self.byte_parser.consume_if(b' ') {
-                return Err(ParsingError::invalid_trees_block(
+            let (Some(key), Some(label)) = (key, label) else {
+                return Err(ParsingError::invalid_translate_command(
                     &mut self.byte_parser,
-                    String::from("Expected ' ' in between key and label."),
+                    format!("Expected key and label as entry {}.", index + 1),
                 ));
-            }
-
-            // Parse label
-            let label = self.byte_parser.parse_label(NEXUS_LABEL_DELIMITERS)?;
-            self.byte_parser.skip_whitespace();
-
-            // d) Add to HashMap
-            map.insert(key, label);
+            };
 
-            // e) Continue if next is a comma
-            if self.byte_parser.consume_if(b',') {
-                continue;
-            }
-            // but stop if semicolon (end of "TRANSLATE" command)
-            if self.byte_parser.consume_if(b';') {
-                break;
-            }
-            // and otherwise invalid
-            let char = self.byte_parser.peek().unwrap().to_string();
-            return Err(ParsingError::invalid_trees_block(
-                &mut self.byte_parser,
-                format!("Unexpected char '{char}' in TRANSLATE."),
-            ));
+            // Add pair (keeping order of TRANSLATE command)
+            translation.push((key.to_string(), label.to_string()));
         }
 
-        // c) small check
-        assert_eq!(map.len(), self.num_leaves);
-
-        Ok(Option::from(map))
+        // (Consistency with TAXA block is checked when choosing the resolver)
+        Ok(translation)
     }
 
     /// Helps parsing TREES block, responsible for parsing all `TREE` commands.
-    /// Returns all [GenTree]s parsed and with labels resolved.
+    /// Returns all [GenTree]s parsed and with labels resolved
+    /// (in lenient mode, without malformed trees); trees not selected by
+    /// state range or thinning are skipped.
     ///
     /// Assumes the parser is positioned at the start of the first `TREE` command
     /// (after any whitespace/comments).
     /// After this method, the parser will be positioned after all `TREE` commands,
     /// so before the block closing keyword.
     fn parse_tree_block_trees(&mut self, trees: &mut Vec<T::Tree>) -> Result<(), ParsingError> {
-        let newick_parser = &mut self.newick_parser;
-
-        // let mut tree_count = 0;
+        let mut tree_index = self.tree_pos;
         loop {
-            self.byte_parser.skip_comment_and_whitespace()?;
-
-            // Stop if "END;"
-            if self.byte_parser.peek_is_sequence(BLOCK_END) {
-                break;
+            if !self.is_selected(tree_index) {
+                // Stop if "END;"
+                if !self.skip_tree()? {
+                    break;
+                }
+                tree_index += 1;
+                continue;
             }
 
-            // Expect "TREE"
-            if !self.byte_parser.consume_if_sequence(b"tree") {
-                return Err(ParsingError::invalid_trees_block(
-                    &mut self.byte_parser,
-                    String::from("Expected 'tree' in tree block."),
-                ));
+            match self.parse_single_tree() {
+                Ok(Some(tree)) => trees.push(tree),
+                // Stop if "END;"
+                Ok(None) => break,
+                Err(err) => self.recover_from_tree_error(err, tree_index)?,
             }
+            tree_index += 1;
+        }
 
-            // Parse tree name
-            let name = self.byte_parser.parse_label(NEXUS_LABEL_DELIMITERS)?;
-
-            // Expect "="
-            self.byte_parser.skip_whitespace();
-            if !self.byte_parser.consume_if(b'=') {
-                return Err(ParsingError::invalid_trees_block(
-                    &mut self.byte_parser,
-                    String::from("Expected '=' in tree block. "),
-                ));
-            }
-
-            // Skip optional "[&R/U]" (rooter or unrooted tree) by considering it a comment
-            self.byte_parser.skip_comment_and_whitespace()?;
-
-            // Parse tree
-            let tree = newick_parser.parse_str_and_name(&mut self.byte_parser, Some(name))?;
-
-            // Verbose information
-            // tree_count += 1;
-            // if tree_count % 10 == 0 {
-            //     print!(".");
-            // }
-            // if tree_count % 1000 == 0 {
-            //     println!(" ({tree_count})");
-            // }
+        Ok(())
+    }
 
-            // Store tree
-            trees.push(tree);
+    /// Handles an error that occurred parsing a `TREE` command: in lenient
+    /// mode, records the error with index and name of the tree and skips the
+    /// command until after its semicolon; otherwise returns the error with
+    /// the tree index attached.
+    fn recover_from_tree_error(
+        &mut self,
+        err: ParsingError,
+        tree_index: usize,
+    ) -> Result<(), ParsingError> {
+        let err = self.attach_tree_to_error(err, tree_index);
+        if !self.error_recovery {
+            return Err(err);
         }
 
+        // Skip to end of command, continuing from the error, as streams
+        // cannot be rewound
+        if !self.byte_parser.consume_until_unquoted(b';') {
+            return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
+        }
+        self.errors.push(err);
         Ok(())
     }
 
+    /// Attaches index and name (if already parsed) of the `TREE` command
+    /// to an error that occurred parsing it.
+    fn attach_tree_to_error(&mut self, err: ParsingError, tree_index: usize) -> ParsingError {
+        let tree_name = self.current_tree_name.take();
+        err.with_tree(tree_index, tree_name.filter(|name| !name.is_empty()))
+    }
+
     /// Parses a single TREE entry.
     ///
     /// Assumes the parser is positioned at the start of a `TREE` command
@@ -1309,26 +2856,62 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
     /// * `Ok(None)` - No more trees (encountered END;)
     /// * `Err(ParsingError)` - If the format is invalid
     fn parse_single_tree(&mut self) -> Result<Option<T::Tree>, ParsingError> {
+        self.current_tree_name = None;
         self.byte_parser.skip_comment_and_whitespace()?;
 
         // Check if we've reached the end of the TREES block
-        if self.byte_parser.peek_is_sequence(BLOCK_END) {
+        if peek_is_block_end(&mut self.byte_parser) {
             return Ok(None);
         }
 
-        // Expect "TREE"
-        if !self.byte_parser.consume_if_sequence(TREE) {
+        // Parse "TREE <name> [metadata] =", with MCMC state taken from name
+        let mut metadata = TreeMetadata::default();
+        let name = self.parse_tree_header(Some(&mut metadata))?;
+        if let Some(state) = TreeMetadata::state_from_name(&name) {
+            metadata.set_state(state);
+        }
+
+        // Optional "[&R/U]", "[&W ...]", and "[&key=value,...]" after "="
+        // are parsed by the Newick parser
+        self.byte_parser.skip_whitespace();
+
+        // Parse the Newick tree
+        let tree =
+            self.newick_parser
+                .parse_str_and_name(&mut self.byte_parser, Some(name), metadata)?;
+        Ok(Some(tree))
+    }
+
+    /// Parses the start of a `TREE` command up to and including its `=`
+    /// and returns the name of the tree.
+    ///
+    /// Comments between name and `=` with tree-level metadata (e.g.
+    /// `[&lnP=-1234.5]`) are parsed into `metadata`, if given, and
+    /// otherwise skipped.
+    ///
+    /// # Errors
+    /// Returns [ParsingError::InvalidTreesBlock] if not positioned at a `TREE`
+    /// command or the name is not followed by `=`.
+    fn parse_tree_header(
+        &mut self,
+        metadata: Option<&mut TreeMetadata>,
+    ) -> Result<String, ParsingError> {
+        // Expect "TREE <name>"
+        let Some(name) = self.parse_tree_name()? else {
             return Err(ParsingError::invalid_trees_block(
                 &mut self.byte_parser,
                 String::from("Expected 'TREE' in tree command."),
             ));
-        }
-
-        // Parse tree name
-        let name = self.byte_parser.parse_label(NEXUS_LABEL_DELIMITERS)?;
+        };
+        self.current_tree_name = Some(name.clone());
 
-        // Expect "="
-        self.byte_parser.skip_whitespace();
+        // Expect "=", possibly after comments
+        match metadata {
+            Some(metadata) => {
+                NewickParser::<T>::parse_tree_metadata(&mut self.byte_parser, metadata)?
+            }
+            None => self.byte_parser.skip_comment_and_whitespace()?,
+        }
         if !self.byte_parser.consume_if(b'=') {
             return Err(ParsingError::invalid_trees_block(
                 &mut self.byte_parser,
@@ -1336,14 +2919,23 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
             ));
         }
 
-        // Skip optional "[&R/U]" annotation
-        self.byte_parser.skip_comment_and_whitespace()?;
+        Ok(name)
+    }
 
-        // Parse the Newick tree
-        let tree = self
-            .newick_parser
-            .parse_str_and_name(&mut self.byte_parser, Some(name))?;
-        Ok(Some(tree))
+    /// Parses `TREE [*] <name>` (case-insensitive), where `*` marks a default
+    /// tree, and returns the name of the tree, or `None` if not positioned at
+    /// a `TREE` command.
+    fn parse_tree_name(&mut self) -> Result<Option<String>, ParsingError> {
+        if !self.byte_parser.consume_if_keyword(TREE, WORD_DELIMITERS) {
+            return Ok(None);
+        }
+
+        self.byte_parser.skip_comment_and_whitespace()?;
+        if self.byte_parser.consume_if(b'*') {
+            self.byte_parser.skip_comment_and_whitespace()?;
+        }
+        let name = self.byte_parser.parse_label(WORD_DELIMITERS)?;
+        Ok(Some(name))
     }
 
     /// Skips over a single TREE entry without parsing the Newick string.
@@ -1360,32 +2952,28 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         self.byte_parser.skip_comment_and_whitespace()?;
 
         // Check if we've reached the end of the TREES block
-        if self.byte_parser.peek_is_sequence(BLOCK_END) {
+        if peek_is_block_end(&mut self.byte_parser) {
             return Ok(false);
         }
 
-        // Expect "TREE"
-        if !self.byte_parser.consume_if_sequence(TREE) {
-            return Err(ParsingError::invalid_trees_block(
-                &mut self.byte_parser,
-                String::from("Expected 'tree' in tree command."),
-            ));
+        // In lenient mode, malformed trees are counted as well,
+        // so skip everything until and including the semicolon
+        if self.error_recovery {
+            if !self.byte_parser.consume_until_unquoted(b';') {
+                return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
+            }
+            return Ok(true);
         }
 
-        // Skip tree name (consume until '=')
-        if !self.byte_parser.consume_until(b'=', Exclusive) {
-            return Err(ParsingError::invalid_trees_block(
-                &mut self.byte_parser,
-                String::from("Expected '=' in tree command."),
-            ));
-        }
-        self.byte_parser.next_byte(); // consume the '='
+        // Skip "TREE <name> ="
+        self.parse_tree_header(None)?;
 
         // Skip optional whitespace/comments and "[&R/U]" annotation
         self.byte_parser.skip_comment_and_whitespace()?;
 
-        // Skip the Newick string (everything until and including semicolon)
-        if !self.byte_parser.consume_until(b';', Inclusive) {
+        // Skip the Newick string (everything until and including semicolon,
+        // but not one within a quoted label or comment)
+        if !self.byte_parser.consume_until_unquoted(b';') {
             return Err(ParsingError::unexpected_eof(&mut self.byte_parser));
         }
 
@@ -1396,6 +2984,8 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
     ///
     /// This method saves the current parser position, counts all trees,
     /// then restores the position to where it was before counting.
+    /// If a state range is configured, also collects the MCMC states of
+    /// the trees from their names.
     ///
     /// # Returns
     /// The number of trees in the TREES block
@@ -1403,15 +2993,68 @@ impl<B: ByteSource, T: TreeBuilder> NexusParserInner<B, T> {
         // Save current position
         let saved_pos = self.byte_parser.position();
 
-        // Count trees
+        // Count trees (and record their offsets and states)
         let mut count = 0;
-        while self.skip_tree()? {
+        self.tree_offsets.clear();
+        self.tree_states.clear();
+        loop {
+            self.byte_parser.skip_comment_and_whitespace()?;
+            let offset = self.byte_parser.position();
+            let state = match self.state_range {
+                Some(_) => self.peek_tree_state()?,
+                None => None,
+            };
+            if !self.skip_tree()? {
+                break;
+            }
+            self.tree_offsets.push(offset);
+            self.tree_states.push(state);
             count += 1;
         }
+        if self.state_range.is_none() {
+            self.tree_states.clear();
+        }
 
         // Restore position
         self.byte_parser.set_position(saved_pos);
 
         Ok(count)
     }
+
+    /// Counts the number of trees like [count_trees()](Self::count_trees), but
+    /// loads their offsets from the tree index file instead, if configured
+    /// and up to date (and not needing states); otherwise (re)writes it.
+    ///
+    /// # Returns
+    /// The number of trees in the TREES block
+    fn count_trees_with_index(&mut self) -> Result<usize, ParsingError> {
+        let Some(tree_index) = &self.tree_index else {
+            return self.count_trees();
+        };
+
+        if self.state_range.is_none()
+            && let Some(offsets) = tree_index.load(self.byte_parser.position())
+        {
+            self.tree_offsets = offsets;
+            return Ok(self.tree_offsets.len());
+        }
+
+        let count = self.count_trees()?;
+        if let Some(tree_index) = &self.tree_index {
+            // The index only saves counting next time, so failing to write it
+            // (e.g. in a read-only directory) is no reason to fail parsing
+            let _ = tree_index.save(&self.tree_offsets);
+        }
+        Ok(count)
+    }
+
+    /// Returns the MCMC state given by the name of the next `TREE` command,
+    /// if any, without moving the parser.
+    fn peek_tree_state(&mut self) -> Result<Option<u64>, ParsingError> {
+        self.byte_parser.skip_comment_and_whitespace()?;
+        let start_pos = self.byte_parser.position();
+        let name = self.parse_tree_name().ok().flatten();
+        self.byte_parser.set_position(start_pos);
+        Ok(name.and_then(|name| TreeMetadata::state_from_name(&name)))
+    }
 }
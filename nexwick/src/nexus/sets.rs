@@ -0,0 +1,379 @@
+//! Parsing of Nexus `SETS` blocks into named sets of taxa and trees
+//! ([NexusSets]).
+//!
+//! Supported commands (case-insensitive), any others are skipped:
+//! * `TAXSET [*] <name> [(STANDARD|VECTOR)] = <element> ...;`
+//! * `TREESET [*] <name> [(STANDARD|VECTOR)] = <element> ...;`
+//!
+//! An element in standard format is one of:
+//! * a taxon label or tree name, e.g. `Larus`,
+//! * a (1-based) number, e.g. `3`, or `.` for the last taxon or tree,
+//! * a range with optional step, e.g. `1-5`, `4-.`, or `1-10\3` (every
+//!   third of 1 to 10, i.e. `1 4 7 10`),
+//! * the name of a set defined earlier (of the same kind).
+//!
+//! In vector format, the elements are a sequence of `0`s and `1`s, one per
+//! taxon or tree.
+//!
+//! Since a `SETS` block might precede the trees, its definitions are first
+//! only parsed and resolved once all taxa and trees are known.
+
+use crate::model::LabelIndex;
+use crate::nexus::block_reader::{consume_block_end, skip_command};
+use crate::nexus::characters::OPTION_DELIMITERS;
+use crate::nexus::defs::NEXUS_LABEL_DELIMITERS;
+use crate::parser::byte_parser::ByteParser;
+use crate::parser::byte_source::ByteSource;
+use crate::parser::parsing_error::ParsingError;
+
+/// Delimiters of numbers in ranges of a set definition
+const NUMBER_DELIMITERS: &[u8] = b" \t\n\r;-\\[";
+
+// =#========================================================================#=
+// NEXUS SETS
+// =#========================================================================$=
+/// Named sets of taxa (`TAXSET`) and trees (`TREESET`) of Nexus `SETS` blocks,
+/// e.g. to define outgroups or clade constraints.
+///
+/// Taxa are given by their [LabelIndex] in the label storage
+/// (e.g. [LeafLabelMap](crate::model::LeafLabelMap)) and trees by their
+/// (0-based) index among all `TREE` commands of the TREES block, including
+/// skipped and burnin trees. Set names are case-insensitive, as in Nexus.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NexusSets {
+    taxsets: Vec<(String, Vec<LabelIndex>)>,
+    treesets: Vec<(String, Vec<usize>)>,
+}
+
+impl NexusSets {
+    /// Returns the sorted taxon indices of the `TAXSET` with the given name,
+    /// or `None` if there is no such set.
+    pub fn taxset(&self, name: &str) -> Option<&[LabelIndex]> {
+        find_set(&self.taxsets, name)
+    }
+
+    /// Returns the sorted (0-based) tree indices of the `TREESET` with the
+    /// given name, or `None` if there is no such set.
+    pub fn treeset(&self, name: &str) -> Option<&[usize]> {
+        find_set(&self.treesets, name)
+    }
+
+    /// Returns an iterator over the names and taxon indices of all `TAXSET`s,
+    /// in order of definition.
+    pub fn taxsets(&self) -> impl Iterator<Item = (&str, &[LabelIndex])> {
+        self.taxsets
+            .iter()
+            .map(|(name, set)| (name.as_str(), set.as_slice()))
+    }
+
+    /// Returns an iterator over the names and tree indices of all `TREESET`s,
+    /// in order of definition.
+    pub fn treesets(&self) -> impl Iterator<Item = (&str, &[usize])> {
+        self.treesets
+            .iter()
+            .map(|(name, set)| (name.as_str(), set.as_slice()))
+    }
+
+    /// Returns whether there are neither taxon nor tree sets.
+    pub fn is_empty(&self) -> bool {
+        self.taxsets.is_empty() && self.treesets.is_empty()
+    }
+}
+
+/// Finds the set with the given name (case-insensitive).
+fn find_set<'a>(sets: &'a [(String, Vec<usize>)], name: &str) -> Option<&'a [usize]> {
+    sets.iter()
+        .find(|(set_name, _)| set_name.eq_ignore_ascii_case(name))
+        .map(|(_, set)| set.as_slice())
+}
+
+// =#========================================================================#=
+// SET DEFINITIONS
+// =#========================================================================$=
+/// Kind of a set definition, i.e. whether it is a set of taxa or trees
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SetKind {
+    Taxa,
+    Trees,
+}
+
+/// Element of a set definition in standard format
+#[derive(Debug, Clone, PartialEq)]
+enum SetElement {
+    /// Label of a taxon, name of a tree, or name of an earlier set
+    Name(String),
+    /// Range of (1-based) numbers with step, where `None` denotes the last
+    /// one (`.`); single numbers are ranges with equal start and end
+    Range {
+        start: Option<usize>,
+        end: Option<usize>,
+        step: usize,
+    },
+}
+
+/// Unresolved definition of a `TAXSET` or `TREESET`
+#[derive(Debug, Clone)]
+pub(crate) struct SetDefinition {
+    kind: SetKind,
+    name: String,
+    vector: bool,
+    elements: Vec<SetElement>,
+}
+
+impl SetDefinition {
+    /// Returns whether this is a `TREESET` definition.
+    pub(crate) fn is_treeset(&self) -> bool {
+        self.kind == SetKind::Trees
+    }
+}
+
+/// Parses a `SETS` block into (unresolved) set definitions, including its
+/// closing `END;`.
+///
+/// Assumes the parser is positioned right after the block header.
+///
+/// # Errors
+/// Returns a [ParsingError] if the block is not properly closed or a set
+/// definition is malformed.
+pub(crate) fn parse_sets_block<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+) -> Result<Vec<SetDefinition>, ParsingError> {
+    let mut definitions = Vec::new();
+
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if consume_block_end(parser)? {
+            return Ok(definitions);
+        }
+        if parser.is_eof() {
+            return Err(ParsingError::unexpected_eof(parser));
+        }
+
+        let command = parser.parse_unquoted_label(OPTION_DELIMITERS)?;
+        match command.to_ascii_lowercase().as_str() {
+            "taxset" => definitions.push(parse_set_definition(parser, SetKind::Taxa)?),
+            "treeset" => definitions.push(parse_set_definition(parser, SetKind::Trees)?),
+            // Skip any other command, e.g. CHARSET or TAXPARTITION
+            _ => skip_command(parser)?,
+        }
+    }
+}
+
+/// Parses a set definition `[*] <name> [(STANDARD|VECTOR)] = <element> ...;`
+/// following the command name, including its semicolon.
+fn parse_set_definition<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    kind: SetKind,
+) -> Result<SetDefinition, ParsingError> {
+    // "[*] <name>"
+    parser.skip_comment_and_whitespace()?;
+    parser.consume_if(b'*');
+    parser.skip_comment_and_whitespace()?;
+    let name = parser.parse_label(b" \t\n\r=(;")?;
+
+    // "[(STANDARD|VECTOR)]"
+    parser.skip_comment_and_whitespace()?;
+    let mut vector = false;
+    if parser.consume_if(b'(') {
+        let format = parser.parse_unquoted_label(b")")?;
+        parser.consume_if(b')');
+        vector = format.trim().eq_ignore_ascii_case("vector");
+        parser.skip_comment_and_whitespace()?;
+    }
+
+    // "="
+    if !parser.consume_if(b'=') {
+        return Err(ParsingError::invalid_sets_block(
+            parser,
+            format!("Expected '=' after name of set '{name}'."),
+        ));
+    }
+
+    // "<element> ...;"
+    let mut elements = Vec::new();
+    loop {
+        parser.skip_comment_and_whitespace()?;
+        if parser.consume_if(b';') {
+            break;
+        }
+        match parser.peek() {
+            None => return Err(ParsingError::unexpected_eof(parser)),
+            Some(b'0'..=b'9' | b'.') if !vector && peek_is_range(parser) => {
+                elements.push(parse_range(parser, &name)?)
+            }
+            Some(_) => elements.push(SetElement::Name(
+                parser.parse_label(NEXUS_LABEL_DELIMITERS)?,
+            )),
+        }
+    }
+
+    Ok(SetDefinition {
+        kind,
+        name,
+        vector,
+        elements,
+    })
+}
+
+/// Returns whether the next element is a number or range, i.e. consists
+/// only of digits, `.`, `-`, and `\` (and not a label like `1abc`),
+/// without moving the parser.
+fn peek_is_range<B: ByteSource>(parser: &mut ByteParser<B>) -> bool {
+    let start_pos = parser.position();
+    let token = parser.parse_unquoted_label(NEXUS_LABEL_DELIMITERS);
+    parser.set_position(start_pos);
+
+    // (Unclosed command is reported when parsing the range)
+    token.map_or(true, |token| {
+        token
+            .bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'\\'))
+    })
+}
+
+/// Parses a number or range `<start>[-<end>[\<step>]]` of a set definition,
+/// with optional whitespace around `-` and `\`.
+fn parse_range<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    set_name: &str,
+) -> Result<SetElement, ParsingError> {
+    let start = parse_number(parser, set_name)?;
+    parser.skip_comment_and_whitespace()?;
+    if !parser.consume_if(b'-') {
+        return Ok(SetElement::Range {
+            start,
+            end: start,
+            step: 1,
+        });
+    }
+
+    parser.skip_comment_and_whitespace()?;
+    let end = parse_number(parser, set_name)?;
+    parser.skip_comment_and_whitespace()?;
+    let step = if parser.consume_if(b'\\') {
+        parser.skip_comment_and_whitespace()?;
+        parse_number(parser, set_name)?.filter(|&step| step > 0)
+    } else {
+        Some(1)
+    };
+
+    let Some(step) = step else {
+        return Err(ParsingError::invalid_sets_block(
+            parser,
+            format!("Invalid step of range in set '{set_name}'."),
+        ));
+    };
+    Ok(SetElement::Range { start, end, step })
+}
+
+/// Parses a (1-based) number of a set definition, or `.` for the last
+/// element returned as `None`.
+fn parse_number<B: ByteSource>(
+    parser: &mut ByteParser<B>,
+    set_name: &str,
+) -> Result<Option<usize>, ParsingError> {
+    let token = parser.parse_unquoted_label(NUMBER_DELIMITERS)?;
+    if token == "." {
+        return Ok(None);
+    }
+    match token.parse::<usize>() {
+        Ok(number) if number > 0 => Ok(Some(number)),
+        _ => Err(ParsingError::invalid_sets_block(
+            parser,
+            format!("Invalid number '{token}' in set '{set_name}'."),
+        )),
+    }
+}
+
+// =#========================================================================#=
+// RESOLVING
+// =#========================================================================$=
+/// Resolves set definitions against the taxa and tree names into [NexusSets].
+///
+/// # Arguments
+/// * `definitions` - Set definitions in order of the file
+/// * `taxa` - Taxon labels, ordered by their [LabelIndex]
+/// * `tree_names` - Names of all trees, in order of the TREES block
+///
+/// # Errors
+/// Returns a message if an element of a set refers to an unknown taxon,
+/// tree, or set, or a number is out of range.
+pub(crate) fn resolve_sets(
+    definitions: &[SetDefinition],
+    taxa: &[String],
+    tree_names: &[String],
+) -> Result<NexusSets, String> {
+    let mut sets = NexusSets::default();
+    for definition in definitions {
+        let (names, earlier_sets) = match definition.kind {
+            SetKind::Taxa => (taxa, &sets.taxsets),
+            SetKind::Trees => (tree_names, &sets.treesets),
+        };
+        let set = resolve_set(definition, names, earlier_sets)?;
+        let entry = (definition.name.clone(), set);
+        match definition.kind {
+            SetKind::Taxa => sets.taxsets.push(entry),
+            SetKind::Trees => sets.treesets.push(entry),
+        }
+    }
+    Ok(sets)
+}
+
+/// Resolves a single set definition into sorted (0-based) indices of
+/// `names`, where elements might refer to `earlier_sets`.
+fn resolve_set(
+    definition: &SetDefinition,
+    names: &[String],
+    earlier_sets: &[(String, Vec<usize>)],
+) -> Result<Vec<usize>, String> {
+    let set_name = &definition.name;
+    let kind = match definition.kind {
+        SetKind::Taxa => "taxon",
+        SetKind::Trees => "tree",
+    };
+    let n = names.len();
+    let mut set = Vec::new();
+
+    // (Vector of 0s and 1s, possibly without whitespace, e.g. `0110`)
+    if definition.vector {
+        let mut bits = Vec::new();
+        for element in &definition.elements {
+            if let SetElement::Name(token) = element {
+                bits.extend(token.bytes());
+            }
+        }
+        if bits.len() != n || bits.iter().any(|bit| !matches!(bit, b'0' | b'1')) {
+            return Err(format!("Invalid vector of set '{set_name}'."));
+        }
+        set.extend((0..n).filter(|&index| bits[index] == b'1'));
+        return Ok(set);
+    }
+
+    for element in &definition.elements {
+        match element {
+            SetElement::Name(name) => {
+                if let Some(index) = names.iter().position(|label| label == name) {
+                    set.push(index);
+                } else if let Some(other) = find_set(earlier_sets, name) {
+                    set.extend_from_slice(other);
+                } else {
+                    return Err(format!("Unknown {kind} '{name}' in set '{set_name}'."));
+                }
+            }
+            SetElement::Range { start, end, step } => {
+                let start = start.unwrap_or(n);
+                let end = end.unwrap_or(n);
+                if start == 0 || start > end || end > n {
+                    return Err(format!(
+                        "Range {start}-{end} of set '{set_name}' is empty or not within 1-{n}."
+                    ));
+                }
+                set.extend((start..=end).step_by(*step).map(|number| number - 1));
+            }
+        }
+    }
+
+    set.sort_unstable();
+    set.dedup();
+    Ok(set)
+}
@@ -0,0 +1,137 @@
+//! Sidecar files storing the byte offsets of all `TREE` commands of a Nexus
+//! file, see [with_tree_index()](crate::nexus::NexusParserBuilder::with_tree_index).
+//!
+//! Reopening a file with an up-to-date index skips counting the trees,
+//! and the offsets give random access to each tree via
+//! [tree_at()](crate::nexus::NexusParser::tree_at).
+//!
+//! File format (all integers as little-endian `u64`):
+//! * magic bytes `NXWKIDX1`,
+//! * length, modification time (seconds since Unix epoch), and a hash of
+//!   the first and last kilobyte of the Nexus file, to detect outdated indices,
+//! * number of trees `n`,
+//! * `n` byte offsets of the `TREE` commands.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Magic bytes at the start of a tree index file
+const MAGIC: &[u8; 8] = b"NXWKIDX1";
+
+/// Number of bytes at the start and end of the Nexus file that are hashed
+const HASHED_LEN: u64 = 1024;
+
+// =#========================================================================#=
+// TREE INDEX FILE
+// =#========================================================================$=
+/// Sidecar file of the tree offsets of a Nexus file.
+pub(crate) struct TreeIndexFile {
+    /// Path of the index file
+    path: PathBuf,
+    /// Length of the Nexus file (in bytes) when opened
+    source_len: u64,
+    /// Modification time of the Nexus file (in seconds) when opened
+    source_modified: u64,
+    /// Hash of the first and last [HASHED_LEN] bytes of the Nexus file,
+    /// to detect changes within the same second that keep the length
+    source_hash: u64,
+}
+
+impl TreeIndexFile {
+    /// Creates a [TreeIndexFile] at `path` for the Nexus file at `source_path`.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the Nexus file or its metadata cannot be read.
+    pub(crate) fn new(path: &Path, source_path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(source_path)?;
+        let source_modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs());
+        Ok(Self {
+            path: path.to_path_buf(),
+            source_len: metadata.len(),
+            source_modified,
+            source_hash: hash_file_ends(source_path)?,
+        })
+    }
+
+    /// Loads the tree offsets from the index file.
+    ///
+    /// # Arguments
+    /// * `first_tree_pos` - Byte offset of the first `TREE` command, as
+    ///   sanity check of the offsets
+    ///
+    /// # Returns
+    /// The offsets, or `None` if the index file does not exist, is corrupt,
+    /// or does not match the Nexus file (e.g. since it was modified).
+    pub(crate) fn load(&self, first_tree_pos: usize) -> Option<Vec<usize>> {
+        let mut reader = BufReader::new(File::open(&self.path).ok()?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).ok()?;
+        if &magic != MAGIC
+            || read_u64(&mut reader)? != self.source_len
+            || read_u64(&mut reader)? != self.source_modified
+            || read_u64(&mut reader)? != self.source_hash
+        {
+            return None;
+        }
+
+        let num_trees = usize::try_from(read_u64(&mut reader)?).ok()?;
+        let mut offsets = Vec::with_capacity(num_trees.min(self.source_len as usize));
+        for _ in 0..num_trees {
+            offsets.push(usize::try_from(read_u64(&mut reader)?).ok()?);
+        }
+
+        let is_consistent = offsets.first().is_none_or(|&pos| pos == first_tree_pos)
+            && offsets.is_sorted()
+            && offsets
+                .last()
+                .is_none_or(|&pos| (pos as u64) < self.source_len);
+        is_consistent.then_some(offsets)
+    }
+
+    /// Saves the tree offsets to the index file, replacing any existing one.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the index file cannot be written.
+    pub(crate) fn save(&self, offsets: &[usize]) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&self.source_len.to_le_bytes())?;
+        writer.write_all(&self.source_modified.to_le_bytes())?;
+        writer.write_all(&self.source_hash.to_le_bytes())?;
+        writer.write_all(&(offsets.len() as u64).to_le_bytes())?;
+        for &offset in offsets {
+            writer.write_all(&(offset as u64).to_le_bytes())?;
+        }
+        writer.flush()
+    }
+}
+
+/// Hashes the first and last [HASHED_LEN] bytes of a file (FNV-1a, which,
+/// unlike the std hashers, is stable across Rust versions).
+fn hash_file_ends(path: &Path) -> std::io::Result<u64> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut bytes = Vec::new();
+    (&mut file).take(HASHED_LEN).read_to_end(&mut bytes)?;
+    if len > HASHED_LEN {
+        file.seek(SeekFrom::Start((len - HASHED_LEN).max(HASHED_LEN)))?;
+        file.take(HASHED_LEN).read_to_end(&mut bytes)?;
+    }
+
+    Ok(bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    }))
+}
+
+/// Reads a little-endian `u64`, or returns `None` if not possible.
+fn read_u64<R: Read>(reader: &mut R) -> Option<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes).ok()?;
+    Some(u64::from_le_bytes(bytes))
+}
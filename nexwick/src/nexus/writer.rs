@@ -1,9 +1,12 @@
 //! NEXUS format file writer (for tree model [CompactTree] +[LeafLabelMap]).
 
-use crate::model::{CompactTree, LeafLabelMap};
-use crate::newick::writer::{NewickStyle, estimate_newick_len, to_newick_with_capacity};
+use crate::model::{CompactNetwork, CompactTree, DistanceMatrix, LeafLabelMap, TreeMetadata};
+use crate::newick::writer::{
+    NewickStyle, estimate_newick_len, to_enewick, to_newick_with_capacity,
+};
 use crate::nexus::defs::{
-    BLOCK_BEGIN, BLOCK_END, DIMENSIONS, NEXUS_HEADER, NTAX, TAXA, TAXLABELS, TRANSLATE, TREE, TREES,
+    BLOCK_BEGIN, BLOCK_END, DIMENSIONS, DISTANCES, FORMAT, MATRIX, NEXUS_HEADER, NTAX, TAXA,
+    TAXLABELS, TRANSLATE, TREE, TREES,
 };
 use crate::parser::utils::escape_label;
 use std::fs::File;
@@ -24,6 +27,8 @@ use std::io::{BufWriter, Write};
 /// - `#NEXUS` header
 /// - `TAXA` block with dimensions and tax labels
 /// - `TREES` block with TRANSLATE command and tree definitions
+/// - or instead `DISTANCES` block with a distance matrix
+///   (see [write_nexus_distances()](Self::write_nexus_distances))
 ///
 /// # Example
 /// ```ignore
@@ -69,9 +74,52 @@ impl NexusWriter {
         leaf_label_map: &LeafLabelMap,
     ) -> io::Result<()> {
         self.header()?
-            .taxa_block(leaf_label_map)?
+            .taxa_block(leaf_label_map.labels())?
             .trees_block(trees, leaf_label_map)?;
-        Ok(())
+        self.bw.flush()
+    }
+
+    /// Writes a complete NEXUS file with phylogenetic networks in extended
+    /// Newick (eNewick) format and their label mapping, using integer keys
+    /// (1-indexed) in TRANSLATE command.
+    ///
+    /// # Arguments
+    /// * `networks` - Vector of networks to write
+    /// * `leaf_label_map` - Shared leaf label mapping for all networks
+    ///
+    /// # Errors
+    /// Returns an I/O error if writing fails
+    pub fn write_nexus_networks(
+        &mut self,
+        networks: &[CompactNetwork],
+        leaf_label_map: &LeafLabelMap,
+    ) -> io::Result<()> {
+        self.header()?
+            .taxa_block(leaf_label_map.labels())?
+            .trees_block_begin()?
+            .translate_cmd(leaf_label_map)?
+            .networks_cmd_list(networks)?
+            .block_end()?;
+        self.bw.flush()
+    }
+
+    /// Writes a complete NEXUS file with a TAXA block and a DISTANCES block
+    /// of the given distance matrix.
+    ///
+    /// Symmetric matrices are written as lower triangle (with diagonal),
+    /// others completely (`TRIANGLE=BOTH`); missing distances (`NaN`)
+    /// are written as `?`.
+    ///
+    /// # Arguments
+    /// * `matrix` - Distance matrix to write, whose taxa define the TAXA block
+    ///
+    /// # Errors
+    /// Returns an I/O error if writing fails
+    pub fn write_nexus_distances(&mut self, matrix: &DistanceMatrix) -> io::Result<()> {
+        self.header()?
+            .taxa_block(matrix.taxa())?
+            .distances_block(matrix)?;
+        self.bw.flush()
     }
 }
 
@@ -86,12 +134,12 @@ impl NexusWriter {
     }
 
     /// Writes the TAXA block with dimensions and taxon labels, returning itself for chaining.
-    fn taxa_block(&mut self, map: &LeafLabelMap) -> io::Result<&mut Self> {
+    fn taxa_block(&mut self, labels: &[String]) -> io::Result<&mut Self> {
         // "Begin TAXA;"
         self.write_all(BLOCK_BEGIN)?
             .space()?
             .write_all(TAXA)?
-            .semicolon_ln()?;
+            .newline()?;
 
         // "\tDimensions ntaxa=n;"
         self.tab()?
@@ -99,16 +147,16 @@ impl NexusWriter {
             .space()?
             .write_all(NTAX)?
             .equals()?
-            .write_all(map.num_labels().to_string().as_bytes())?
+            .write_all(labels.len().to_string().as_bytes())?
             .semicolon_ln()?;
 
         // "\tTaxlabels [label ...];"
         self.tab()?.write_all(TAXLABELS)?;
-        for label in map.labels() {
+        for label in labels {
             let escaped_label = escape_label(label);
             self.space()?.write_all(escaped_label.as_bytes())?;
         }
-        self.newline()?;
+        self.semicolon_ln()?;
 
         // "End;"
         self.write_all(BLOCK_END)?.newline()?;
@@ -122,14 +170,73 @@ impl NexusWriter {
         trees: &[CompactTree],
         leaf_label_map: &LeafLabelMap,
     ) -> io::Result<&mut Self> {
-        // - "Begin TREES;"
+        self.trees_block_begin()?
+            .translate_cmd(leaf_label_map)?
+            .trees_cmd_list(trees)?
+            .block_end()?;
+
+        Ok(self)
+    }
+
+    /// Writes the DISTANCES block with FORMAT and MATRIX command, returning itself for chaining.
+    fn distances_block(&mut self, matrix: &DistanceMatrix) -> io::Result<&mut Self> {
+        // "Begin DISTANCES;"
         self.write_all(BLOCK_BEGIN)?
             .space()?
-            .write_all(TREES)?
+            .write_all(DISTANCES)?
+            .newline()?;
+
+        // "\tFormat triangle=<lower|both> diagonal labels missing=?;"
+        let symmetric = matrix.is_symmetric();
+        let triangle: &[u8] = if symmetric { b"lower" } else { b"both" };
+        self.tab()?
+            .write_all(FORMAT)?
+            .space()?
+            .write_all(b"triangle")?
+            .equals()?
+            .write_all(triangle)?
+            .write_all(b" diagonal labels missing")?
+            .equals()?
+            .write_all(b"?")?
             .semicolon_ln()?;
 
-        self.translate_cmd(leaf_label_map)?.trees_cmd_list(trees)?;
+        // "\tMatrix\n\t\t<label> <distance> ...\n\t;"
+        self.tab()?.write_all(MATRIX)?.newline()?;
+        let num_taxa = matrix.num_taxa();
+        for (i, label) in matrix.taxa().iter().enumerate() {
+            let escaped_label = escape_label(label);
+            self.tab()?.tab()?.write_all(escaped_label.as_bytes())?;
+
+            let num_columns = if symmetric { i + 1 } else { num_taxa };
+            for j in 0..num_columns {
+                let distance = matrix.get(i, j).unwrap_or(f64::NAN);
+                let value = if distance.is_nan() {
+                    String::from("?")
+                } else {
+                    distance.to_string()
+                };
+                self.space()?.write_all(value.as_bytes())?;
+            }
+            self.newline()?;
+        }
+        self.tab()?.semicolon_ln()?;
+
+        // "End;"
+        self.block_end()
+    }
 
+    /// Writes the TREES block header ("Begin TREES;"), returning itself for chaining.
+    fn trees_block_begin(&mut self) -> io::Result<&mut Self> {
+        self.write_all(BLOCK_BEGIN)?
+            .space()?
+            .write_all(TREES)?
+            .newline()?;
+        Ok(self)
+    }
+
+    /// Writes the block end ("End;"), returning itself for chaining.
+    fn block_end(&mut self) -> io::Result<&mut Self> {
+        self.write_all(BLOCK_END)?.newline()?;
         Ok(self)
     }
 
@@ -161,9 +268,6 @@ impl NexusWriter {
         }
         self.semicolon()?.newline()?;
 
-        // - "End;"
-        self.write_all(BLOCK_END)?.newline()?;
-
         Ok(self)
     }
 
@@ -179,22 +283,57 @@ impl NexusWriter {
 
         // "TREE <name> = <Newick;>
         for (i, tree) in trees.iter().enumerate() {
-            let name = tree
-                .name()
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| format!("tree_{}", i));
+            let newick = to_newick_with_capacity(
+                &NewickStyle::OneIndexed,
+                tree,
+                None,
+                None,
+                estimated_length,
+            );
+            self.tree_cmd(tree.name(), i, Some(tree.metadata()), &newick)?;
+        }
 
-            self.write_all(TREE)?
-                .space()?
-                .write_all(name.as_bytes())?
-                .space()?
-                .equals()?
-                .space()?
-                .write_all(
-                    to_newick_with_capacity(&NewickStyle::OneIndexed, tree, None, estimated_length)
-                        .as_bytes(),
-                )?;
+        Ok(self)
+    }
+
+    /// Writes the list of TREE commands with networks in eNewick format, returning itself for chaining.
+    fn networks_cmd_list(&mut self, networks: &[CompactNetwork]) -> io::Result<&mut Self> {
+        // "TREE <name> = <eNewick;>
+        for (i, network) in networks.iter().enumerate() {
+            let enewick = to_enewick(&NewickStyle::OneIndexed, network, None);
+            self.tree_cmd(network.name(), i, None, &enewick)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Writes a single TREE command ("\tTREE <name> = [metadata] <newick>\n"),
+    /// named `tree_<i>` if no name given, with tree metadata as comments
+    /// (e.g. `[&R] [&W 0.25]`), returning itself for chaining.
+    fn tree_cmd(
+        &mut self,
+        name: Option<&String>,
+        i: usize,
+        metadata: Option<&TreeMetadata>,
+        newick: &str,
+    ) -> io::Result<&mut Self> {
+        let name = name
+            .map(|s| escape_label(s))
+            .unwrap_or_else(|| format!("tree_{}", i));
+
+        self.tab()?
+            .write_all(TREE)?
+            .space()?
+            .write_all(name.as_bytes())?
+            .space()?
+            .equals()?
+            .space()?;
+        // (The MCMC state is part of the name, so is not written as comment)
+        let comments = metadata.map(|m| m.to_string()).unwrap_or_default();
+        if !comments.is_empty() {
+            self.write_all(comments.as_bytes())?.space()?;
         }
+        self.write_all(newick.as_bytes())?.newline()?;
 
         Ok(self)
     }
@@ -5,10 +5,12 @@
 //! parser. Used as the foundation for both NEXUS and Newick parsers.
 
 use crate::parser::buffered_byte_source::BufferedByteSource;
-use crate::parser::byte_parser::ConsumeMode::Inclusive;
 use crate::parser::byte_source::ByteSource;
+use crate::parser::decompressing_byte_source::DecompressingByteSource;
 use crate::parser::in_memory_byte_source::InMemoryByteSource;
 use crate::parser::parsing_error::ParsingError;
+use crate::parser::reader_byte_source::ReaderByteSource;
+use std::io::Read;
 use std::path::Path;
 
 // =#========================================================================#=
@@ -51,9 +53,6 @@ pub enum ConsumeMode {
     Exclusive,
 }
 
-#[cfg(test)]
-mod tests {}
-
 // =#========================================================================#=
 // BYTE PARSER
 // =#========================================================================$=
@@ -111,7 +110,9 @@ impl ByteParser<InMemoryByteSource> {
         Self::new(InMemoryByteSource::from_vec(input.as_bytes().to_vec()))
     }
 
-    /// Creates a new [ByteParser] from a file fully loaded into memory.
+    /// Creates a new [ByteParser] from a file fully loaded into memory,
+    /// decompressed if it is compressed (gzip, zstd, or bzip2 with the
+    /// respective cargo feature enabled).
     ///
     /// # Arguments
     /// * `path` - Path to the file (accepting `&str`, `String`, `Path`, or `PathBuf`)
@@ -119,11 +120,27 @@ impl ByteParser<InMemoryByteSource> {
         let source = InMemoryByteSource::from_file(path)?;
         Ok(Self::new(source))
     }
+
+    /// Creates a new [ByteParser] from a reader fully read into memory.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader to read from, e.g. stdin
+    ///
+    /// # Errors
+    /// Returns an error if reading fails.
+    pub fn from_reader_in_memory<R: Read>(reader: R) -> std::io::Result<Self> {
+        let source = InMemoryByteSource::from_reader(reader)?;
+        Ok(Self::new(source))
+    }
 }
 
 impl ByteParser<BufferedByteSource> {
     /// Creates a new [ByteParser] from a file read with a buffer.
     ///
+    /// The file is read as is; use
+    /// [from_file_decompressing()](ByteParser::from_file_decompressing)
+    /// for compressed files.
+    ///
     /// # Arguments
     /// * `path` - Path to the file (accepting `&str`, `String`, `Path`, or `PathBuf`)
     pub fn from_file_buffered<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
@@ -132,6 +149,38 @@ impl ByteParser<BufferedByteSource> {
     }
 }
 
+impl<R: Read> ByteParser<ReaderByteSource<R>> {
+    /// Creates a new [ByteParser] streaming from a reader, e.g. stdin,
+    /// a pipe, a socket, or an archive entry.
+    ///
+    /// The reader cannot be rewound, so only short backtracking (within the
+    /// last few MB) is possible; after [set_position()](ByteParser::set_position)
+    /// further back, the parser behaves as at the end of the input.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader to read from (unbuffered is fine)
+    pub fn from_reader(reader: R) -> Self {
+        Self::new(ReaderByteSource::new(reader))
+    }
+}
+
+impl ByteParser<DecompressingByteSource> {
+    /// Creates a new [ByteParser] from a file streamed through a decompressor,
+    /// detecting gzip, zstd, or bzip2 compression from its magic bytes
+    /// (uncompressed files are streamed as is).
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file (accepting `&str`, `String`, `Path`, or `PathBuf`)
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened, or if it is compressed
+    /// in a format whose cargo feature is not enabled.
+    pub fn from_file_decompressing<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let source = DecompressingByteSource::from_file(path)?;
+        Ok(Self::new(source))
+    }
+}
+
 impl<S: ByteSource> ByteParser<S> {
     /// Creates a new `ByteParser` from a byte source.
     ///
@@ -176,7 +225,8 @@ impl<S: ByteSource> ByteParser<S> {
 
     /// Skips (consumes) a NEXUS-style comment if present.
     ///
-    /// NEXUS comments are enclosed in square brackets `[...]`.
+    /// NEXUS comments are enclosed in square brackets `[...]` and may be
+    /// nested, e.g. `[outer [inner] comment]`.
     ///
     /// # Returns
     /// * `Ok(true)` - A comment was found and consumed
@@ -187,8 +237,14 @@ impl<S: ByteSource> ByteParser<S> {
     /// Returns an error if a comment starts with `[` but doesn't have a closing `]`.
     pub fn skip_comment(&mut self) -> Result<bool, ParsingError> {
         if self.consume_if(b'[') {
-            if !self.consume_until(b']', Inclusive) {
-                return Err(ParsingError::unclosed_comment(self));
+            let mut depth = 1;
+            while depth > 0 {
+                match self.next_byte() {
+                    Some(b'[') => depth += 1,
+                    Some(b']') => depth -= 1,
+                    Some(_) => {}
+                    None => return Err(ParsingError::unclosed_comment(self)),
+                }
             }
             return Ok(true);
         }
@@ -269,6 +325,29 @@ impl<S: ByteSource> ByteParser<S> {
         true
     }
 
+    /// Checks if the following bytes match the given keyword (case-insensitive)
+    /// as a whole word, i.e. followed by one of the delimiters or the end of input.
+    ///
+    /// This is a peek operation - the parser position is not changed.
+    ///
+    /// # Arguments
+    /// * `keyword` - The keyword to match against, e.g. `b"tree"`
+    /// * `delimiters` - Bytes that may follow the keyword
+    ///
+    /// # Returns
+    /// `true` if the next bytes match `keyword` (case-insensitive) and are followed
+    /// by a delimiter or the end of input, `false` otherwise
+    pub fn peek_is_keyword(&mut self, keyword: &[u8], delimiters: &[u8]) -> bool {
+        if !self.peek_is_sequence(keyword) {
+            return false;
+        }
+
+        let context = self.source.peek_slice(keyword.len() + 1);
+        context
+            .get(keyword.len())
+            .is_none_or(|byte| delimiters.contains(byte))
+    }
+
     /// Consumes the current byte if it matches the target byte (case-insensitive).
     ///
     /// # Arguments
@@ -318,6 +397,27 @@ impl<S: ByteSource> ByteParser<S> {
         true
     }
 
+    /// Consumes the next bytes if they match the given keyword (case-insensitive)
+    /// as a whole word, see [peek_is_keyword()](Self::peek_is_keyword).
+    ///
+    /// # Arguments
+    /// * `keyword` - The keyword to match and consume
+    /// * `delimiters` - Bytes that may follow the keyword (not consumed)
+    ///
+    /// # Returns
+    /// `true` if the keyword was matched and consumed, `false` otherwise
+    pub fn consume_if_keyword(&mut self, keyword: &[u8], delimiters: &[u8]) -> bool {
+        if !self.peek_is_keyword(keyword, delimiters) {
+            return false;
+        }
+
+        for _ in 0..keyword.len() {
+            self.next_byte();
+        }
+
+        true
+    }
+
     /// Consumes bytes until the target byte is found.
     ///
     /// # Arguments
@@ -361,6 +461,43 @@ impl<S: ByteSource> ByteParser<S> {
         None // reached EOF without finding target
     }
 
+    /// Consumes bytes until and including the target byte, ignoring it
+    /// within quoted labels (`'...'`, with `''` as escaped quote) and
+    /// comments (`[...]`), e.g. to skip a malformed tree `('a;b',C);`.
+    ///
+    /// # Arguments
+    /// * `target` - The byte to search for
+    ///
+    /// # Returns
+    /// `true` if the target was found, `false` if EOF was reached first
+    /// (including within an unclosed quoted label or comment)
+    pub fn consume_until_unquoted(&mut self, target: u8) -> bool {
+        while let Some(b) = self.peek() {
+            if b == target {
+                self.next_byte();
+                return true;
+            }
+            match b {
+                // (An escaped quote just closes and reopens the quoted label)
+                b'\'' => {
+                    self.next_byte();
+                    if !self.consume_until(b'\'', ConsumeMode::Inclusive) {
+                        return false;
+                    }
+                }
+                b'[' => {
+                    if self.skip_comment().is_err() {
+                        return false;
+                    }
+                }
+                _ => {
+                    self.next_byte();
+                }
+            }
+        }
+        false // reached EOF without finding target
+    }
+
     /// Consumes bytes until the next bytes match the given word/token (case-insensitive).
     ///
     /// # Arguments
@@ -534,3 +671,6 @@ impl<S: ByteSource> ByteParser<S> {
         Err(ParsingError::unexpected_eof(self))
     }
 }
+
+#[cfg(test)]
+mod tests {}
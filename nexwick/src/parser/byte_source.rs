@@ -11,6 +11,8 @@
 /// This trait abstracts over different ways of accessing byte data:
 /// - In-memory byte slices (`&[u8]`)
 /// - Buffered reading from files (`BufReader<File>`)
+/// - Reading from any reader without seeking (`Read`), e.g. stdin or a
+///   streaming decompressor of compressed files
 ///
 /// By using this trait, the same parser logic can work with both small files
 /// loaded entirely into memory and large files streamed from disk.
@@ -0,0 +1,160 @@
+//! Streaming decompression implementation of byte source for parser.
+//!
+//! This module provides [DecompressingByteSource], which reads compressed
+//! files (e.g. `.trees.gz` or `.trees.zst`) through a streaming decompressor
+//! as [ReaderByteSource],
+//! and [Compression] to detect the format of a file from its magic bytes.
+//! Each format is only supported if the respective cargo feature is enabled:
+//! * `gzip` for gzip (including multi-member files, e.g. of `bgzip`),
+//! * `zstd` for Zstandard,
+//! * `bzip2` for bzip2,
+//!
+//! or `compression` for all of them.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use crate::parser::reader_byte_source::ReaderByteSource;
+
+// =#========================================================================#=
+// COMPRESSION
+// =#========================================================================$=
+/// Compression format of a file, detected from its magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    /// gzip, starting with `1f 8b`
+    Gzip,
+    /// Zstandard, starting with `28 b5 2f fd`
+    Zstd,
+    /// bzip2, starting with `BZh`
+    Bzip2,
+}
+
+impl Compression {
+    /// Detects the compression format of the file at `path`.
+    ///
+    /// # Returns
+    /// The format, or `None` if the file is not compressed (in a known format)
+    ///
+    /// # Errors
+    /// Returns an I/O error if the file cannot be read.
+    pub fn detect<P: AsRef<Path>>(path: P) -> std::io::Result<Option<Compression>> {
+        let mut magic = Vec::with_capacity(4);
+        File::open(path)?.take(4).read_to_end(&mut magic)?;
+        Ok(Self::from_magic_bytes(&magic))
+    }
+
+    /// Returns the compression format starting with the given bytes, if any.
+    fn from_magic_bytes(bytes: &[u8]) -> Option<Compression> {
+        match bytes {
+            [0x1f, 0x8b, ..] => Some(Compression::Gzip),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Compression::Zstd),
+            [b'B', b'Z', b'h', ..] => Some(Compression::Bzip2),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the cargo feature needed to decompress this format.
+    fn feature(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Bzip2 => "bzip2",
+        }
+    }
+
+    /// Wraps the (compressed) `file` in a streaming decompressor.
+    ///
+    /// # Errors
+    /// Returns an error of kind [ErrorKind::Unsupported] if the cargo feature
+    /// of the format is not enabled.
+    fn decoder(&self, file: File) -> std::io::Result<Box<dyn Read + Send>> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(file))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(Box::new(zstd::stream::read::Decoder::new(file)?)),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => Ok(Box::new(bzip2::read::MultiBzDecoder::new(file))),
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = file;
+                Err(std::io::Error::new(
+                    ErrorKind::Unsupported,
+                    format!(
+                        "File is {0}-compressed, but nexwick was built without feature `{0}`",
+                        self.feature()
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+/// Opens the file at `path` for reading, decompressing it on the fly if it is
+/// compressed (see [Compression]).
+///
+/// # Errors
+/// Returns an I/O error if the file cannot be opened, or if it is compressed
+/// in a format whose cargo feature is not enabled.
+pub(crate) fn open_decompressed<P: AsRef<Path>>(path: P) -> std::io::Result<Box<dyn Read + Send>> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    match Compression::detect(path)? {
+        Some(compression) => compression.decoder(file),
+        None => Ok(Box::new(file)),
+    }
+}
+
+// =#========================================================================#=
+// DECOMPRESSING BYTE SOURCE
+// =#========================================================================$=
+/// A byte source streaming a compressed file through a decompressor.
+///
+/// Since decompressors cannot seek, moving back beyond the buffer of the
+/// [ReaderByteSource] (e.g. when a lazy parser is reset) re-opens the file
+/// and decompresses it again from the start.
+pub type DecompressingByteSource = ReaderByteSource<Box<dyn Read + Send>>;
+
+impl ReaderByteSource<Box<dyn Read + Send>> {
+    /// Creates a new decompressing byte source from a file path, detecting
+    /// the compression format from its magic bytes.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file (accepting `&str`, `String`, `Path`, or `PathBuf`)
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened, or if it is compressed
+    /// in a format whose cargo feature is not enabled.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<DecompressingByteSource> {
+        let path: PathBuf = path.as_ref().to_path_buf();
+        Self::reopenable(Box::new(move || open_decompressed(&path)))
+    }
+}
+
+// =#========================================================================#=
+// TESTS - DECOMPRESSING BYTE SOURCE
+// =#========================================================================$=
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_magic_bytes() {
+        assert_eq!(
+            Compression::from_magic_bytes(&[0x1f, 0x8b, 0x08, 0x00]),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_magic_bytes(&[0x28, 0xb5, 0x2f, 0xfd]),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(
+            Compression::from_magic_bytes(b"BZh9"),
+            Some(Compression::Bzip2)
+        );
+        assert_eq!(Compression::from_magic_bytes(b"#NEX"), None);
+        assert_eq!(Compression::from_magic_bytes(&[0x1f]), None);
+    }
+}
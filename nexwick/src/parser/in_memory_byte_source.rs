@@ -1,7 +1,7 @@
 //! In-memory implementation of byte source for parser.
 
 use crate::parser::byte_source::ByteSource;
-use std::fs::File;
+use crate::parser::decompressing_byte_source::open_decompressed;
 use std::io::Read;
 use std::path::Path;
 
@@ -31,11 +31,30 @@ impl InMemoryByteSource {
         }
     }
 
+    /// Creates a new in-memory byte source from a file, decompressing it
+    /// if compressed (see [Compression](crate::parser::decompressing_byte_source::Compression)).
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file (accepting `&str`, `String`, `Path`, or `PathBuf`)
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, or if it is compressed
+    /// in a format whose cargo feature is not enabled.
     pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<InMemoryByteSource> {
-        // Read entire file into memory
+        // Read entire (decompressed) file into memory
+        Self::from_reader(open_decompressed(path)?)
+    }
+
+    /// Creates a new in-memory byte source by reading `reader` to its end.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader to read from, e.g. stdin
+    ///
+    /// # Errors
+    /// Returns an error if reading fails.
+    pub fn from_reader<R: Read>(mut reader: R) -> std::io::Result<InMemoryByteSource> {
         let mut contents = Vec::new();
-        let mut file = File::open(path)?;
-        file.read_to_end(&mut contents)?;
+        reader.read_to_end(&mut contents)?;
         Ok(Self {
             input: contents,
             pos: 0,
@@ -2,8 +2,10 @@
 pub(crate) mod buffered_byte_source;
 pub mod byte_parser;
 pub(crate) mod byte_source;
+pub(crate) mod decompressing_byte_source;
 pub(crate) mod in_memory_byte_source;
 pub mod parsing_error;
+pub(crate) mod reader_byte_source;
 pub mod utils;
 
 pub use byte_parser::ByteParser;
@@ -36,7 +36,16 @@ pub enum ParsingErrorType {
     InvalidTreesBlock(String),
 
     /// TRANSLATE command is malformed or inconsistent with TAXA block.
-    InvalidTranslateCommand,
+    InvalidTranslateCommand(String),
+
+    /// Error in DATA/CHARACTERS block structure or content.
+    InvalidCharactersBlock(String),
+
+    /// Error in DISTANCES block structure or content.
+    InvalidDistancesBlock(String),
+
+    /// Error in SETS block structure or content.
+    InvalidSetsBlock(String),
 
     /// Comment opened with `[` but never closed with `]`.
     UnclosedComment,
@@ -44,6 +53,11 @@ pub enum ParsingErrorType {
     /// Malformed Newick tree string.
     InvalidNewickString(String),
 
+    /// Branch length rejected by the parser's
+    /// [BranchLengthPolicy](crate::newick::BranchLengthPolicy),
+    /// e.g. negative, NaN, or infinite.
+    InvalidBranchLength(String),
+
     /// General formatting error in input.
     InvalidFormatting,
 
@@ -57,17 +71,24 @@ pub enum ParsingErrorType {
 
     /// Tree structure is invalid (e.g., wrong number of children).
     InvalidTreeStructure,
+
+    /// Parser settings that cannot be combined, e.g.
+    /// [Burnin::Percentage](crate::nexus::Burnin::Percentage) in follow mode.
+    InvalidConfiguration(String),
 }
 
 // =#========================================================================#=
 // PARSING ERROR
 // =#========================================================================$=
-/// Parsing error with contextual information (position and surrounding bytes).
+/// Parsing error with contextual information (position and surrounding bytes,
+/// as well as index and name of the tree if the error occurred within one).
 #[derive(Debug)]
 pub struct ParsingError {
     kind: ParsingErrorType,
     position: usize,
     context: String,
+    tree_index: Option<usize>,
+    tree_name: Option<String>,
 }
 
 impl ParsingError {
@@ -77,6 +98,8 @@ impl ParsingError {
             kind,
             position: parser.position(),
             context: parser.get_context_as_string(DEFAULT_CONTEXT_LENGTH),
+            tree_index: None,
+            tree_name: None,
         }
     }
 
@@ -86,9 +109,17 @@ impl ParsingError {
             kind,
             position: 0,
             context: String::new(),
+            tree_index: None,
+            tree_name: None,
         }
     }
 
+    /// Convenience constructor for [ParsingErrorType::InvalidConfiguration]
+    /// (without parser context)
+    pub fn invalid_configuration(msg: String) -> Self {
+        Self::without_context(ParsingErrorType::InvalidConfiguration(msg))
+    }
+
     /// Convenience constructor for [ParsingErrorType::UnexpectedEOF]
     pub fn unexpected_eof<S: ByteSource>(parser: &mut ByteParser<S>) -> Self {
         Self::from_parser(ParsingErrorType::UnexpectedEOF, parser)
@@ -115,8 +146,29 @@ impl ParsingError {
     }
 
     /// Convenience constructor for [ParsingErrorType::InvalidTranslateCommand]
-    pub fn invalid_translate_command<S: ByteSource>(parser: &mut ByteParser<S>) -> Self {
-        Self::from_parser(ParsingErrorType::InvalidTranslateCommand, parser)
+    pub fn invalid_translate_command<S: ByteSource>(
+        parser: &mut ByteParser<S>,
+        msg: String,
+    ) -> Self {
+        Self::from_parser(ParsingErrorType::InvalidTranslateCommand(msg), parser)
+    }
+
+    /// Convenience constructor for [ParsingErrorType::InvalidCharactersBlock]
+    pub fn invalid_characters_block<S: ByteSource>(
+        parser: &mut ByteParser<S>,
+        msg: String,
+    ) -> Self {
+        Self::from_parser(ParsingErrorType::InvalidCharactersBlock(msg), parser)
+    }
+
+    /// Convenience constructor for [ParsingErrorType::InvalidDistancesBlock]
+    pub fn invalid_distances_block<S: ByteSource>(parser: &mut ByteParser<S>, msg: String) -> Self {
+        Self::from_parser(ParsingErrorType::InvalidDistancesBlock(msg), parser)
+    }
+
+    /// Convenience constructor for [ParsingErrorType::InvalidSetsBlock]
+    pub fn invalid_sets_block<S: ByteSource>(parser: &mut ByteParser<S>, msg: String) -> Self {
+        Self::from_parser(ParsingErrorType::InvalidSetsBlock(msg), parser)
     }
 
     /// Convenience constructor for [ParsingErrorType::UnclosedComment]
@@ -129,6 +181,11 @@ impl ParsingError {
         Self::from_parser(ParsingErrorType::InvalidNewickString(msg), parser)
     }
 
+    /// Convenience constructor for [ParsingErrorType::InvalidBranchLength]
+    pub fn invalid_branch_length<S: ByteSource>(parser: &mut ByteParser<S>, msg: String) -> Self {
+        Self::from_parser(ParsingErrorType::InvalidBranchLength(msg), parser)
+    }
+
     /// Convenience constructor for [ParsingErrorType::InvalidFormatting]
     pub fn invalid_formatting<S: ByteSource>(parser: &mut ByteParser<S>) -> Self {
         Self::from_parser(ParsingErrorType::InvalidFormatting, parser)
@@ -139,6 +196,14 @@ impl ParsingError {
         Self::from_parser(ParsingErrorType::UnresolvedLabel(msg), parser)
     }
 
+    /// Attaches the (0-based) index and the name (if any) of the tree
+    /// in which the error occurred
+    pub fn with_tree(mut self, index: usize, name: Option<String>) -> Self {
+        self.tree_index = Some(index);
+        self.tree_name = name;
+        self
+    }
+
     /// Get the error kind
     pub fn kind(&self) -> &ParsingErrorType {
         &self.kind
@@ -148,6 +213,16 @@ impl ParsingError {
     pub fn position(&self) -> usize {
         self.position
     }
+
+    /// Get the (0-based) index of the tree in which the error occurred, if known
+    pub fn tree_index(&self) -> Option<usize> {
+        self.tree_index
+    }
+
+    /// Get the name of the tree in which the error occurred, if known
+    pub fn tree_name(&self) -> Option<&str> {
+        self.tree_name.as_deref()
+    }
 }
 
 impl fmt::Display for ParsingError {
@@ -163,23 +238,45 @@ impl fmt::Display for ParsingError {
             ParsingErrorType::InvalidTreesBlock(msg) => {
                 write!(f, "Invalid TREES block format - {msg}")?
             }
-            ParsingErrorType::InvalidTranslateCommand => write!(
-                f,
-                "Invalid TRANSLATE command - likely inconsistent with TAXA block"
-            )?,
+            ParsingErrorType::InvalidTranslateCommand(msg) => {
+                write!(f, "Invalid TRANSLATE command - {msg}")?
+            }
+            ParsingErrorType::InvalidCharactersBlock(msg) => {
+                write!(f, "Invalid DATA/CHARACTERS block format - {msg}")?
+            }
+            ParsingErrorType::InvalidDistancesBlock(msg) => {
+                write!(f, "Invalid DISTANCES block format - {msg}")?
+            }
+            ParsingErrorType::InvalidSetsBlock(msg) => {
+                write!(f, "Invalid SETS block format - {msg}")?
+            }
             ParsingErrorType::UnclosedComment => write!(f, "Unclosed comment")?,
             ParsingErrorType::InvalidBlockName => write!(f, "Invalid block name")?,
             ParsingErrorType::InvalidNewickString(msg) => {
                 write!(f, "Invalid newick string: {}", msg)?
             }
+            ParsingErrorType::InvalidBranchLength(msg) => {
+                write!(f, "Invalid branch length: {}", msg)?
+            }
             ParsingErrorType::UnexpectedEOF => write!(f, "Unexpected end of file")?,
             ParsingErrorType::InvalidFormatting => write!(f, "Invalid formatting")?,
             ParsingErrorType::UnresolvedLabel(msg) => write!(f, "Could not resolve label - {msg}")?,
             ParsingErrorType::BuilderNotInitialized => write!(f, "Builder not initialized")?,
             ParsingErrorType::InvalidTreeStructure => write!(f, "Invalid tree structure")?,
+            ParsingErrorType::InvalidConfiguration(msg) => {
+                write!(f, "Invalid parser configuration - {msg}")?
+            }
             ParsingErrorType::IoError(msg) => write!(f, "IO error - {msg}")?,
         }
 
+        // Additional tree information
+        if let Some(index) = self.tree_index {
+            write!(f, " in tree {index}")?;
+            if let Some(name) = &self.tree_name {
+                write!(f, " ('{name}')")?;
+            }
+        }
+
         // Additional position information
         write!(f, " at position {}", self.position)?;
 
@@ -209,6 +306,8 @@ impl From<std::io::Error> for ParsingError {
             kind: ParsingErrorType::IoError(err.to_string()),
             position: 0,            // No position for IO errors
             context: String::new(), // No parsing context
+            tree_index: None,
+            tree_name: None,
         }
     }
 }
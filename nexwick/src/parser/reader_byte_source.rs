@@ -0,0 +1,253 @@
+//! Reader implementation of byte source for parser.
+//!
+//! This module provides [ReaderByteSource], which reads from any
+//! [std::io::Read], e.g. stdin, a pipe, a socket, an archive entry, or a
+//! streaming decompressor (see
+//! [DecompressingByteSource](crate::parser::decompressing_byte_source::DecompressingByteSource)).
+
+use std::io::{ErrorKind, Read};
+
+use crate::parser::byte_source::ByteSource;
+
+/// Function (re-)opening the stream of a [ReaderByteSource] from its start
+pub(crate) type StreamOpener<R> = Box<dyn Fn() -> std::io::Result<R> + Send>;
+
+// =#========================================================================#=
+// READER BYTE SOURCE
+// =#========================================================================$=
+/// A byte source reading from any reader, without seeking.
+///
+/// Data read is kept in a buffer, retaining some history before the current
+/// position, so that peeking and short backtracking (e.g. to the start of a
+/// tree) work on the buffer.
+/// [set_position()](ByteSource::set_position) moves forward by reading and
+/// discarding data. Moving back beyond the buffer requires re-opening the
+/// stream, which is possible for files (e.g. when a lazy parser is reset),
+/// but not for arbitrary readers: these then behave as at their end.
+pub struct ReaderByteSource<R: Read> {
+    /// Re-opens the stream from its start, if possible
+    open: Option<StreamOpener<R>>,
+
+    /// Underlying reader
+    reader: R,
+
+    /// Data read, starting at absolute position `buffer_start`
+    buffer: Vec<u8>,
+
+    /// Absolute position of the first byte in the buffer
+    buffer_start: usize,
+
+    /// Number of bytes before the current position that are kept in the buffer
+    history: usize,
+
+    /// Current absolute position in the stream
+    pos: usize,
+
+    /// Whether the end of the stream (or an error) has been reached
+    eof: bool,
+}
+
+impl<R: Read> ReaderByteSource<R> {
+    /// Number of bytes read at once.
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Number of bytes kept before the current position, enough to
+    /// backtrack to the start of all but huge trees.
+    const HISTORY_CAPACITY: usize = 4 * 1024 * 1024;
+
+    /// Creates a new byte source reading from `reader`, which cannot be
+    /// re-opened.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader to read from (unbuffered is fine)
+    pub fn new(reader: R) -> Self {
+        Self {
+            open: None,
+            reader,
+            buffer: Vec::with_capacity(Self::CHUNK_SIZE),
+            buffer_start: 0,
+            history: Self::HISTORY_CAPACITY,
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Creates a new byte source from a function opening the stream, which
+    /// is called again to move back beyond the buffer.
+    ///
+    /// # Errors
+    /// Returns an error if the stream cannot be opened.
+    pub(crate) fn reopenable(open: StreamOpener<R>) -> std::io::Result<Self> {
+        let mut source = Self::new(open()?);
+        source.open = Some(open);
+        Ok(source)
+    }
+
+    /// Reads data until the buffer holds `k` bytes from the current
+    /// position on (or the end of the stream is reached).
+    fn fill(&mut self, k: usize) {
+        while !self.eof && self.buffer_start + self.buffer.len() < self.pos + k {
+            // Discard data long before the current position (keeping history)
+            let behind = self.pos - self.buffer_start;
+            if behind > 2 * self.history {
+                let discard = (behind - self.history).min(self.buffer.len());
+                self.buffer.drain(..discard);
+                self.buffer_start += discard;
+            }
+
+            let len = self.buffer.len();
+            self.buffer.resize(len + Self::CHUNK_SIZE, 0);
+            match self.reader.read(&mut self.buffer[len..]) {
+                Ok(0) => {
+                    self.buffer.truncate(len);
+                    self.eof = true;
+                }
+                Ok(n) => self.buffer.truncate(len + n),
+                Err(err) if err.kind() == ErrorKind::Interrupted => self.buffer.truncate(len),
+                Err(_) => {
+                    self.buffer.truncate(len);
+                    self.eof = true;
+                }
+            }
+        }
+    }
+
+    /// Returns the buffered bytes from the current position on.
+    fn available(&self) -> &[u8] {
+        let start = (self.pos - self.buffer_start).min(self.buffer.len());
+        &self.buffer[start..]
+    }
+}
+
+impl<R: Read> ByteSource for ReaderByteSource<R> {
+    fn peek(&mut self) -> Option<u8> {
+        self.fill(1);
+        self.available().first().copied()
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn peek_slice(&mut self, k: usize) -> &[u8] {
+        self.fill(k);
+        let available = self.available();
+        &available[..k.min(available.len())]
+    }
+
+    fn get_context(&mut self, k: usize) -> Vec<u8> {
+        self.peek_slice(k).to_vec()
+    }
+
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn set_position(&mut self, pos: usize) {
+        if pos < self.buffer_start {
+            // (Discarded already, so read again from the start, if possible)
+            match self.open.as_ref().map(|open| open()) {
+                Some(Ok(reader)) => {
+                    self.reader = reader;
+                    self.eof = false;
+                    self.buffer_start = 0;
+                }
+                Some(Err(_)) | None => {
+                    self.eof = true;
+                    self.buffer_start = pos;
+                }
+            }
+            self.buffer.clear();
+        }
+        self.pos = pos;
+    }
+
+    fn is_eof(&mut self) -> bool {
+        self.fill(1);
+        self.available().is_empty()
+    }
+}
+
+// =#========================================================================#=
+// TESTS - READER BYTE SOURCE
+// =#========================================================================$=
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Bytes 0, 1, ..., 255, 0, 1, ... of the given length
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    /// Re-openable source over [data()], counting how often it is opened.
+    fn counting_source(
+        len: usize,
+        history: usize,
+    ) -> (ReaderByteSource<Cursor<Vec<u8>>>, Arc<AtomicUsize>) {
+        let num_opened = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&num_opened);
+        let open: StreamOpener<Cursor<Vec<u8>>> = Box::new(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Ok(Cursor::new(data(len)))
+        });
+        let mut source = ReaderByteSource::reopenable(open).unwrap();
+        source.history = history;
+        (source, num_opened)
+    }
+
+    #[test]
+    fn test_peek_and_backtrack_within_history() {
+        let (mut source, num_opened) = counting_source(200_000, 1000);
+        assert_eq!(source.peek_slice(3), &[0, 1, 2]);
+        assert_eq!(source.next_byte(), Some(0));
+
+        // Forward beyond the buffer, then a short backtrack
+        source.set_position(150_000);
+        assert_eq!(source.peek(), Some((150_000 % 256) as u8));
+        source.set_position(149_500);
+        assert_eq!(source.next_byte(), Some((149_500 % 256) as u8));
+        assert_eq!(num_opened.load(Ordering::Relaxed), 1);
+
+        // Peek across the end
+        source.set_position(199_998);
+        assert_eq!(source.get_context(5).len(), 2);
+        source.set_position(200_000);
+        assert!(source.is_eof());
+        assert_eq!(source.next_byte(), None);
+    }
+
+    #[test]
+    fn test_reset_reopens_stream() {
+        let (mut source, num_opened) = counting_source(300_000, 1000);
+        source.set_position(250_000);
+        assert_eq!(source.peek(), Some((250_000 % 256) as u8));
+
+        // Start has been discarded, so the stream is re-opened
+        source.set_position(10);
+        assert_eq!(num_opened.load(Ordering::Relaxed), 2);
+        assert_eq!(source.position(), 10);
+        assert_eq!(source.next_byte(), Some(10));
+        assert!(!source.is_eof());
+    }
+
+    #[test]
+    fn test_reset_without_reopen_ends_stream() {
+        let mut source = ReaderByteSource::new(Cursor::new(data(300_000)));
+        source.history = 1000;
+        source.set_position(250_000);
+        assert_eq!(source.next_byte(), Some((250_000 % 256) as u8));
+
+        // Short backtrack works, but the start has been discarded
+        source.set_position(249_990);
+        assert_eq!(source.peek(), Some((249_990 % 256) as u8));
+        source.set_position(10);
+        assert!(source.is_eof());
+        assert_eq!(source.peek(), None);
+    }
+}
@@ -23,6 +23,16 @@ fn test_skip_comment() {
     assert!(!parser.skip_comment().unwrap());
 }
 
+#[test]
+fn test_skip_nested_comment() {
+    let mut parser = ByteParser::for_str("[outer [inner] [deeper [still]]] A [unclosed [nested]");
+    assert!(parser.skip_comment().unwrap());
+    assert_eq!(parser.next_byte(), Some(b' '));
+    assert_eq!(parser.next_byte(), Some(b'A'));
+    parser.skip_whitespace();
+    assert!(parser.skip_comment().is_err());
+}
+
 #[test]
 fn test_skip_comment_and_whitespace() {
     let mut parser =
@@ -78,6 +88,17 @@ fn test_consume_until_any_exclusive() {
     assert_eq!(parser.position(), 24);
 }
 
+#[test]
+fn test_consume_until_unquoted() {
+    let mut parser = ByteParser::for_str("('a;b','It''s;' [;],C); D;");
+    assert!(parser.consume_until_unquoted(b';'));
+    assert_eq!(parser.peek(), Some(b' '));
+    assert_eq!(parser.position(), 23);
+
+    let mut parser = ByteParser::for_str("('a;b,C);");
+    assert!(!parser.consume_until_unquoted(b';'));
+}
+
 #[test]
 fn test_is_eof() {
     let mut parser = ByteParser::for_str("... happily ever after!");
@@ -0,0 +1,194 @@
+use nexwick::model::{CompactNetworkBuilder, GenNetwork, LeafLabelMap};
+use nexwick::newick::{NewickParser, NewickStyle, parse_network_str};
+use nexwick::nexus::{NexusParserBuilder, NexusWriter};
+use nexwick::parser::byte_parser::ByteParser;
+use std::fs::File;
+use std::path::Path;
+
+// --- TESTS NETWORK MODEL ---
+
+#[test]
+fn test_build_network() {
+    // Root -> (u, v), u -> (A, h), v -> (h, C), h -> B
+    let mut labels = LeafLabelMap::new(3);
+    let mut network = GenNetwork::new(7);
+    let a = network.add_leaf(labels.get_or_insert("A"));
+    let b = network.add_leaf(labels.get_or_insert("B"));
+    let c = network.add_leaf(labels.get_or_insert("C"));
+    let h = network.add_vertex();
+    let u = network.add_vertex();
+    let v = network.add_vertex();
+    let root = network.add_vertex();
+    network.add_edge(h, b, None, None);
+    network.add_edge(u, a, None, None);
+    network.add_edge(u, h, None, Some(0.4));
+    network.add_edge(v, h, None, Some(0.6));
+    network.add_edge(v, c, None, None);
+    network.add_edge(root, u, None, None);
+    network.add_edge(root, v, None, None);
+    network.set_root(root);
+
+    assert!(network.is_valid());
+    assert!(!network.is_tree());
+    assert_eq!(network.num_leaves(), 3);
+    assert_eq!(network.num_hybrids(), 1);
+    assert_eq!(network.num_edges(), 7);
+    assert!(network.vertex(h).is_hybrid());
+    assert_eq!(network.vertex(h).num_parents(), 2);
+
+    // Generated hybrid identifier
+    assert_eq!(
+        network.to_enewick(&NewickStyle::Label, Some(&labels)),
+        "((A,(B)#H1:::0.4),(#H1:::0.6,C));"
+    );
+}
+
+#[test]
+fn test_is_valid_inheritance_sum() {
+    let network =
+        parse_network_str("((A,(B)#H1:0.5::0.3),(#H1:0.2::0.6,C));").expect("Should parse");
+    assert!(!network.is_valid());
+}
+
+#[test]
+fn test_is_valid_cycle() {
+    // Hybrid is its own ancestor
+    let network = parse_network_str("((A,((B,#H1))#H1),C);").expect("Should parse");
+    assert!(!network.is_valid());
+}
+
+// --- TESTS ENEWICK PARSING ---
+
+#[test]
+fn test_parse_enewick() {
+    let enewick = "((A:1,(B:1)#H1:0.5::0.3):1,(#H1:0.2::0.7,C:1):1);";
+    let mut byte_parser = ByteParser::for_str(enewick);
+    let mut parser = NewickParser::new(CompactNetworkBuilder::new());
+    let network = parser.parse_str(&mut byte_parser).unwrap();
+    let labels = parser.into_label_storage();
+
+    assert!(network.is_valid());
+    assert_eq!(network.num_leaves(), 3);
+    assert_eq!(network.num_hybrids(), 1);
+    assert_eq!(labels.num_labels(), 3);
+
+    let hybrid = network.hybrids().next().unwrap();
+    assert_eq!(hybrid.hybrid_id(), Some("H1"));
+    assert_eq!(hybrid.num_children(), 1);
+    let edges = hybrid.parents();
+    assert_eq!(*edges[0].branch_length().unwrap(), 0.5);
+    assert_eq!(edges[0].inheritance(), Some(0.3));
+    assert_eq!(*edges[1].branch_length().unwrap(), 0.2);
+    assert_eq!(edges[1].inheritance(), Some(0.7));
+
+    // Round trip
+    assert_eq!(
+        network.to_enewick(&NewickStyle::Label, Some(&labels)),
+        enewick
+    );
+}
+
+#[test]
+fn test_parse_enewick_hybrid_leaf_and_labels() {
+    // Hybrid leaf B with two parents, labelled internal vertices
+    let network = parse_network_str("((A,B#H1)x,(#H1,C)y)root;").unwrap();
+
+    assert!(network.is_valid());
+    assert_eq!(network.num_leaves(), 3);
+    let hybrid = network.hybrids().next().unwrap();
+    assert!(hybrid.is_leaf());
+    assert_eq!(hybrid.label().map(String::as_str), Some("B"));
+    assert_eq!(network.internal_label(network.root_index()), Some("root"));
+}
+
+#[test]
+fn test_parse_enewick_hybrid_defined_later() {
+    // Hybrid referenced before its subtree is given
+    let network = parse_network_str("((A,#H1),(C,(B,D)#H1));").unwrap();
+
+    assert!(network.is_valid());
+    assert_eq!(network.num_leaves(), 4);
+    let hybrid = network.hybrids().next().unwrap();
+    assert_eq!(hybrid.num_children(), 2);
+}
+
+#[test]
+fn test_parse_tree_as_network() {
+    let network = parse_network_str("((A:1,B:1):1,C:2);").unwrap();
+    assert!(network.is_valid());
+    assert!(network.is_tree());
+}
+
+#[test]
+fn test_hash_in_tree_labels() {
+    // Without a network builder, '#' is part of the label
+    let tree = nexwick::parse_newick_str("((A#1,B),C);").unwrap();
+    assert_eq!(tree.num_leaves(), 3);
+}
+
+#[test]
+fn test_missing_hybrid_id() {
+    assert!(parse_network_str("((A,(B)#),(#,C));").is_err());
+}
+
+#[test]
+fn test_undefined_or_single_hybrid() {
+    use nexwick::parser::parsing_error::ParsingErrorType::InvalidNewickString;
+
+    for enewick in [
+        "(#H1,A);",               // Referenced once, never defined
+        "((A,#H1),(#H1,C));",     // Neither subtree nor label
+        "((A,(B)#H1),C);",        // Defined, but only one parent
+        "(((B)#H1,(D)#H1),#H1);", // Defined twice
+    ] {
+        let err = parse_network_str(enewick).unwrap_err();
+        assert!(matches!(err.kind(), InvalidNewickString(_)), "{enewick}");
+    }
+}
+
+// --- TESTS NEXUS WITH NETWORKS ---
+
+#[test]
+fn test_nexus_networks() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_networks.trees");
+    let parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_tree_builder(CompactNetworkBuilder::new())
+        .build()
+        .unwrap();
+    let (networks, labels) = parser.into_results().unwrap();
+
+    assert_eq!(networks.len(), 2);
+    assert_eq!(labels.num_labels(), 4);
+    assert_eq!(networks[0].name().unwrap(), "net_0");
+    assert_eq!(networks[0].num_hybrids(), 1);
+    assert!(networks[1].is_tree());
+    for network in &networks {
+        assert!(network.is_valid());
+        assert_eq!(network.num_leaves(), 4);
+    }
+
+    // Round trip via writer
+    let out_path = std::env::temp_dir().join("nexwick_test_networks.trees");
+    let mut writer = NexusWriter::new(File::create(&out_path).unwrap());
+    writer.write_nexus_networks(&networks, &labels).unwrap();
+
+    let parser = NexusParserBuilder::for_file(&out_path)
+        .unwrap()
+        .with_tree_builder(CompactNetworkBuilder::new())
+        .build()
+        .unwrap();
+    let (networks_again, labels_again) = parser.into_results().unwrap();
+    std::fs::remove_file(&out_path).unwrap();
+
+    assert_eq!(networks_again.len(), 2);
+    for (network, network_again) in networks.iter().zip(&networks_again) {
+        assert_eq!(network.name(), network_again.name());
+        assert_eq!(
+            network.to_enewick(&NewickStyle::Label, Some(&labels)),
+            network_again.to_enewick(&NewickStyle::Label, Some(&labels_again))
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use nexwick::model::annotation::AnnotationValue;
-use nexwick::newick::{NewickParser, parse_file};
+use nexwick::newick::{
+    AnnotationStyle, BranchAnnotationPolicy, BranchLengthPolicy, NewickParser, NewickStyle,
+    UnrootedTreePolicy, parse_file, parse_reader,
+};
 use nexwick::parser::byte_parser::ByteParser;
+use nexwick::parser::parsing_error::ParsingErrorType;
 use std::path::Path;
 
 // --- TESTS NEWICK STRING PARSING ---
@@ -27,12 +31,14 @@ fn test_basic_compact_tree() {
     // - Root has children (internal, C)
     let root = tree.root();
     let root_index = root.index();
-    let (root_left, root_right) = root.children().unwrap();
+    let root_children = root.children().unwrap();
+    let (root_left, root_right) = (root_children[0], root_children[1]);
 
     // - Internal node has children (A, B)
     let internal = tree.vertex(root_left);
     assert!(internal.is_internal());
-    let (internal_left, internal_right) = internal.children().unwrap();
+    let internal_children = internal.children().unwrap();
+    let (internal_left, internal_right) = (internal_children[0], internal_children[1]);
 
     // - Three leaves
     let leaf_a = tree.vertex(internal_left);
@@ -60,9 +66,11 @@ fn test_basic_simple_tree() {
 
     // Labels are stored directly in leaves
     let root = tree.root();
-    let (left, right) = root.children().unwrap();
+    let root_children = root.children().unwrap();
+    let (left, right) = (root_children[0], root_children[1]);
     let internal = tree.vertex(left);
-    let (a_idx, b_idx) = internal.children().unwrap();
+    let internal_children = internal.children().unwrap();
+    let (a_idx, b_idx) = (internal_children[0], internal_children[1]);
 
     assert_eq!(tree.vertex(a_idx).label().unwrap(), "A");
     assert_eq!(tree.vertex(b_idx).label().unwrap(), "B");
@@ -158,6 +166,105 @@ fn test_newick_with_comment_2() {
     assert!(tree.is_ok());
 }
 
+#[test]
+fn test_polytomies() {
+    let newick = "((A:1.0,B:1.0,C:1.0):1.0,D:2.0,(E:0.5,F:0.5):1.5);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let leaf_map = newick_parser.into_label_storage();
+
+    assert!(tree.is_valid());
+    assert!(!tree.is_binary());
+    assert_eq!(tree.num_leaves(), 6);
+    assert_eq!(tree.num_internal(), 2);
+    assert_eq!(tree.num_vertices(), 9);
+    assert_eq!(tree.root().num_children(), 3);
+    assert!(tree.is_ultrametric());
+
+    // Round trip
+    assert_eq!(
+        tree.to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "((A:1,B:1,C:1):1,D:2,(E:0.5,F:0.5):1.5);"
+    );
+}
+
+#[test]
+fn test_internal_support_values() {
+    let newick = "((A:1.0,B:1.0)95:0.1,(C:1.0,D:1.0)80/99.5:0.1);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let leaf_map = newick_parser.into_label_storage();
+
+    assert!(tree.has_internal_labels());
+    let root = tree.root();
+    let root_children = root.children().unwrap();
+    let (ab, cd) = (root_children[0], root_children[1]);
+
+    assert_eq!(tree.internal_label(ab), Some("95"));
+    assert_eq!(tree.support(ab), Some(95.0));
+    assert_eq!(tree.internal_label(cd), Some("80/99.5"));
+    assert_eq!(tree.support_values(cd), Some(vec![80.0, 99.5]));
+    assert_eq!(tree.internal_label(root.index()), None);
+    assert_eq!(*tree.vertex(ab).branch_length().unwrap(), 0.1);
+
+    // Round trip
+    assert_eq!(
+        tree.to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "((A:1,B:1)95:0.1,(C:1,D:1)80/99.5:0.1);"
+    );
+}
+
+#[test]
+fn test_internal_clade_names() {
+    let newick = "((A,B)Clade_X,(C,D) 'Clade Y')Root;";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let leaf_map = newick_parser.into_label_storage();
+
+    let root = tree.root();
+    let root_children = root.children().unwrap();
+    assert_eq!(tree.internal_label(root_children[0]), Some("Clade_X"));
+    assert_eq!(tree.internal_label(root_children[1]), Some("Clade Y"));
+    assert_eq!(tree.internal_label(root.index()), Some("Root"));
+    assert_eq!(tree.support(root_children[0]), None);
+
+    // Round trip
+    assert_eq!(
+        tree.to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "((A,B)Clade_X,(C,D)Clade_Y)Root;"
+    );
+}
+
+#[test]
+fn test_internal_label_with_annotations() {
+    let newick = "((A:1.0,B:2.0)95[&height=3.0]:3.0,C:4.0)[&height=5.0];";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    let internal_idx = tree.root().children().unwrap()[0];
+    assert_eq!(tree.support(internal_idx), Some(95.0));
+    let annots = tree.annotations().expect("Expected annotations");
+    assert!(
+        matches!(annots.get("height", internal_idx), Some(AnnotationValue::Float(v)) if (v - 3.0).abs() < 1e-10)
+    );
+}
+
+#[test]
+fn test_no_internal_labels() {
+    let newick = "((A:1.0,B:2.0)[comment]:3.0,C:4.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let tree = NewickParser::new_compact_defaults()
+        .parse_str(&mut parser)
+        .unwrap();
+
+    assert!(!tree.has_internal_labels());
+    assert_eq!(tree.internal_label(tree.root_index()), None);
+}
+
 // --- TESTS DEALING WITH CORRUPT NEWICK STRINGS ---
 
 #[test]
@@ -190,6 +297,14 @@ fn test_unmatched_parentheses() {
     assert!(tree.is_err());
 }
 
+#[test]
+fn test_single_child() {
+    let newick = "((A:1.0):3.0,C:4.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let tree = NewickParser::new_compact_defaults().parse_str(&mut parser);
+    assert!(tree.is_err());
+}
+
 #[test]
 fn test_invalid_branch_length() {
     let newick = "((A:1.0,B:abc):3.0,C:4.0):0.5;";
@@ -200,6 +315,62 @@ fn test_invalid_branch_length() {
     assert!(tree.is_err());
 }
 
+#[test]
+fn test_negative_branch_length_error_by_default() {
+    let newick = "((A:1.0,B:-0.0003):3.0,C:4.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let result = NewickParser::new_compact_defaults().parse_str(&mut parser);
+    let error = result.expect_err("Expected error for negative branch length");
+    assert!(matches!(
+        error.kind(),
+        ParsingErrorType::InvalidBranchLength(_)
+    ));
+}
+
+#[test]
+fn test_non_finite_branch_length_error() {
+    for newick in ["(A:NaN,B:1.0);", "(A:inf,B:1.0);", "(A:1e999,B:1.0);"] {
+        let mut parser = ByteParser::for_str(newick);
+        let result = NewickParser::new_compact_defaults()
+            .with_branch_length_policy(BranchLengthPolicy::ClampToZero)
+            .parse_str(&mut parser);
+        assert!(
+            matches!(result, Err(ref e) if matches!(e.kind(), ParsingErrorType::InvalidBranchLength(_))),
+            "Expected error for {}",
+            newick
+        );
+    }
+}
+
+#[test]
+fn test_negative_branch_length_clamped() {
+    let newick = "((A:1.0,B:-0.0003):3.0,C:-0.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let tree = NewickParser::new_compact_defaults()
+        .with_branch_length_policy(BranchLengthPolicy::ClampToZero)
+        .parse_str(&mut parser)
+        .unwrap();
+
+    // Build order: A=0, B=1, internal(A,B)=2, C=3, root=4
+    assert_eq!(*tree.vertex(1).branch_length().unwrap(), 0.0);
+    assert_eq!(*tree.vertex(3).branch_length().unwrap(), 0.0);
+    assert_eq!(*tree.vertex(0).branch_length().unwrap(), 1.0);
+}
+
+#[test]
+fn test_negative_branch_length_kept() {
+    let newick = "((A:1,B:-0.0003):3,C:NaN);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser =
+        NewickParser::new_compact_defaults().with_branch_length_policy(BranchLengthPolicy::Keep);
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let map = newick_parser.into_label_storage();
+
+    assert_eq!(*tree.vertex(1).branch_length().unwrap(), -0.0003);
+    assert!(tree.vertex(3).branch_length().unwrap().is_nan());
+    assert_eq!(tree.to_newick(&NewickStyle::Label, Some(&map)), newick);
+}
+
 // --- TESTS PARSING WHOLE FILE ---
 #[test]
 fn test_parsing_newick_file() {
@@ -217,6 +388,45 @@ fn test_parsing_newick_file() {
     }
 }
 
+#[test]
+fn test_parsing_newick_from_reader() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("newick_t3_n10.nwk");
+    let (file_trees, _) = parse_file(&path).unwrap();
+
+    let (trees, leaf_map) = parse_reader(std::fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(trees.len(), 3);
+    assert_eq!(leaf_map.num_labels(), 10);
+    assert_eq!(
+        trees[2].to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        file_trees[2].to_newick(&NewickStyle::Label, Some(&leaf_map))
+    );
+
+    // Iterating as trees come
+    let content = std::fs::read(&path).unwrap();
+    let parser = NewickParser::new_compact_defaults();
+    let trees: Vec<_> = parser
+        .into_iter_from_reader(content.as_slice())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(trees.len(), 3);
+}
+
+#[test]
+fn test_parsing_more_taxa_than_guessed() {
+    // More leaves than the default guess of 10
+    let newick = "(((A,B),(C,D)),((E,F),(G,H)),((I,J),(K,L)));";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let leaf_map = newick_parser.into_label_storage();
+
+    assert_eq!(tree.num_leaves(), 12);
+    assert_eq!(leaf_map.num_labels(), 12);
+    assert!(leaf_map.contains_label("L"));
+}
+
 // --- TESTS ANNOTATION PARSING ---
 
 #[test]
@@ -253,7 +463,8 @@ fn test_annotations_on_internal_and_root() {
 
     // Build order: A=0, B=1, internal(A,B)=2, C=3, root=4
     let root = tree.root();
-    let (internal_idx, _) = root.children().unwrap();
+    let root_children = root.children().unwrap();
+    let internal_idx = root_children[0];
 
     let height_internal = annots.get("height", internal_idx);
     let height_root = annots.get("height", root.index());
@@ -331,3 +542,435 @@ fn test_no_annotations_returns_none() {
     // Annotations enabled but tree has none
     assert!(tree.annotations().is_none());
 }
+
+// --- TESTS NHX ANNOTATIONS ---
+
+#[test]
+fn test_nhx_annotations() {
+    let newick = "((A:1.0[&&NHX:S=human:D=N],B:2.0[&&NHX:S=mouse])[&&NHX:D=Y:B=95]:3.0,C:4.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults()
+        .with_num_leaves(3)
+        .with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    let annots = tree.annotations().expect("Expected annotations");
+
+    // Build order: A=0, B=1, internal(A,B)=2, C=3, root=4
+    assert!(matches!(annots.get("S", 0), Some(AnnotationValue::String(ref s)) if s == "human"));
+    assert!(matches!(annots.get("D", 0), Some(AnnotationValue::String(ref s)) if s == "N"));
+    assert!(matches!(annots.get("S", 1), Some(AnnotationValue::String(ref s)) if s == "mouse"));
+    assert!(matches!(annots.get("D", 2), Some(AnnotationValue::String(ref s)) if s == "Y"));
+    assert!(matches!(annots.get("B", 2), Some(AnnotationValue::Int(95))));
+    assert!(annots.get("S", 3).is_none());
+
+    // Branch lengths are unaffected
+    assert_eq!(*tree.vertex(0).branch_length().unwrap(), 1.0);
+    assert_eq!(*tree.vertex(2).branch_length().unwrap(), 3.0);
+}
+
+#[test]
+fn test_nhx_and_beast_annotations_merged() {
+    let newick = "(A[&rate=0.5]:1.0[&&NHX:S=human],B:2.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    let annots = tree.annotations().expect("Expected annotations");
+    assert!(
+        matches!(annots.get("rate", 0), Some(AnnotationValue::Float(v)) if (v - 0.5).abs() < 1e-10)
+    );
+    assert!(matches!(annots.get("S", 0), Some(AnnotationValue::String(ref s)) if s == "human"));
+}
+
+#[test]
+fn test_nhx_treated_as_comment_when_disabled() {
+    let newick = "(A:1.0[&&NHX:S=human],B:2.0[&&NHX:S=mouse]);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    assert!(tree.annotations().is_none());
+    assert_eq!(tree.num_leaves(), 2);
+}
+
+#[test]
+fn test_write_annotations_round_trip() {
+    let newick = "((A:1.0[&&NHX:D=N:S=human],B:2.0)[&&NHX:B=95]:3.0,C:4.0[&&NHX:S=chicken]);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let map = newick_parser.into_label_storage();
+
+    let nhx =
+        tree.to_newick_with_annotations(&NewickStyle::Label, &AnnotationStyle::Nhx, Some(&map));
+    assert_eq!(
+        nhx,
+        "((A:1[&&NHX:D=N:S=human],B:2):3[&&NHX:B=95],C:4[&&NHX:S=chicken]);"
+    );
+
+    let beast =
+        tree.to_newick_with_annotations(&NewickStyle::Label, &AnnotationStyle::Beast, Some(&map));
+    assert_eq!(beast, "((A[&D=N,S=human]:1,B:2)[&B=95]:3,C[&S=chicken]:4);");
+
+    // Parsing the BEAST output again gives the same annotations
+    let mut parser = ByteParser::for_str(&beast);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let reparsed = newick_parser.parse_str(&mut parser).unwrap();
+    let map = newick_parser.into_label_storage();
+    assert_eq!(
+        reparsed.to_newick_with_annotations(&NewickStyle::Label, &AnnotationStyle::Nhx, Some(&map)),
+        nhx
+    );
+}
+
+// --- TESTS LIST AND QUOTED ANNOTATION VALUES ---
+
+#[test]
+fn test_annotations_list_values() {
+    let newick = "((A[&height=0.0,height_95%_HPD={0.0,0.0}]:1.0,B[&height=0.0]:1.0)\
+                  [&height=1.0,height_95%_HPD={0.8,1.3},posterior=1.0]:2.0,C:3.0)\
+                  [&height_95%_HPD={2.7,3.4},nested={{1,2},{a,b}},empty={}];";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    let annots = tree.annotations().expect("Expected annotations");
+
+    // Build order: A=0, B=1, internal(A,B)=2, C=3, root=4
+    let hpd = annots.get("height_95%_HPD", 2).unwrap();
+    let hpd: Vec<f64> = hpd
+        .as_list()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_f64().unwrap())
+        .collect();
+    assert_eq!(hpd, vec![0.8, 1.3]);
+    assert!(matches!(annots.get("posterior", 2), Some(AnnotationValue::Float(v)) if v == 1.0));
+
+    let nested = annots.get("nested", 4).unwrap();
+    let nested = nested.as_list().unwrap();
+    assert_eq!(nested.len(), 2);
+    assert!(matches!(
+        nested[0].as_list(),
+        Some([AnnotationValue::Int(1), AnnotationValue::Int(2)])
+    ));
+    assert!(matches!(&nested[1].as_list().unwrap()[1], AnnotationValue::String(s) if s == "b"));
+
+    let empty = annots.get("empty", 4).unwrap();
+    assert!(empty.as_list().unwrap().is_empty());
+}
+
+#[test]
+fn test_annotations_quoted_string_values() {
+    let newick = "(A[&name=\"x, y\",note='it''s [here]']:1.0,B[&!color=#ff0000]:2.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    let annots = tree.annotations().expect("Expected annotations");
+    assert!(matches!(annots.get("name", 0), Some(AnnotationValue::String(ref s)) if s == "x, y"));
+    assert!(
+        matches!(annots.get("note", 0), Some(AnnotationValue::String(ref s)) if s == "it's [here]")
+    );
+    assert!(
+        matches!(annots.get("!color", 1), Some(AnnotationValue::String(ref s)) if s == "#ff0000")
+    );
+}
+
+#[test]
+fn test_annotations_unclosed_list_fails() {
+    let newick = "(A[&hpd={1.2,3.4]:1.0,B:2.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    assert!(newick_parser.parse_str(&mut parser).is_err());
+}
+
+#[test]
+fn test_write_list_annotations_round_trip() {
+    let newick = "((A[&hpd={0.5,1.5},name=\"x, y\"]:1,B:1)[&hpd={{1,2},{3.5}}]:2,C:3);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let map = newick_parser.into_label_storage();
+
+    let written =
+        tree.to_newick_with_annotations(&NewickStyle::Label, &AnnotationStyle::Beast, Some(&map));
+    assert_eq!(written, newick);
+}
+
+// --- TESTS BRANCH ANNOTATIONS ---
+
+#[test]
+fn test_branch_annotations_separate() {
+    // MrBayes .con.tre style, plus annotation after branch length
+    let newick = "((A[&prob=1.0]:[&length_mean=0.12,length_95%HPD={0.1,0.14}]0.12,\
+                  B:0.3[&rate=0.9])[&prob=0.95]:[&rate=1.1]0.5,C:1.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    // Build order: A=0, B=1, internal(A,B)=2, C=3, root=4
+    assert!(matches!(tree.annotation("prob", 0), Some(AnnotationValue::Float(v)) if v == 1.0));
+    assert!(matches!(tree.annotation("prob", 2), Some(AnnotationValue::Float(v)) if v == 0.95));
+    assert!(tree.annotation("length_mean", 0).is_none());
+    assert!(tree.annotation("rate", 1).is_none());
+
+    assert!(
+        matches!(tree.branch_annotation("length_mean", 0), Some(AnnotationValue::Float(v)) if v == 0.12)
+    );
+    let hpd = tree.branch_annotation("length_95%HPD", 0).unwrap();
+    assert_eq!(hpd.as_list().unwrap().len(), 2);
+    assert!(
+        matches!(tree.branch_annotation("rate", 1), Some(AnnotationValue::Float(v)) if v == 0.9)
+    );
+    assert!(
+        matches!(tree.branch_annotation("rate", 2), Some(AnnotationValue::Float(v)) if v == 1.1)
+    );
+    assert!(tree.branch_annotation("rate", 3).is_none());
+
+    // Branch lengths are unaffected
+    assert_eq!(*tree.vertex(0).branch_length().unwrap(), 0.12);
+    assert_eq!(*tree.vertex(1).branch_length().unwrap(), 0.3);
+    assert_eq!(*tree.vertex(2).branch_length().unwrap(), 0.5);
+}
+
+#[test]
+fn test_branch_annotations_merged() {
+    let newick = "(A[&rate=0.5]:[&rate=0.9,prob=1.0]0.12,B:0.3[&rate=0.7]);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults()
+        .with_annotations()
+        .with_branch_annotation_policy(BranchAnnotationPolicy::Merge);
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    assert!(!tree.has_branch_annotations());
+    // Vertex annotation takes precedence on conflicting key
+    assert!(matches!(tree.annotation("rate", 0), Some(AnnotationValue::Float(v)) if v == 0.5));
+    assert!(matches!(tree.annotation("prob", 0), Some(AnnotationValue::Float(v)) if v == 1.0));
+    assert!(matches!(tree.annotation("rate", 1), Some(AnnotationValue::Float(v)) if v == 0.7));
+}
+
+#[test]
+fn test_branch_annotations_skipped_when_disabled() {
+    let newick = "(A:[&rate=0.9]0.12,B:0.3[&rate=0.7]);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+
+    assert!(!tree.has_annotations());
+    assert!(!tree.has_branch_annotations());
+    assert_eq!(*tree.vertex(0).branch_length().unwrap(), 0.12);
+}
+
+#[test]
+fn test_write_branch_annotations_round_trip() {
+    let newick = "((A[&prob=1.0]:[&rate=0.9]0.5,B:1)[&prob=0.5]:[&rate=1.1]2,C:3);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_annotations();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let map = newick_parser.into_label_storage();
+
+    let written =
+        tree.to_newick_with_annotations(&NewickStyle::Label, &AnnotationStyle::Beast, Some(&map));
+    assert_eq!(written, newick);
+}
+
+// --- TESTS TREE METADATA ---
+
+#[test]
+fn test_tree_metadata() {
+    let newick = "[&U] [some comment] [&W 1/4] ((A:1.0,B:2.0):3.0,C:4.0);";
+    let mut parser = ByteParser::for_str(newick);
+    let tree = NewickParser::new_compact_defaults()
+        .parse_str(&mut parser)
+        .unwrap();
+
+    assert_eq!(tree.metadata().rooted(), Some(false));
+    assert_eq!(tree.metadata().weight(), Some(0.25));
+    assert_eq!(tree.metadata().to_string(), "[&U] [&W 0.25]");
+}
+
+#[test]
+fn test_tree_metadata_multiple_trees() {
+    let newick = "[&R] (A,B);\n[a comment]\n[&W 0.5] (A,B);\n(A,B);";
+    let byte_parser = ByteParser::for_str(newick);
+    let trees = NewickParser::new_compact_defaults()
+        .parse_all(byte_parser)
+        .unwrap();
+
+    assert_eq!(trees.len(), 3);
+    assert_eq!(trees[0].metadata().rooted(), Some(true));
+    assert_eq!(trees[1].metadata().weight(), Some(0.5));
+    assert!(trees[2].metadata().is_empty());
+}
+
+#[test]
+fn test_tree_metadata_invalid_weight() {
+    let newick = "[&W abc] (A,B);";
+    let mut parser = ByteParser::for_str(newick);
+    assert!(
+        NewickParser::new_compact_defaults()
+            .parse_str(&mut parser)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_tree_metadata_quoted_bracket_and_invalid_annotations() {
+    let newick = "[&note='a ] b',lnP=-12.5] [&free text] (A,B);";
+    let mut parser = ByteParser::for_str(newick);
+    let tree = NewickParser::new_compact_defaults()
+        .parse_str(&mut parser)
+        .unwrap();
+    assert_eq!(
+        tree.metadata().annotation("note"),
+        Some(&AnnotationValue::String(String::from("a ] b")))
+    );
+    assert_eq!(tree.metadata().log_likelihood(), Some(-12.5));
+
+    // Malformed key-value pairs are an error, like a malformed weight
+    let mut parser = ByteParser::for_str("[&=1,lnP=-12.5] (A,B);");
+    assert!(
+        NewickParser::new_compact_defaults()
+            .parse_str(&mut parser)
+            .is_err()
+    );
+}
+
+// --- TESTS UNROOTED TREES ---
+
+#[test]
+fn test_unrooted_trifurcation_kept() {
+    let newick = "(A,B,(C,D));";
+    let mut parser = ByteParser::for_str(newick);
+    let tree = NewickParser::new_compact_defaults()
+        .parse_str(&mut parser)
+        .unwrap();
+
+    assert_eq!(tree.root().num_children(), 3);
+    assert_eq!(tree.metadata().rooted(), Some(false));
+    assert!(!tree.is_rooted());
+}
+
+#[test]
+fn test_explicitly_rooted_trifurcation() {
+    let newick = "[&R] (A,B,C);";
+    let mut parser = ByteParser::for_str(newick);
+    let tree = NewickParser::new_compact_defaults()
+        .with_unrooted_tree_policy(UnrootedTreePolicy::RootAtFirstChild)
+        .parse_str(&mut parser)
+        .unwrap();
+
+    assert_eq!(tree.root().num_children(), 3);
+    assert!(tree.is_rooted());
+}
+
+#[test]
+fn test_unrooted_trifurcation_rooted_at_first_child() {
+    let newick = "(A:1,B:2,(C:1,D:1):3);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults()
+        .with_unrooted_tree_policy(UnrootedTreePolicy::RootAtFirstChild);
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let leaf_map = newick_parser.into_label_storage();
+
+    assert!(tree.is_valid());
+    assert_eq!(tree.root().num_children(), 2);
+    assert_eq!(tree.num_internal(), 2);
+    assert!(!tree.is_rooted());
+
+    // Basal trifurcation restored when writing
+    assert_eq!(
+        tree.to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "(A:1,B:2,(C:1,D:1):3);"
+    );
+}
+
+#[test]
+fn test_unrooted_binary_root_written_as_is() {
+    let newick = "[&U]((A:1,B:1)x:1,(C:1,D:1)y:2);";
+    let mut parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults();
+    let tree = newick_parser.parse_str(&mut parser).unwrap();
+    let leaf_map = newick_parser.into_label_storage();
+
+    assert!(!tree.is_rooted());
+    assert!(!tree.metadata().rooted_at_first_child());
+    assert_eq!(
+        tree.to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "((A:1,B:1)x:1,(C:1,D:1)y:2);"
+    );
+}
+
+// --- TESTS ERROR RECOVERY ---
+
+#[test]
+fn test_error_recovery() {
+    let newick = "(A,B);\n(A,(B,C);\n(A,C);\n(A:x,B);\n(B,C);";
+
+    // Strict mode fails at the second tree
+    let byte_parser = ByteParser::for_str(newick);
+    let err = NewickParser::new_compact_defaults()
+        .parse_all(byte_parser)
+        .unwrap_err();
+    assert_eq!(err.tree_index(), Some(1));
+
+    // Lenient mode skips malformed trees
+    let byte_parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_error_recovery();
+    let trees = newick_parser.parse_all(byte_parser).unwrap();
+    assert_eq!(trees.len(), 3);
+    let errors: Vec<_> = newick_parser
+        .errors()
+        .iter()
+        .map(|err| err.tree_index())
+        .collect();
+    assert_eq!(errors, [Some(1), Some(3)]);
+}
+
+#[test]
+fn test_error_recovery_semicolon_in_quoted_label() {
+    let newick = "(A,B);\n('A;B' [;],C:x);\n(A,C);";
+    let byte_parser = ByteParser::for_str(newick);
+    let mut newick_parser = NewickParser::new_compact_defaults().with_error_recovery();
+    let trees = newick_parser.parse_all(byte_parser).unwrap();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(newick_parser.errors().len(), 1);
+    assert_eq!(newick_parser.errors()[0].tree_index(), Some(1));
+}
+
+#[test]
+fn test_error_recovery_discards_labels_of_skipped_trees() {
+    let byte_parser = ByteParser::for_str("(A,B);(C,D,;(A,B);");
+    let mut newick_parser = NewickParser::new_compact_defaults().with_error_recovery();
+    let trees = newick_parser.parse_all(byte_parser).unwrap();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(newick_parser.label_storage().labels(), &["A", "B"]);
+}
+
+#[test]
+fn test_error_recovery_from_reader_resyncs_forward() {
+    // Malformed tree longer than the history kept by the reader source
+    let long_comment = "x".repeat(10 * 1024 * 1024);
+    let newick = format!("(A,[{long_comment}]B,;\n(A,B);\n(B,C);");
+    let mut newick_parser = NewickParser::new_compact_defaults().with_error_recovery();
+    let trees = newick_parser
+        .parse_all_from_reader(std::io::Cursor::new(newick))
+        .unwrap();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(newick_parser.errors().len(), 1);
+}
+
+#[test]
+fn test_error_recovery_iterator() {
+    let newick = "(A,B);\n(A,(B,C);\n(A,C);";
+    let byte_parser = ByteParser::for_str(newick);
+    let mut iter = NewickParser::new_compact_defaults()
+        .with_error_recovery()
+        .into_iter(byte_parser);
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().is_none());
+    assert_eq!(iter.into_parser().errors().len(), 1);
+}
@@ -1,6 +1,12 @@
+use nexwick::model::{AnnotationValue, CompactTree, DataType};
 use nexwick::newick::NewickStyle;
-use nexwick::nexus::{Burnin, NexusParserBuilder};
+use nexwick::nexus::{
+    Burnin, NexusBlock, NexusBlockReader, NexusCombiner, NexusParserBuilder, NexusToken,
+    NexusWriter, RawBlock, Thinning,
+};
 use nexwick::parse_nexus_file;
+use nexwick::parser::parsing_error::ParsingErrorType;
+use std::fs::File;
 use std::path::Path;
 
 #[test]
@@ -65,6 +71,19 @@ fn test_comments_and_unknown_blocks() {
     }
 }
 
+#[test]
+fn test_unknown_block_with_end_in_quotes_and_comments() {
+    let nexus = "#NEXUS\nbegin paup;\n\tlog file='paup.log; end; log';\n\t[ end; ]\n\
+        \ttitle \"a; end;\" unterminated command\nend;\n\
+        begin trees; tree t1 = ((A,B),C); end;\n";
+    let parser = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .build()
+        .unwrap();
+    let (trees, leaf_map) = parser.into_results().unwrap();
+    assert_eq!(trees.len(), 1);
+    assert_eq!(leaf_map.num_labels(), 3);
+}
+
 #[test]
 fn test_skip_first() {
     let path = Path::new("tests")
@@ -165,6 +184,74 @@ fn test_skip_first_and_burnin() {
     assert_eq!(parser.num_total_trees(), 11);
 }
 
+#[test]
+fn test_thinning() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+    let names_of = |thinning, lazy| {
+        let builder = NexusParserBuilder::for_file(&path)
+            .unwrap()
+            .with_burnin(Burnin::Count(1))
+            .with_thinning(thinning);
+        let builder = if lazy { builder.lazy() } else { builder };
+        let parser = builder.build().unwrap();
+        let num_trees = parser.num_trees();
+        let (trees, _) = parser.into_results().unwrap();
+        assert_eq!(trees.len(), num_trees);
+        trees
+            .iter()
+            .map(|tree| tree.name().unwrap().clone())
+            .collect::<Vec<_>>()
+    };
+
+    // Every 3rd of the 10 trees after burnin
+    let expected = ["STATE_1000", "STATE_4000", "STATE_7000", "STATE_10000"];
+    assert_eq!(names_of(Thinning::Every(3), false), expected);
+    assert_eq!(names_of(Thinning::Every(3), true), expected);
+
+    // 4 evenly spaced trees of the 10 trees after burnin
+    let expected = ["STATE_1000", "STATE_3000", "STATE_6000", "STATE_8000"];
+    assert_eq!(names_of(Thinning::Target(4), false), expected);
+    assert_eq!(names_of(Thinning::Target(4), true), expected);
+
+    // Target larger than number of trees keeps all
+    assert_eq!(names_of(Thinning::Target(100), true).len(), 10);
+}
+
+#[test]
+fn test_state_range() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+
+    let parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .with_state_range(3000..=8000)
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_trees(), 6);
+    assert_eq!(parser.num_total_trees(), 11);
+
+    // With thinning applied after the range, in lazy mode and after reset
+    let mut parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .lazy()
+        .with_state_range(3000..)
+        .with_thinning(Thinning::Every(2))
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_trees(), 4);
+    for _ in 0..2 {
+        let mut states = Vec::new();
+        while let Some(tree) = parser.next_tree().unwrap() {
+            states.push(tree.metadata().state().unwrap());
+        }
+        assert_eq!(states, [3000, 5000, 7000, 9000]);
+        parser.reset();
+    }
+}
+
 #[test]
 fn test_lazy_mode() {
     let path = Path::new("tests")
@@ -213,6 +300,421 @@ fn test_lazy_mode_with_burnin() {
     assert_eq!(count, 8);
 }
 
+#[test]
+fn test_tree_at() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+    let (all_trees, leaf_map) = parse_nexus_file(&path).unwrap();
+    let newick_of = |tree: &CompactTree| tree.to_newick(&NewickStyle::Label, Some(&leaf_map));
+
+    let mut parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .lazy()
+        .with_burnin(Burnin::Count(2))
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_trees(), 9);
+
+    // Random access in any order, without affecting next_tree()
+    let first = parser.next_tree().unwrap().unwrap();
+    for index in [8, 0, 4, 4, 7] {
+        let tree = parser.tree_at(index).unwrap().unwrap();
+        assert_eq!(newick_of(&tree), newick_of(&all_trees[index + 2]));
+    }
+    assert!(parser.tree_at(9).unwrap().is_none());
+    let second = parser.next_tree().unwrap().unwrap();
+    assert_eq!(newick_of(&first), newick_of(&all_trees[2]));
+    assert_eq!(newick_of(&second), newick_of(&all_trees[3]));
+
+    // With thinning, indices refer to the selected trees (also buffered)
+    let mut parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .with_buffered_source()
+        .lazy()
+        .with_thinning(Thinning::Every(5))
+        .build()
+        .unwrap();
+    let tree = parser.tree_at(2).unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "STATE_10000");
+
+    // Eager mode stores trees, so they are accessed by reference
+    let parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(
+        newick_of(parser.tree_ref_at(3).unwrap()),
+        newick_of(&all_trees[3])
+    );
+    assert!(parser.tree_ref_at(11).is_none());
+}
+
+#[test]
+fn test_tree_index_file() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+    let index_path = std::env::temp_dir().join("nexwick_test_tree_index.idx");
+    let _ = std::fs::remove_file(&index_path);
+    let build = || {
+        NexusParserBuilder::for_file(&path)
+            .unwrap()
+            .lazy()
+            .with_tree_index(&index_path)
+            .build()
+            .unwrap()
+    };
+
+    // First run writes the index ...
+    let mut parser = build();
+    assert!(index_path.exists());
+    let expected = parser.tree_at(6).unwrap().unwrap();
+
+    // ... which is used when reopening the file
+    let mut parser = build();
+    assert_eq!(parser.num_trees(), 11);
+    let tree = parser.tree_at(6).unwrap().unwrap();
+    assert_eq!(tree.name(), expected.name());
+    assert_eq!(tree.num_leaves(), 20);
+    let mut count = 0;
+    while parser.next_tree().unwrap().is_some() {
+        count += 1;
+    }
+    assert_eq!(count, 11);
+
+    // An invalid index is ignored and rewritten
+    std::fs::write(&index_path, b"not an index").unwrap();
+    let mut parser = build();
+    assert_eq!(parser.num_trees(), 11);
+    assert_eq!(parser.tree_at(6).unwrap().unwrap().name(), expected.name());
+    assert!(std::fs::read(&index_path).unwrap().starts_with(b"NXWKIDX1"));
+
+    std::fs::remove_file(&index_path).unwrap();
+}
+
+#[test]
+fn test_tree_index_detects_changed_content() {
+    let path = std::env::temp_dir().join("nexwick_test_tree_index_content.trees");
+    let index_path = std::env::temp_dir().join("nexwick_test_tree_index_content.idx");
+    let _ = std::fs::remove_file(&index_path);
+    let write = |first: &str, second: &str| {
+        let nexus = format!(
+            "#NEXUS\nbegin trees;\n\ttree {first} = ((A,B),C);\n\ttree {second} = ((A,C),B);\nend;\n"
+        );
+        std::fs::write(&path, nexus).unwrap();
+    };
+    let build = || {
+        NexusParserBuilder::for_file(&path)
+            .unwrap()
+            .lazy()
+            .with_tree_index(&index_path)
+            .build()
+            .unwrap()
+    };
+
+    write("t1", "second");
+    let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+    build();
+
+    // Same length and modification time, but the second tree moved
+    write("first", "t2");
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    let mut parser = build();
+    assert_eq!(parser.tree_at(1).unwrap().unwrap().name().unwrap(), "t2");
+
+    // Failing to write the index does not fail parsing
+    let missing_dir_index = std::env::temp_dir()
+        .join("nexwick_missing_dir")
+        .join("t.idx");
+    let parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .with_tree_index(&missing_dir_index)
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_trees(), 2);
+
+    std::fs::remove_file(&index_path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_follow_mode() {
+    use std::io::Write;
+    use std::time::Duration;
+
+    let path = std::env::temp_dir().join("nexwick_test_follow.trees");
+    let append = |text: &str| {
+        let mut file = File::options().append(true).open(&path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    };
+    let header = "#NEXUS\nbegin taxa; dimensions ntax=3; taxlabels A B C; end;\n\
+                  begin trees;\n\ttranslate 1 A, 2 B, 3 C;\n";
+    std::fs::write(&path, header).unwrap();
+    append("\ttree STATE_0 = ((1:1,2:1):1,3:2);\n\ttree STATE_10 = ((1:1,3");
+
+    let mut parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .follow(Duration::from_millis(5))
+        .build()
+        .unwrap();
+
+    // Complete trees are returned, incomplete ones not (yet)
+    let tree = parser.next_available_tree().unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "STATE_0");
+    assert!(parser.next_available_tree().unwrap().is_none());
+    assert!(!parser.is_finished());
+
+    // ... until the file grows
+    append(":1):1,2:2);\n\ttree STATE_20 = ((2:1,3:1):1,1:2);\n\tEn");
+    let tree = parser.next_available_tree().unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "STATE_10");
+    let tree = parser.next_available_tree().unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "STATE_20");
+    assert!(parser.next_available_tree().unwrap().is_none());
+    assert_eq!(parser.num_trees(), 3);
+
+    // Blocking, while another thread writes the rest of the file
+    let writer = std::thread::spawn({
+        let path = path.clone();
+        move || {
+            std::thread::sleep(Duration::from_millis(50));
+            let mut file = File::options().append(true).open(&path).unwrap();
+            file.write_all(b"d;\n").unwrap();
+        }
+    });
+    assert!(parser.next_tree().unwrap().is_none());
+    assert!(parser.is_finished());
+    writer.join().unwrap();
+
+    // Burnin and thinning apply as trees are read
+    let mut parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .with_burnin(Burnin::Count(1))
+        .with_thinning(Thinning::Every(2))
+        .follow(Duration::from_millis(5))
+        .build()
+        .unwrap();
+    let tree = parser.next_tree().unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "STATE_10");
+    assert!(parser.next_tree().unwrap().is_none());
+    assert_eq!(parser.num_total_trees(), 3);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_follow_mode_taxa_from_first_tree() {
+    use std::io::Write;
+    use std::time::Duration;
+
+    // Without TAXA block and TRANSLATE command, and first tree not yet complete
+    // (with a semicolon in a quoted label)
+    let path = std::env::temp_dir().join("nexwick_test_follow_taxa.trees");
+    std::fs::write(&path, "#NEXUS\nbegin trees;\n\ttree t1 = (('A;1':1,B:1):1").unwrap();
+    let mut parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .follow(Duration::from_millis(5))
+        .build()
+        .unwrap();
+    assert!(parser.next_available_tree().unwrap().is_none());
+
+    let mut file = File::options().append(true).open(&path).unwrap();
+    file.write_all(b",C:2);\n\ttree t2 = (('A;1':1,C:1):1,B:2);\nend;\n")
+        .unwrap();
+    let tree = parser.next_available_tree().unwrap().unwrap();
+    assert_eq!(tree.num_leaves(), 3);
+    assert_eq!(parser.label_storage().num_labels(), 3);
+    assert!(parser.label_storage().get_index("A;1").is_some());
+    let tree = parser.next_available_tree().unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "t2");
+    assert!(parser.next_available_tree().unwrap().is_none());
+    assert!(parser.is_finished());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_follow_mode_rejects_unknown_tree_count() {
+    use nexwick::parser::parsing_error::ParsingErrorType::InvalidConfiguration;
+    use std::time::Duration;
+
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+    let builder = || {
+        NexusParserBuilder::for_file(&path)
+            .unwrap()
+            .follow(Duration::from_millis(5))
+    };
+    let result = builder().with_burnin(Burnin::Percentage(0.1)).build();
+    assert!(matches!(
+        result.err().unwrap().kind(),
+        InvalidConfiguration(_)
+    ));
+    let result = builder().with_thinning(Thinning::Target(5)).build();
+    assert!(matches!(
+        result.err().unwrap().kind(),
+        InvalidConfiguration(_)
+    ));
+    assert!(builder().with_burnin(Burnin::Count(1)).build().is_ok());
+}
+
+/// Compressed copies of `nexus_t11_n20_translate.trees` and whether the
+/// cargo feature to decompress them is enabled.
+const COMPRESSED_FIXTURES: [(&str, bool); 3] = [
+    ("nexus_t11_n20_translate.trees.gz", cfg!(feature = "gzip")),
+    ("nexus_t11_n20_translate.trees.zst", cfg!(feature = "zstd")),
+    ("nexus_t11_n20_translate.trees.bz2", cfg!(feature = "bzip2")),
+];
+
+#[test]
+fn test_compressed_files() {
+    let fixtures = Path::new("tests").join("fixtures");
+    let (all_trees, leaf_map) =
+        parse_nexus_file(fixtures.join("nexus_t11_n20_translate.trees")).unwrap();
+    let newick_of = |tree: &CompactTree| tree.to_newick(&NewickStyle::Label, Some(&leaf_map));
+
+    for (name, _) in COMPRESSED_FIXTURES.iter().filter(|(_, enabled)| *enabled) {
+        let path = fixtures.join(name);
+
+        // Eager, decompressed into memory
+        let (trees, _) = parse_nexus_file(&path).unwrap();
+        assert_eq!(trees.len(), 11);
+        assert_eq!(newick_of(&trees[5]), newick_of(&all_trees[5]));
+
+        // Lazy, streamed through decompressor
+        let mut parser = NexusParserBuilder::for_file(&path)
+            .unwrap()
+            .with_buffered_source()
+            .lazy()
+            .with_burnin(Burnin::Count(2))
+            .build()
+            .unwrap();
+        assert_eq!(parser.num_trees(), 9);
+        let mut count = 0;
+        while let Some(tree) = parser.next_tree().unwrap() {
+            assert_eq!(newick_of(&tree), newick_of(&all_trees[count + 2]));
+            count += 1;
+        }
+        assert_eq!(count, 9);
+
+        // Reset and random access going back (re-opening the stream if needed)
+        parser.reset();
+        let tree = parser.next_tree().unwrap().unwrap();
+        assert_eq!(newick_of(&tree), newick_of(&all_trees[2]));
+        let tree = parser.tree_at(8).unwrap().unwrap();
+        assert_eq!(newick_of(&tree), newick_of(&all_trees[10]));
+        let tree = parser.tree_at(0).unwrap().unwrap();
+        assert_eq!(newick_of(&tree), newick_of(&all_trees[2]));
+
+        // Compressed files cannot be followed
+        let result = NexusParserBuilder::for_file(&path)
+            .unwrap()
+            .follow(std::time::Duration::from_millis(5))
+            .build();
+        assert!(matches!(
+            result.err().unwrap().kind(),
+            ParsingErrorType::InvalidConfiguration(_)
+        ));
+    }
+}
+
+#[test]
+fn test_compressed_file_without_feature_fails() {
+    let fixtures = Path::new("tests").join("fixtures");
+    for (name, _) in COMPRESSED_FIXTURES.iter().filter(|(_, enabled)| !*enabled) {
+        let result = NexusParserBuilder::for_file(fixtures.join(name))
+            .unwrap()
+            .build();
+        let Err(err) = result else {
+            panic!("Parsing '{name}' should fail without its feature");
+        };
+        assert!(matches!(err.kind(), ParsingErrorType::IoError(msg) if msg.contains("feature")));
+    }
+}
+
+/// Reader yielding at most a few bytes per read, like a slow pipe.
+struct TricklingReader(std::io::Cursor<Vec<u8>>);
+
+impl std::io::Read for TricklingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(7);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+#[test]
+fn test_parse_from_reader() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+    let content = std::fs::read(&path).unwrap();
+    let (all_trees, leaf_map) = parse_nexus_file(&path).unwrap();
+    let newick_of = |tree: &CompactTree| tree.to_newick(&NewickStyle::Label, Some(&leaf_map));
+
+    // Eager, read into memory, so all settings supported
+    let parser = NexusParserBuilder::for_reader(File::open(&path).unwrap())
+        .with_burnin(Burnin::Percentage(0.5))
+        .build()
+        .unwrap();
+    let (trees, _) = parser.into_results().unwrap();
+    assert_eq!(trees.len(), 6);
+    assert_eq!(newick_of(&trees[0]), newick_of(&all_trees[5]));
+
+    // Lazy, streamed and trees read as they come
+    let reader = TricklingReader(std::io::Cursor::new(content.clone()));
+    let mut parser = NexusParserBuilder::for_reader(reader)
+        .lazy()
+        .with_burnin(Burnin::Count(2))
+        .with_thinning(Thinning::Every(3))
+        .build()
+        .unwrap();
+    let mut trees = Vec::new();
+    while let Some(tree) = parser.next_tree().unwrap() {
+        trees.push(tree);
+    }
+    assert_eq!(trees.len(), 3);
+    for (tree, index) in trees.iter().zip([2, 5, 8]) {
+        assert_eq!(newick_of(tree), newick_of(&all_trees[index]));
+    }
+    assert_eq!(parser.num_trees(), 3);
+    assert!(parser.is_finished());
+
+    // Streams cannot be read again
+    parser.reset();
+    assert!(parser.next_tree().unwrap().is_none());
+    assert!(parser.tree_at(0).unwrap().is_none());
+
+    // Number of trees unknown when streaming
+    for builder in [
+        NexusParserBuilder::for_reader(std::io::Cursor::new(content.clone()))
+            .with_burnin(Burnin::Percentage(0.1)),
+        NexusParserBuilder::for_reader(std::io::Cursor::new(content.clone()))
+            .with_thinning(Thinning::Target(5)),
+    ] {
+        let result = builder.lazy().build();
+        assert!(matches!(
+            result.err().unwrap().kind(),
+            ParsingErrorType::InvalidConfiguration(_)
+        ));
+    }
+
+    // Stream ending within the TREES block
+    let truncated = content[..content.len() - 40].to_vec();
+    let mut parser = NexusParserBuilder::for_reader(std::io::Cursor::new(truncated))
+        .lazy()
+        .build()
+        .unwrap();
+    let result: Result<Vec<_>, _> = std::iter::from_fn(|| parser.next_tree().transpose()).collect();
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_lazy_mode_reset() {
     let path = Path::new("tests")
@@ -293,3 +795,823 @@ fn test_with_annotations() {
         assert!(tree.annotations().is_some());
     }
 }
+
+#[test]
+fn test_write_and_parse_again() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+    let (trees, leaf_map) = parse_nexus_file(path).unwrap();
+
+    let out_path = std::env::temp_dir().join("nexwick_test_write_nexus.trees");
+    let mut writer = NexusWriter::new(File::create(&out_path).unwrap());
+    writer.write_nexus(&trees, &leaf_map).unwrap();
+
+    let (trees_again, leaf_map_again) = parse_nexus_file(&out_path).unwrap();
+    std::fs::remove_file(&out_path).unwrap();
+
+    assert_eq!(trees_again.len(), trees.len());
+    for (tree, tree_again) in trees.iter().zip(&trees_again) {
+        assert_eq!(tree.name(), tree_again.name());
+        assert_eq!(
+            tree.to_newick(&NewickStyle::Label, Some(&leaf_map)),
+            tree_again.to_newick(&NewickStyle::Label, Some(&leaf_map_again))
+        );
+    }
+}
+
+#[test]
+fn test_tree_metadata() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t1_n10.trees");
+    let (trees, _) = parse_nexus_file(path).unwrap();
+    assert_eq!(trees[0].metadata().rooted(), Some(true));
+    assert_eq!(trees[0].metadata().weight(), None);
+
+    // Also in lazy mode
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t1_n10.trees");
+    let mut parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .lazy()
+        .build()
+        .unwrap();
+    let tree = parser.next_tree().unwrap().unwrap();
+    assert_eq!(tree.metadata().rooted(), Some(true));
+}
+
+#[test]
+fn test_write_and_parse_tree_metadata() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t11_n20_translate.trees");
+    let (mut trees, leaf_map) = parse_nexus_file(path).unwrap();
+    trees[0].metadata_mut().set_rooted(false);
+    trees[0].metadata_mut().set_weight(0.25);
+    trees[1].metadata_mut().set_rooted(true);
+
+    let out_path = std::env::temp_dir().join("nexwick_test_write_nexus_metadata.trees");
+    let mut writer = NexusWriter::new(File::create(&out_path).unwrap());
+    writer.write_nexus(&trees, &leaf_map).unwrap();
+
+    let (trees_again, _) = parse_nexus_file(&out_path).unwrap();
+    std::fs::remove_file(&out_path).unwrap();
+
+    assert_eq!(trees_again[0].metadata().rooted(), Some(false));
+    assert_eq!(trees_again[0].metadata().weight(), Some(0.25));
+    assert_eq!(trees_again[1].metadata().rooted(), Some(true));
+    assert_eq!(trees_again[1].metadata().weight(), None);
+    assert_eq!(trees_again[2].metadata(), trees[2].metadata());
+}
+
+#[test]
+fn test_write_tree_with_state_only() {
+    let nexus = "#NEXUS
+        begin trees;
+            tree STATE_1 = ((A,B),C);
+        end;";
+    let (trees, leaf_map) = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .build()
+        .unwrap()
+        .into_results()
+        .unwrap();
+    assert_eq!(trees[0].metadata().state(), Some(1));
+
+    let out_path = std::env::temp_dir().join("nexwick_test_write_nexus_state_only.trees");
+    let mut writer = NexusWriter::new(File::create(&out_path).unwrap());
+    writer.write_nexus(&trees, &leaf_map).unwrap();
+    let written = std::fs::read_to_string(&out_path).unwrap();
+    std::fs::remove_file(&out_path).unwrap();
+
+    // State is kept in the name, without an empty comment before the tree
+    assert!(written.contains("tree STATE_1 = ("));
+}
+
+#[test]
+fn test_mcmc_states_and_tree_annotations() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t3_n4_mcmc_states.trees");
+    let (trees, leaf_map) = parse_nexus_file(path).unwrap();
+    assert_eq!(trees.len(), 3);
+
+    let states: Vec<_> = trees.iter().map(|tree| tree.metadata().state()).collect();
+    assert_eq!(states, [Some(0), Some(1000), Some(2000)]);
+
+    assert_eq!(trees[0].metadata().log_likelihood(), Some(-1234.5));
+    assert_eq!(trees[0].metadata().posterior(), Some(-1300.25));
+    assert_eq!(trees[0].metadata().rooted(), Some(true));
+    assert_eq!(trees[1].metadata().log_likelihood(), Some(-1230.0));
+    assert_eq!(
+        trees[1].metadata().annotation("joint"),
+        Some(&AnnotationValue::Int(-1290))
+    );
+    assert_eq!(trees[2].metadata().log_likelihood(), Some(-1228.75));
+    assert_eq!(trees[2].metadata().posterior(), None);
+    assert_eq!(trees[2].metadata().rooted(), Some(false));
+
+    // Filter by likelihood
+    let best: Vec<_> = trees
+        .iter()
+        .filter(|tree| tree.metadata().log_likelihood().unwrap() > -1231.0)
+        .filter_map(|tree| tree.name())
+        .collect();
+    assert_eq!(best, ["STATE_1000", "gen.2000"]);
+
+    // Tree-level annotations survive writing and parsing again
+    let out_path = std::env::temp_dir().join("nexwick_test_write_nexus_mcmc_states.trees");
+    let mut writer = NexusWriter::new(File::create(&out_path).unwrap());
+    writer.write_nexus(&trees, &leaf_map).unwrap();
+
+    let (trees_again, _) = parse_nexus_file(&out_path).unwrap();
+    std::fs::remove_file(&out_path).unwrap();
+    for (tree, tree_again) in trees.iter().zip(&trees_again) {
+        assert_eq!(tree_again.metadata(), tree.metadata());
+    }
+}
+
+#[test]
+fn test_combine_chains() {
+    let chain1 = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t3_n4_mcmc_states.trees");
+    let chain2 = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t4_n4_mcmc_chain2.trees");
+    let (trees, label_map) = NexusCombiner::for_files([&chain1, &chain2])
+        .with_burnin(Burnin::Count(1))
+        .combine()
+        .unwrap();
+
+    // Burnin applies per file, trees are tagged with their chain
+    let chains: Vec<_> = trees.iter().map(|tree| tree.chain()).collect();
+    assert_eq!(chains, [0, 0, 1, 1, 1]);
+    assert_eq!(
+        label_map.labels(),
+        &["Anas", "Aythya", "Mergus", "Somateria"]
+    );
+
+    // Trees of the second chain are mapped to the shared label map
+    let (trees2, label_map2) = parse_nexus_file(&chain2).unwrap();
+    for (tree, tree2) in trees[2..].iter().zip(&trees2[1..]) {
+        assert_eq!(
+            tree.tree().to_newick(&NewickStyle::Label, Some(&label_map)),
+            tree2.to_newick(&NewickStyle::Label, Some(&label_map2))
+        );
+    }
+
+    // Lazily with thinning
+    let combined = NexusCombiner::for_files([&chain1, &chain2])
+        .with_skip_first()
+        .with_thinning(Thinning::Every(2))
+        .build()
+        .unwrap();
+    assert_eq!(combined.num_chains(), 2);
+    assert_eq!(combined.num_trees(), 3);
+    let names: Vec<_> = combined
+        .map(|tree| tree.unwrap().into_tree().name().unwrap().clone())
+        .collect();
+    assert_eq!(names, ["STATE_1000", "STATE_1000", "STATE_3000"]);
+
+    // Files with different taxa cannot be combined
+    let other = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t1_n10.trees");
+    let result = NexusCombiner::for_files([&chain1, &other]).combine();
+    assert!(matches!(
+        result.map(|_| ()).unwrap_err().kind(),
+        ParsingErrorType::InvalidTaxaBlock(_)
+    ));
+}
+
+#[test]
+fn test_combine_chains_without_taxa_block() {
+    let dir = std::env::temp_dir();
+    let chain1 = dir.join("nexwick_test_combine_no_taxa_1.trees");
+    let chain2 = dir.join("nexwick_test_combine_no_taxa_2.trees");
+    std::fs::write(
+        &chain1,
+        "#NEXUS\nbegin trees;\n\ttree t1 = ((A,B),C);\nend;\n",
+    )
+    .unwrap();
+    std::fs::write(
+        &chain2,
+        "#NEXUS\nbegin trees;\n\ttree t1 = ((C,B),A);\n\ttree t2 = ((C,D),A);\nend;\n",
+    )
+    .unwrap();
+
+    // Leaf of a later tree that is not a taxon of the first tree
+    let mut combined = NexusCombiner::for_files([&chain1, &chain2])
+        .build()
+        .unwrap();
+    assert!(combined.next().unwrap().is_ok());
+    assert!(combined.next().unwrap().is_ok());
+    let err = combined.next().unwrap().unwrap_err();
+    assert!(matches!(err.kind(), ParsingErrorType::UnresolvedLabel(_)));
+    assert!(combined.next().is_none());
+
+    let result = NexusCombiner::for_files([&chain1, &chain2]).combine();
+    std::fs::remove_file(&chain1).unwrap();
+    std::fs::remove_file(&chain2).unwrap();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_malformed_tree_fails() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t5_n4_malformed.trees");
+    let err = parse_nexus_file(path).err().unwrap();
+    assert_eq!(err.tree_index(), Some(1));
+    assert_eq!(err.tree_name(), Some("STATE_1"));
+}
+
+#[test]
+fn test_error_recovery() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t5_n4_malformed.trees");
+    let parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_error_recovery()
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_total_trees(), 5);
+    assert_eq!(parser.num_trees(), 3);
+
+    let (trees, _, errors) = parser.into_results_with_errors().unwrap();
+    let names: Vec<_> = trees.iter().map(|tree| tree.name().unwrap()).collect();
+    assert_eq!(names, ["STATE_0", "STATE_3", "STATE_4"]);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].tree_index(), Some(1));
+    assert_eq!(errors[0].tree_name(), Some("STATE_1"));
+    assert_eq!(errors[1].tree_index(), Some(2));
+    assert_eq!(errors[1].tree_name(), Some("STATE_2"));
+    assert!(errors[1].to_string().contains("in tree 2 ('STATE_2')"));
+}
+
+#[test]
+fn test_error_recovery_lazy_mode_with_burnin() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t5_n4_malformed.trees");
+    let mut parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_error_recovery()
+        .with_burnin(Burnin::Count(2))
+        .lazy()
+        .build()
+        .unwrap();
+
+    let mut names = Vec::new();
+    while let Some(tree) = parser.next_tree().unwrap() {
+        names.push(tree.name().unwrap().to_string());
+    }
+    assert_eq!(names, ["STATE_3", "STATE_4"]);
+    assert_eq!(parser.errors().len(), 1);
+    assert_eq!(parser.errors()[0].tree_index(), Some(2));
+
+    // Errors are not duplicated when parsing again
+    parser.reset();
+    while parser.next_tree().unwrap().is_some() {}
+    assert_eq!(parser.errors().len(), 1);
+}
+
+#[test]
+fn test_error_recovery_semicolon_in_quoted_label() {
+    let nexus = "#NEXUS
+        begin taxa;
+            dimensions ntax=4;
+            taxlabels 'A;x' B C D;
+        end;
+        begin trees;
+            tree t1 = (('A;x':1,B:1):1,(C:1,D:1):1);
+            tree t2 = (('A;x':1,B:1):1,(C:1,D:1:1) [;];
+            tree t3 = ((C:1,B:1):1,('A;x':1,D:1):1);
+        end;";
+    let parser = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .with_error_recovery()
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_total_trees(), 3);
+
+    let (trees, _, errors) = parser.into_results_with_errors().unwrap();
+    let names: Vec<_> = trees.iter().map(|tree| tree.name().unwrap()).collect();
+    assert_eq!(names, ["t1", "t3"]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].tree_name(), Some("t2"));
+}
+
+#[test]
+fn test_leaf_not_in_taxa_block_fails() {
+    let nexus = "#NEXUS
+        begin taxa;
+            dimensions ntax=3;
+            taxlabels A B C;
+        end;
+        begin trees;
+            tree t1 = ((A:1,B:1):1,C:2);
+            tree t2 = ((A:1,D:1):1,C:2);
+        end;";
+    let err = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(err.kind(), ParsingErrorType::UnresolvedLabel(_)));
+    assert_eq!(err.tree_index(), Some(1));
+    assert!(err.to_string().contains("Unknown taxon 'D'"));
+}
+
+#[test]
+fn test_no_taxa_block_with_translate() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_no_taxa_translate.trees");
+    let (trees, leaf_map) = parse_nexus_file(path).unwrap();
+
+    assert_eq!(trees.len(), 2);
+    assert_eq!(leaf_map.num_labels(), 4);
+    // Taxa in order of TRANSLATE command
+    assert_eq!(leaf_map.get_label(0), Some("Anas"));
+    assert_eq!(leaf_map.get_label(3), Some("Somateria"));
+    assert_eq!(
+        trees[1].to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "(Anas:0.1,Aythya:0.2,(Mergus:0.3,Somateria:0.4):0.1);"
+    );
+}
+
+#[test]
+fn test_no_taxa_block_without_translate() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_no_taxa.trees");
+    let mut parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .lazy()
+        .build()
+        .unwrap();
+
+    // Taxa derived from first tree, before any tree is retrieved
+    assert_eq!(parser.num_leaves(), 4);
+    assert_eq!(parser.label_storage().get_label(0), Some("Mergus"));
+
+    let tree = parser.next_tree().unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "PAUP_1");
+    assert_eq!(tree.num_leaves(), 4);
+    assert!(parser.next_tree().unwrap().is_some());
+    assert!(parser.next_tree().unwrap().is_none());
+    assert_eq!(parser.into_label_storage().num_labels(), 4);
+}
+
+#[test]
+fn test_no_taxa_block_leaf_missing_from_first_tree() {
+    let nexus = "#NEXUS
+        begin trees;
+            tree t1 = ((A,B),C);
+            tree t2 = ((A,D),C);
+            tree t3 = ((C,B),A);
+        end;";
+    let err = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(err.kind(), ParsingErrorType::UnresolvedLabel(_)));
+    assert_eq!(err.tree_index(), Some(1));
+
+    // Lenient mode skips the tree, without adding its leaf to the taxa
+    let parser = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .with_error_recovery()
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_leaves(), 3);
+    assert_eq!(parser.errors().len(), 1);
+    let (trees, leaf_map) = parser.into_results().unwrap();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(leaf_map.labels(), &["A", "B", "C"]);
+}
+
+#[test]
+fn test_alignments() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_alignment.trees");
+    let parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_alignment()
+        .build()
+        .unwrap();
+    let alignments = parser.alignments().to_vec();
+    let (trees, leaf_map) = parser.into_results().unwrap();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(alignments.len(), 2);
+
+    // DNA, interleaved, ordered like taxa of TAXA block
+    let dna = &alignments[0];
+    assert_eq!(dna.data_type(), DataType::Dna);
+    assert_eq!(dna.num_taxa(), 4);
+    assert_eq!(dna.num_chars(), 12);
+    assert_eq!(dna.taxa(), leaf_map.labels().as_slice());
+    let anas = leaf_map.get_index("Anas").unwrap();
+    assert_eq!(dna.sequence(anas), Some(&b"ACGTACGAAC?T"[..]));
+    // Match characters resolved from first row
+    assert_eq!(dna.sequence_by_label("Aythya"), Some(&b"ACGTACGTAC-T"[..]));
+    assert!(dna.is_gap(dna.character(3, 3).unwrap()));
+
+    // Standard data after TREES block, with polymorphic state stored as missing
+    let morphology = &alignments[1];
+    assert_eq!(morphology.data_type(), DataType::Standard);
+    assert_eq!(morphology.num_chars(), 5);
+    assert_eq!(morphology.sequence_by_label("Anas"), Some(&b"01201"[..]));
+    assert_eq!(morphology.sequence_by_label("Aythya"), Some(&b"01?01"[..]));
+}
+
+#[test]
+fn test_alignment_ordered_by_taxa_of_first_tree() {
+    let nexus = "#NEXUS
+        begin trees;
+            tree t1 = ((Anas:1,Aythya:1):1,Mergus:2);
+        end;
+        begin data;
+            dimensions ntax=3 nchar=4;
+            format datatype=dna;
+            matrix
+                Mergus ACGT
+                Aythya AAAA
+                Anas   CCCC
+            ;
+        end;";
+    let parser = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .with_alignment()
+        .build()
+        .unwrap();
+    let alignment = parser.alignments()[0].clone();
+    let (_, leaf_map) = parser.into_results().unwrap();
+    assert_eq!(alignment.taxa(), leaf_map.labels().as_slice());
+    assert_eq!(alignment.taxa()[0], "Anas");
+    assert_eq!(alignment.sequence(0), Some(&b"CCCC"[..]));
+
+    // Rows are checked against the taxa of the first tree
+    let nexus = nexus.replace("Aythya AAAA", "Netta  AAAA");
+    let result = NexusParserBuilder::for_reader(std::io::Cursor::new(nexus))
+        .with_alignment()
+        .build();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_alignments_skipped_by_default() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_alignment.trees");
+    let parser = NexusParserBuilder::for_file(path).unwrap().build().unwrap();
+    assert!(parser.alignments().is_empty());
+    assert_eq!(parser.num_trees(), 2);
+}
+
+#[test]
+fn test_distances_without_trees_block() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_n5_distances.dist");
+    let parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_distances()
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_trees(), 0);
+    assert_eq!(parser.num_leaves(), 5);
+
+    let matrix = &parser.distance_matrices()[0];
+    assert_eq!(matrix.num_taxa(), 5);
+    assert_eq!(matrix.get(1, 0), Some(0.12));
+    assert_eq!(matrix.get(0, 1), Some(0.12));
+    assert_eq!(matrix.get_by_label("Rynchops", "Sterna"), Some(0.19));
+    assert!(
+        matrix
+            .get_by_label("Sterna", "Chroicocephalus")
+            .unwrap()
+            .is_nan()
+    );
+    assert!(matrix.is_symmetric());
+    assert!(matrix.has_missing());
+}
+
+#[test]
+fn test_distances_keyed_by_label_map() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_distances.trees");
+    let parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_distances()
+        .build()
+        .unwrap();
+    let matrices = parser.distance_matrices().to_vec();
+    let (trees, leaf_map) = parser.into_results().unwrap();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(matrices.len(), 2);
+
+    // Upper triangle without diagonal and labels
+    let upper = &matrices[0];
+    assert_eq!(upper.taxa(), leaf_map.labels().as_slice());
+    let anas = leaf_map.get_index("Anas").unwrap();
+    let somateria = leaf_map.get_index("Somateria").unwrap();
+    assert_eq!(upper.get(somateria, anas), Some(0.6));
+    assert_eq!(upper.get(somateria, somateria), Some(0.0));
+
+    // Both triangles (asymmetric) after TREES block, for a subset of taxa
+    let both = &matrices[1];
+    assert!(!both.is_symmetric());
+    let mergus = leaf_map.get_index("Mergus").unwrap();
+    assert_eq!(both.get(mergus, anas), Some(1.5));
+    assert_eq!(both.get(anas, mergus), Some(1.0));
+    assert!(both.get(somateria, anas).unwrap().is_nan());
+}
+
+#[test]
+fn test_write_and_parse_distances() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_distances.trees");
+    let parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_distances()
+        .build()
+        .unwrap();
+
+    for (i, matrix) in parser.distance_matrices().iter().enumerate() {
+        let out_path = std::env::temp_dir().join(format!("nexwick_test_write_distances_{i}.dist"));
+        let mut writer = NexusWriter::new(File::create(&out_path).unwrap());
+        writer.write_nexus_distances(matrix).unwrap();
+
+        let parser = NexusParserBuilder::for_file(&out_path)
+            .unwrap()
+            .with_distances()
+            .build()
+            .unwrap();
+        let parsed = &parser.distance_matrices()[0];
+        assert_eq!(parsed.taxa(), matrix.taxa());
+        for a in 0..matrix.num_taxa() {
+            for b in 0..matrix.num_taxa() {
+                let (expected, actual) = (matrix.get(a, b).unwrap(), parsed.get(a, b).unwrap());
+                assert!(expected == actual || (expected.is_nan() && actual.is_nan()));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sets() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t3_n5_sets.trees");
+    let parser = NexusParserBuilder::for_file(path)
+        .unwrap()
+        .with_sets()
+        .build()
+        .unwrap();
+    let sets = parser.sets().clone();
+    let (trees, leaf_map) = parser.into_results().unwrap();
+    assert_eq!(trees.len(), 3);
+
+    let rynchops = leaf_map.get_index("Rynchops").unwrap();
+    assert_eq!(sets.taxset("outgroup"), Some(&[rynchops][..]));
+    assert_eq!(sets.taxset("GULLS"), Some(&[0, 1, 3][..]));
+    assert_eq!(sets.taxset("every_other"), Some(&[0, 2, 4][..]));
+    assert_eq!(sets.taxset("larids"), Some(&[0, 1, 2, 3][..]));
+    assert_eq!(sets.taxset("gulls_and_outgroup"), Some(&[0, 1, 3, 4][..]));
+    assert_eq!(sets.taxsets().count(), 5);
+
+    assert_eq!(sets.treeset("first_two"), Some(&[0, 1][..]));
+    assert_eq!(sets.treeset("last"), Some(&[2][..]));
+    assert_eq!(sets.taxset("coding"), None);
+}
+
+#[test]
+fn test_sets_with_taxa_from_first_tree() {
+    let nexus = "#NEXUS\nbegin trees; tree t1 = ((A:1,1abc:1):1,C:2); end;\n\
+        begin sets; taxset ab = A 1abc; taxset c = 3; end;\n";
+    let parser = NexusParserBuilder::for_reader(nexus.as_bytes())
+        .with_sets()
+        .build()
+        .unwrap();
+    let sets = parser.sets().clone();
+    let (_, leaf_map) = parser.into_results().unwrap();
+
+    let a = leaf_map.get_index("A").unwrap();
+    let abc = leaf_map.get_index("1abc").unwrap();
+    let c = leaf_map.get_index("C").unwrap();
+    assert_eq!(sets.taxset("ab"), Some(&[a, abc][..]));
+    assert_eq!(sets.taxset("c"), Some(&[c][..]));
+}
+
+#[test]
+fn test_sets_with_unknown_taxon_fails() {
+    let nexus = "#NEXUS\nbegin taxa; dimensions ntax=2; taxlabels A B; end;\n\
+        begin sets; taxset outgroup = C; end;\n\
+        begin trees; tree t1 = (A,B); end;\n";
+    let path = std::env::temp_dir().join("nexwick_test_sets_unknown_taxon.trees");
+    std::fs::write(&path, nexus).unwrap();
+
+    let result = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .with_sets()
+        .build();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_multiple_trees_blocks() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t5_mesquite_blocks.nex");
+
+    // By default, only first TREES block with its linked TAXA block
+    let parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(parser.trees_title(), Some("Trees for terns"));
+    assert_eq!(parser.num_leaves(), 3);
+    assert_eq!(parser.num_trees(), 2);
+
+    // All TREES blocks
+    let blocks = parser.into_trees_blocks().unwrap();
+    assert_eq!(blocks.len(), 2);
+    let titles: Vec<_> = blocks.iter().map(|block| block.title()).collect();
+    assert_eq!(titles, [Some("Trees for terns"), Some("Trees for gulls")]);
+
+    let gulls = blocks
+        .iter()
+        .find(|block| block.title() == Some("Trees for gulls"))
+        .unwrap();
+    assert_eq!(gulls.taxa_title(), Some("Gulls"));
+    assert_eq!(gulls.trees().len(), 3);
+    assert_eq!(gulls.label_storage().num_labels(), 4);
+    assert_eq!(gulls.label_storage().get_label(1), Some("Rissa"));
+    assert_eq!(gulls.trees()[1].name().unwrap(), "gull_2");
+
+    let (tern_trees, tern_map) = blocks.into_iter().next().unwrap().into_results();
+    assert_eq!(tern_trees.len(), 2);
+    assert_eq!(tern_map.labels(), &["Sterna", "Thalasseus", "Rynchops"]);
+}
+
+#[test]
+fn test_multiple_trees_blocks_with_burnin() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t5_mesquite_blocks.nex");
+    let parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .with_skip_first()
+        .lazy()
+        .build()
+        .unwrap();
+
+    let blocks = parser.into_trees_blocks().unwrap();
+    let num_trees: Vec<_> = blocks.iter().map(|block| block.trees().len()).collect();
+    assert_eq!(num_trees, [1, 2]);
+}
+
+#[test]
+fn test_spacing_and_comments_in_commands() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t3_n5_spacing.nex");
+    let (trees, leaf_map) = parse_nexus_file(&path).unwrap();
+
+    // Labels of both TAXLABELS commands, in order
+    assert_eq!(
+        leaf_map.labels(),
+        &["Cygnus", "Anser", "Branta", "Anas platyrhynchos", "Aythya"]
+    );
+
+    let names: Vec<_> = trees.iter().map(|tree| tree.name().unwrap()).collect();
+    assert_eq!(names, ["first", "second", "third"]);
+    assert!(trees.iter().all(|tree| tree.num_leaves() == 5));
+
+    // Same when skipping trees in lazy mode
+    let mut parser = NexusParserBuilder::for_file(&path)
+        .unwrap()
+        .lazy()
+        .with_skip_first()
+        .build()
+        .unwrap();
+    assert_eq!(parser.num_trees(), 2);
+    let tree = parser.next_tree().unwrap().unwrap();
+    assert_eq!(tree.name().unwrap(), "second");
+}
+
+#[test]
+fn test_partial_translate() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t2_n4_partial_translate.trees");
+    let (trees, leaf_map) = parse_nexus_file(path).unwrap();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(
+        leaf_map.labels(),
+        &["Anas", "Aythya", "Mergus", "Somateria"]
+    );
+    assert_eq!(
+        trees[0].to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "((Anas,Aythya),(Mergus,Somateria));"
+    );
+    assert_eq!(
+        trees[1].to_newick(&NewickStyle::Label, Some(&leaf_map)),
+        "((Anas,Mergus),(Aythya,Somateria));"
+    );
+}
+
+#[test]
+fn test_invalid_translate_fails() {
+    let taxa = "#NEXUS\nbegin taxa; dimensions ntax=2; taxlabels A B; end;\n";
+    let translates = [
+        // Label not in TAXA block
+        "translate 1 A, 2 C;",
+        // Duplicate key
+        "translate 1 A, 1 B;",
+        // Missing label
+        "translate 1 A, 2;",
+        // Truncated command
+        "translate 1 A, 2",
+    ];
+    for (i, translate) in translates.iter().enumerate() {
+        let nexus = format!("{taxa}begin trees; {translate} tree t1 = (1,2); end;\n");
+        let path = std::env::temp_dir().join(format!("nexwick_test_invalid_translate_{i}.trees"));
+        std::fs::write(&path, nexus).unwrap();
+
+        let result = NexusParserBuilder::for_file(&path).unwrap().build();
+        assert!(result.is_err(), "{translate}");
+    }
+}
+
+#[test]
+fn test_block_reader() {
+    let path = Path::new("tests")
+        .join("fixtures")
+        .join("nexus_t1_n4_mrbayes_block.nex");
+    let blocks: Vec<RawBlock> = NexusBlockReader::from_file(path)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let names: Vec<_> = blocks.iter().map(|block| block.name()).collect();
+    assert_eq!(names, ["taxa", "trees", "mrbayes", "figtree"]);
+    assert_eq!(blocks[0].kind(), NexusBlock::Taxa);
+
+    // Tree command with comment
+    let tree = blocks[1].command("TREE").unwrap();
+    assert_eq!(tree.tokens()[0], NexusToken::Word(String::from("t1")));
+    assert_eq!(tree.tokens()[1], NexusToken::Equals);
+    assert_eq!(tree.tokens()[2], NexusToken::Comment(String::from("&R")));
+
+    // Unknown block with pairs, quoted tokens, and comments
+    let mrbayes = &blocks[2];
+    assert_eq!(
+        mrbayes.kind(),
+        NexusBlock::UnknownBlock(String::from("mrbayes"))
+    );
+    assert_eq!(mrbayes.commands().len(), 5);
+    let outgroup = mrbayes.command("outgroup").unwrap();
+    assert_eq!(
+        outgroup.words().collect::<Vec<_>>(),
+        ["Somateria mollissima"]
+    );
+    let mcmcp = mrbayes.command("mcmcp").unwrap();
+    assert_eq!(mcmcp.value("NGEN"), Some("1000000"));
+    assert_eq!(mcmcp.value("printfreq"), Some("1000"));
+    assert_eq!(mcmcp.pairs().count(), 3);
+    assert!(mrbayes.command("mcmc").unwrap().tokens().is_empty());
+
+    let figtree = &blocks[3];
+    assert_eq!(
+        figtree.commands()[0].value("appearance.backgroundColorAttribute"),
+        Some("Default")
+    );
+}
+
+#[test]
+fn test_block_reader_nested_comments() {
+    let nexus = "#NEXUS\n[outer [inner; end;] comment]\nbegin paup;\n\
+        \tset [nested [comment] ; end;] criterion=parsimony;\nend;\n";
+    let blocks: Vec<RawBlock> = NexusBlockReader::for_str(nexus)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(blocks.len(), 1);
+    let set = blocks[0].command("set").unwrap();
+    assert_eq!(
+        set.tokens()[0],
+        NexusToken::Comment(String::from("nested [comment] ; end;"))
+    );
+    assert_eq!(set.value("criterion"), Some("parsimony"));
+}
+
+#[test]
+fn test_block_reader_unclosed_block_fails() {
+    let mut reader = NexusBlockReader::for_str("#NEXUS\nbegin paup;\n\tset criterion=parsimony;\n");
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
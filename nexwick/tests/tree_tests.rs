@@ -11,8 +11,8 @@ fn test_building_tree() {
     let index_l1 = tree.add_leaf(Some(BranchLength::new(1.0)), 0);
     let index_l2 = tree.add_leaf(Some(BranchLength::new(1.0)), 1);
     let index_l3 = tree.add_leaf(Some(BranchLength::new(0.5)), 2);
-    let index_i1 = tree.add_internal_vertex((index_l1, index_l2), Some(BranchLength::new(1.5)));
-    let index_root = tree.add_root_without_branch((index_l3, index_i1));
+    let index_i1 = tree.add_internal_vertex(vec![index_l1, index_l2], Some(BranchLength::new(1.5)));
+    let index_root = tree.add_root_without_branch(vec![index_l3, index_i1]);
 
     // Counts
     assert_eq!(tree.num_leaves(), 3);
@@ -48,8 +48,8 @@ fn make_test_tree() -> (GenTree<LabelIndex>, LeafLabelMap) {
     let a = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("A"));
     let b = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("B"));
     let c = tree.add_leaf(Some(BranchLength::new(2.0)), labels.get_or_insert("C"));
-    let internal = tree.add_internal_vertex((a, b), Some(BranchLength::new(1.0)));
-    tree.add_root_without_branch((internal, c));
+    let internal = tree.add_internal_vertex(vec![a, b], Some(BranchLength::new(1.0)));
+    tree.add_root_without_branch(vec![internal, c]);
 
     (tree, labels)
 }
@@ -67,8 +67,8 @@ fn test_is_ultrametric_false() {
     tree.add_leaf(Some(BranchLength::new(1.0)), 0); // A - path = 2.0
     tree.add_leaf(Some(BranchLength::new(2.0)), 1); // B - path = 3.0 (different!)
     tree.add_leaf(Some(BranchLength::new(2.0)), 2);
-    tree.add_internal_vertex((0, 1), Some(BranchLength::new(1.0)));
-    tree.add_root_without_branch((3, 2));
+    tree.add_internal_vertex(vec![0, 1], Some(BranchLength::new(1.0)));
+    tree.add_root_without_branch(vec![3, 2]);
 
     assert!(!tree.is_ultrametric());
 }
@@ -126,7 +126,7 @@ fn test_vertices_have_branch_lengths_false() {
     let mut tree: GenTree<LabelIndex> = GenTree::new(2);
     tree.add_leaf(Some(BranchLength::new(1.0)), 0);
     tree.add_leaf(None, 1); // No branch length
-    tree.add_root_without_branch((0, 1));
+    tree.add_root_without_branch(vec![0, 1]);
 
     assert!(!tree.vertices_have_branch_lengths());
 }
@@ -143,13 +143,39 @@ fn test_num_leaves_mismatch() {
     let mut tree: GenTree<LabelIndex> = GenTree::new(5);
     tree.add_leaf(Some(BranchLength::new(1.0)), 0);
     tree.add_leaf(Some(BranchLength::new(1.0)), 1);
-    tree.add_root_without_branch((0, 1));
+    tree.add_root_without_branch(vec![0, 1]);
 
     assert_eq!(tree.num_leaves_init(), 5);
     assert_eq!(tree.num_leaves(), 2);
     assert!(tree.is_valid()); // Should still be valid
 }
 
+#[test]
+fn test_is_valid_polytomy() {
+    // (A:1.0, B:1.0, C:1.0, D:1.0)
+    let mut tree: GenTree<LabelIndex> = GenTree::new(4);
+    let leaves: Vec<_> = (0..4)
+        .map(|i| tree.add_leaf(Some(BranchLength::new(1.0)), i))
+        .collect();
+    tree.add_root_without_branch(leaves);
+
+    assert!(tree.is_valid());
+    assert!(!tree.is_binary());
+    assert!(tree.is_ultrametric());
+    assert_eq!(tree.num_vertices(), 5);
+}
+
+#[test]
+fn test_is_valid_single_child() {
+    let mut tree: GenTree<LabelIndex> = GenTree::new(2);
+    let a = tree.add_leaf(Some(BranchLength::new(1.0)), 0);
+    let b = tree.add_leaf(Some(BranchLength::new(1.0)), 1);
+    let internal = tree.add_internal_vertex(vec![a], Some(BranchLength::new(1.0)));
+    tree.add_root_without_branch(vec![internal, b]);
+
+    assert!(!tree.is_valid());
+}
+
 // ============= Tree Consistency Tests =============
 #[test]
 #[should_panic]
@@ -173,8 +199,8 @@ fn test_post_order_iter_visits_children_before_parents() {
     let a = tree.add_leaf(Some(BranchLength::new(1.0)), 0);
     let b = tree.add_leaf(Some(BranchLength::new(1.0)), 1);
     let c = tree.add_leaf(Some(BranchLength::new(1.5)), 2);
-    let internal = tree.add_internal_vertex((a, b), Some(BranchLength::new(0.5)));
-    let root = tree.add_root_without_branch((internal, c));
+    let internal = tree.add_internal_vertex(vec![a, b], Some(BranchLength::new(0.5)));
+    let root = tree.add_root_without_branch(vec![internal, c]);
 
     let visited: Vec<_> = tree.post_order_iter().map(|v| v.index()).collect();
 
@@ -204,8 +230,8 @@ fn test_pre_order_iter_visits_parents_before_children() {
     let a = tree.add_leaf(Some(BranchLength::new(1.0)), 0);
     let b = tree.add_leaf(Some(BranchLength::new(1.0)), 1);
     let c = tree.add_leaf(Some(BranchLength::new(1.5)), 2);
-    let internal = tree.add_internal_vertex((a, b), Some(BranchLength::new(0.5)));
-    let root = tree.add_root_without_branch((internal, c));
+    let internal = tree.add_internal_vertex(vec![a, b], Some(BranchLength::new(0.5)));
+    let root = tree.add_root_without_branch(vec![internal, c]);
 
     let visited: Vec<_> = tree.pre_order_iter().map(|v| v.index()).collect();
 
@@ -228,6 +254,24 @@ fn test_pre_order_iter_visits_parents_before_children() {
     assert!(internal_pos < b_pos);
 }
 
+#[test]
+fn test_iters_on_polytomy() {
+    // Build tree: ((A,B,C),D);
+    let mut tree: GenTree<LabelIndex> = GenTree::new(4);
+    let a = tree.add_leaf(None, 0);
+    let b = tree.add_leaf(None, 1);
+    let c = tree.add_leaf(None, 2);
+    let internal = tree.add_internal_vertex(vec![a, b, c], None);
+    let d = tree.add_leaf(None, 3);
+    let root = tree.add_root_without_branch(vec![internal, d]);
+
+    let post: Vec<_> = tree.post_order_iter().map(|v| v.index()).collect();
+    assert_eq!(post, vec![a, b, c, internal, d, root]);
+
+    let pre: Vec<_> = tree.pre_order_iter().map(|v| v.index()).collect();
+    assert_eq!(pre, vec![root, internal, a, b, c, d]);
+}
+
 #[test]
 fn test_iter_on_empty_tree() {
     let tree: GenTree<LabelIndex> = GenTree::new(2);
@@ -248,8 +292,8 @@ fn test_to_newick_with_labels() {
     let a = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("A"));
     let b = tree.add_leaf(Some(BranchLength::new(1.0)), labels.get_or_insert("B"));
     let c = tree.add_leaf(Some(BranchLength::new(2.0)), labels.get_or_insert("C"));
-    let internal = tree.add_internal_vertex((a, b), Some(BranchLength::new(0.5)));
-    tree.add_root_without_branch((internal, c));
+    let internal = tree.add_internal_vertex(vec![a, b], Some(BranchLength::new(0.5)));
+    tree.add_root_without_branch(vec![internal, c]);
 
     let newick = tree.to_newick(&NewickStyle::Label, Some(&labels));
     assert_eq!(newick, "((A:1,B:1):0.5,C:2);");
@@ -260,7 +304,7 @@ fn test_to_newick_zero_indexed() {
     let mut tree: GenTree<LabelIndex> = GenTree::new(2);
     let a = tree.add_leaf(Some(BranchLength::new(1.0)), 0);
     let b = tree.add_leaf(Some(BranchLength::new(2.0)), 1);
-    tree.add_root_without_branch((a, b));
+    tree.add_root_without_branch(vec![a, b]);
 
     let newick = tree.to_newick(&NewickStyle::ZeroIndexed, None);
     assert_eq!(newick, "(0:1,1:2);");
@@ -271,7 +315,7 @@ fn test_to_newick_one_indexed() {
     let mut tree: GenTree<LabelIndex> = GenTree::new(2);
     let a = tree.add_leaf(Some(BranchLength::new(1.5)), 0);
     let b = tree.add_leaf(Some(BranchLength::new(2.5)), 1);
-    tree.add_root_without_branch((a, b));
+    tree.add_root_without_branch(vec![a, b]);
 
     let newick = tree.to_newick(&NewickStyle::OneIndexed, None);
     assert_eq!(newick, "(1:1.5,2:2.5);");